@@ -0,0 +1,49 @@
+// Benchmarks of the step function with all engines
+// Run with `cargo bench`, every iteration is a single generation
+//
+// Baseline (release build, time per generation, dense / sparse):
+//   empty 64x64     58 µs / 46 ns
+//   soup 64x64      59 µs / 82 µs
+//   soup 256x256   887 µs / 1.26 ms
+// Bitwise engine on a soup of 1024x1024 (dense / bitwise):
+//   first generations   27 ms / 4.2 ms
+//   settled soup        21 ms / 1.2 ms
+// Half of the time of the bitwise engine goes to keeping ages of cells, computing the next
+// generation alone takes 0.6 ms
+// Soups are stepped on between iterations, so they soon settle into a sparse ash
+// A change that makes any of these noticeably slower is a regression
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use game_of_life::board::Board;
+use game_of_life::engine::Engine;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+// The same seed gives the same soups, so numbers are comparable between runs
+const SEED: u64 = 42;
+
+// Part of cells alive in a soup
+const DENSITY: f64 = 0.3;
+
+// Function creates a board of the given size filled with a random soup
+fn soup(width: i32, height: i32, density: f64, engine: Engine) -> Board {
+    let mut board = Board::new(width, height);
+    board.randomize(density, &mut StdRng::seed_from_u64(SEED));
+    board.set_engine(Some(engine));
+    board
+}
+
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("step");
+    let boards = [("empty 64x64", 64, 0.0), ("soup 64x64", 64, DENSITY), ("soup 256x256", 256, DENSITY), ("soup 1024x1024", 1024, DENSITY)];
+    for (name, size, density) in boards {
+        for engine in [Engine::Dense, Engine::Sparse, Engine::Bitwise] {
+            // The soup is stepped on and on, as recreating it every time would take longer than a step
+            let mut board = soup(size, size, density, engine);
+            group.bench_function(BenchmarkId::new(name, engine), |b| b.iter(|| board.step()));
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, step);
+criterion_main!(benches);
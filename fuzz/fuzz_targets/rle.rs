@@ -0,0 +1,13 @@
+// Any text has to be either read as a pattern within the size of its header or rejected with an error
+#![no_main]
+use game_of_life::pattern_io::rle;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(pattern) = rle::parse(text) {
+            assert!(pattern.cells.len() <= rle::MAX_ALIVE_CELLS);
+            assert!(pattern.cells.iter().all(|(x, y)| (0..pattern.width).contains(x) && (0..pattern.height).contains(y)));
+        }
+    }
+});
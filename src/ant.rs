@@ -0,0 +1,219 @@
+// Langton's Ant: ants walk over white (dead) and black (alive) cells, turning right on white cells
+// and left on black ones, flipping the color of the cell they leave and moving a cell forward
+// See https://conwaylife.com/wiki/Langton%27s_ant
+// Ants are kept in the states of cells, so the board, its history and sessions keep them like any other state
+use crate::automaton::DEAD;
+
+// First state of cells with ants, a cell with an ant is in the state `ANT + 2 * heading + color`
+pub const ANT: u8 = 2;
+// Number of states: white and black cells without an ant or with an ant heading one of 4 ways
+pub const STATES: u8 = ANT + 8;
+
+// Ways ants head in, clockwise from up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Heading {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Heading {
+    // All headings clockwise from up
+    pub const ALL: [Heading; 4] = [Heading::Up, Heading::Right, Heading::Down, Heading::Left];
+
+    // Offset of the cell an ant with the heading moves to
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Heading::Up => (0, -1),
+            Heading::Right => (1, 0),
+            Heading::Down => (0, 1),
+            Heading::Left => (-1, 0),
+        }
+    }
+
+    // Heading of an ant after it turns on a cell of the color: right on white cells and left on black ones
+    pub fn turned(self, color: u8) -> Heading {
+        let turn = match color {
+            DEAD => 1,
+            _ => 3,
+        };
+        Heading::ALL[(self as usize + turn) % 4]
+    }
+
+    // Angle of the heading clockwise from up in radians
+    pub fn angle(self) -> f32 {
+        self as usize as f32 * std::f32::consts::FRAC_PI_2
+    }
+}
+
+// Heading of the ant on the cell in the state (if there is one)
+pub fn ant(state: u8) -> Option<Heading> {
+    match state {
+        ANT..STATES => Some(Heading::ALL[((state - ANT) / 2) as usize]),
+        _ => None,
+    }
+}
+
+// Color of the cell in the state without its ant (`DEAD` for white cells and `ALIVE` for black ones)
+pub fn color(state: u8) -> u8 {
+    match ant(state) {
+        Some(_) => (state - ANT) % 2,
+        None => state,
+    }
+}
+
+// State of a cell of the color with the ant
+pub fn state(color: u8, ant: Option<Heading>) -> u8 {
+    match ant {
+        Some(heading) => ANT + 2 * heading as u8 + color,
+        None => color,
+    }
+}
+
+// Function returns the state a cell gets when it's clicked
+// An ant heading up is put on the cell, the next clicks turn it clockwise and the click after it heads left takes it away
+pub fn clicked(state: u8) -> u8 {
+    match ant(state) {
+        None => self::state(color(state), Some(Heading::Up)),
+        Some(Heading::Left) => color(state),
+        Some(heading) => self::state(color(state), Some(heading.turned(DEAD))),
+    }
+}
+
+// Function returns the state of the cell with the other color and the same ant
+pub fn painted(state: u8) -> u8 {
+    state ^ 1
+}
+
+// Function returns the state of a cell in the next generation
+// `neighbour(dx, dy)` gives the state of the cell at the offset from this one
+// The cell flips its color if its ant leaves it, and gets the ant of a neighbour that heads its way after turning
+// Of ants coming from several sides at once only the first one clockwise from up is kept
+pub fn next_state<F: Fn(i32, i32) -> u8>(state: u8, neighbour: F) -> u8 {
+    let next_color = match ant(state) {
+        Some(_) => color(state) ^ 1,
+        None => color(state),
+    };
+    let coming = Heading::ALL.into_iter().find(|heading| {
+        let (dx, dy) = heading.offset();
+        let from = neighbour(-dx, -dy);
+        ant(from).map(|ant| ant.turned(color(from))) == Some(*heading)
+    });
+    self::state(next_color, coming)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{Automaton, ALIVE};
+    use crate::board::Board;
+
+    // Function creates a board of Langton's Ant with the ants
+    fn board_with(width: i32, height: i32, wrap: bool, ants: &[(i32, i32, Heading)]) -> Board {
+        let mut board = Board::new(width, height);
+        board.set_automaton(Automaton::LangtonsAnt);
+        board.set_wrap(wrap);
+        for (x, y, heading) in ants {
+            board.set_state(*x, *y, state(DEAD, Some(*heading)));
+        }
+        board
+    }
+
+    // Positions and headings of all ants of the board
+    fn ants_of(board: &Board) -> Vec<(i32, i32, Heading)> {
+        (0..board.height())
+            .flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+            .filter_map(|(x, y)| ant(board.state(x, y)).map(|heading| (x, y, heading)))
+            .collect()
+    }
+
+    // Number of black cells of the board
+    fn black_cells(board: &Board) -> usize {
+        board.cells().iter().filter(|state| color(**state) == ALIVE).count()
+    }
+
+    #[test]
+    fn ants_turn_and_flip_cells() {
+        let mut board = board_with(9, 9, false, &[(4, 4, Heading::Up)]);
+        // A white cell turns the ant right and becomes black
+        board.step();
+        assert_eq!(ants_of(&board), vec![(5, 4, Heading::Right)]);
+        assert_eq!(color(board.state(4, 4)), ALIVE);
+        board.step();
+        board.step();
+        board.step();
+        // Back at the start after a square, where the black cell turns the ant left and becomes white again
+        assert_eq!(ants_of(&board), vec![(4, 4, Heading::Up)]);
+        board.step();
+        assert_eq!(ants_of(&board), vec![(3, 4, Heading::Left)]);
+        assert_eq!(board.state(4, 4), DEAD);
+
+        // Clicks put an ant, turn it and take it away without changing the color of the cell
+        let clicks: Vec<u8> = (0..5).scan(ALIVE, |state, _| {
+            *state = clicked(*state);
+            Some(*state)
+        }).collect();
+        assert_eq!(clicks.iter().map(|state| ant(*state)).collect::<Vec<_>>(), vec![Some(Heading::Up), Some(Heading::Right), Some(Heading::Down), Some(Heading::Left), None]);
+        assert!(clicks.iter().all(|state| color(*state) == ALIVE));
+        assert_eq!(color(painted(clicks[1])), DEAD);
+    }
+
+    #[test]
+    fn ants_cross_edges_of_wrapped_boards_only() {
+        let mut wrapped = board_with(5, 5, true, &[(0, 2, Heading::Down)]);
+        wrapped.step();
+        assert_eq!(ants_of(&wrapped), vec![(4, 2, Heading::Left)]);
+
+        // Ants walk off the edges of bounded boards
+        let mut bounded = board_with(5, 5, false, &[(0, 2, Heading::Down)]);
+        bounded.step();
+        assert_eq!(ants_of(&bounded), Vec::new());
+        assert_eq!(black_cells(&bounded), 1);
+    }
+
+    #[test]
+    fn ants_share_cells() {
+        // Ants heading at each other from two cells apart turn the same way and pass each other
+        let mut board = board_with(11, 11, false, &[(3, 5, Heading::Right), (7, 5, Heading::Left)]);
+        board.step();
+        assert_eq!(ants_of(&board), vec![(7, 4, Heading::Up), (3, 6, Heading::Down)]);
+        // An ant stepping onto the cell the other one has just left and turned black turns left instead of right
+        let mut board = board_with(11, 11, false, &[(5, 5, Heading::Up), (5, 6, Heading::Left)]);
+        board.step();
+        assert_eq!(ants_of(&board), vec![(5, 5, Heading::Up), (6, 5, Heading::Right)]);
+        board.step();
+        assert_eq!(ants_of(&board), vec![(4, 5, Heading::Left), (6, 6, Heading::Down)]);
+        // Ants meeting on a cell merge into the one heading first clockwise from up
+        let mut board = board_with(11, 11, false, &[(5, 5, Heading::Up), (5, 7, Heading::Left)]);
+        board.step();
+        board.step();
+        assert_eq!(ants_of(&board), vec![(6, 6, Heading::Right)]);
+    }
+
+    #[test]
+    fn single_ant_builds_the_highway() {
+        // The ant walks chaotically for about 10000 steps and then builds a diagonal highway,
+        // which repeats every 104 steps moving the ant by 2 cells diagonally and adding 12 black cells
+        let mut board = board_with(128, 128, false, &[(64, 64, Heading::Up)]);
+        for _ in 0..11000 {
+            board.step();
+        }
+        // FNV-1a hash of the states of all cells
+        let hash = board.cells().iter().fold(0xcbf29ce484222325_u64, |hash, state| (hash ^ *state as u64).wrapping_mul(0x100000001b3));
+        assert_eq!(hash, 4317084907685483143);
+        // By then the ant is 34 cells left and 14 cells down from where it started and has left 834 black cells
+        assert_eq!(ants_of(&board), vec![(30, 78, Heading::Down)]);
+        assert_eq!(black_cells(&board), 834);
+
+        for period in 1..=3 {
+            for _ in 0..104 {
+                board.step();
+            }
+            let (x, y, _) = ants_of(&board)[0];
+            assert_eq!(((x - 30).abs(), (y - 78).abs()), (2 * period, 2 * period));
+            assert_eq!(black_cells(&board), 834 + 12 * period as usize);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+// Cellular automata the board can follow
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use crate::ant;
+use crate::rule::Rule;
+
+// State of dead cells of every automaton (empty cells of Wireworld)
+pub const DEAD: u8 = 0;
+// State of alive cells of Life (and firing cells of Brian's Brain)
+pub const ALIVE: u8 = 1;
+
+// State of walls in every automaton: cells that never change and never count as neighbours
+pub const WALL: u8 = u8::MAX;
+// State of immortal cells in every automaton: cells that never change and always count as neighbours
+pub const IMMORTAL: u8 = u8::MAX - 1;
+
+// State of Brian's Brain cells that have just fired and can't fire again yet
+pub const REFRACTORY: u8 = 2;
+
+// States of cells of the two colors of Immigration
+pub const RED: u8 = 1;
+pub const BLUE: u8 = 2;
+
+// States of Wireworld cells
+pub const CONDUCTOR: u8 = 1;
+pub const HEAD: u8 = 2;
+pub const TAIL: u8 = 3;
+
+// Rules cells change their states by
+// Automata are saved by their names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Automaton {
+    // Life-like games where cells are dead or alive and change by a B/S rule
+    // Rules of the Generations family add dying states to them
+    #[default]
+    Life,
+    // Electrons running along wires of conductors
+    // See https://conwaylife.com/wiki/WireWorld
+    Wireworld,
+    // Ready cells fire with 2 firing neighbours and rest for a generation after firing
+    // See https://conwaylife.com/wiki/OCA:Brian%27s_Brain
+    BriansBrain,
+    // Life with red and blue cells where newborn cells take the color most of their parents have
+    // See https://conwaylife.com/wiki/Immigration
+    Immigration,
+    // Ants walking over white and black cells, turning right on white cells and left on black ones
+    // and flipping the colors of the cells they leave (see the `ant` module)
+    // See https://conwaylife.com/wiki/Langton%27s_ant
+    LangtonsAnt,
+}
+
+
+impl Automaton {
+    // Number of states cells can be in
+    // The number of states of Life is set by its rule
+    pub fn states(self, rule: Rule) -> u8 {
+        match self {
+            Automaton::Life => rule.states(),
+            Automaton::Wireworld => 4,
+            Automaton::BriansBrain => 3,
+            Automaton::Immigration => 3,
+            Automaton::LangtonsAnt => ant::STATES,
+        }
+    }
+
+    // Does a neighbour in this state count for the next state of a cell
+    // Immortal cells count in every automaton
+    pub fn counts(self, state: u8) -> bool {
+        match self {
+            _ if state == IMMORTAL => true,
+            Automaton::Life | Automaton::BriansBrain | Automaton::LangtonsAnt => state == ALIVE,
+            Automaton::Immigration => state == RED || state == BLUE,
+            Automaton::Wireworld => state == HEAD,
+        }
+    }
+
+    // Function returns the state of a cell in the next generation
+    // `neighbours` is the number of neighbours that count, the rule is used by Life and Immigration
+    // Cells of Immigration are born `RED`, the board gives them the color of their parents
+    // Cells of Langton's Ant don't change by their neighbours, the board moves the ants
+    pub fn next_state(self, rule: Rule, state: u8, neighbours: u32) -> u8 {
+        match self {
+            Automaton::Life => generations_next(rule, rule.states(), state, neighbours),
+            // Brian's Brain is the Generations rule B2/S/C3: Seeds with a state cells rest in after firing
+            Automaton::BriansBrain => generations_next(Rule::SEEDS, 3, state, neighbours),
+            Automaton::Immigration => match state {
+                DEAD => u8::from(rule.born(neighbours)),
+                _ if rule.survives(neighbours) => state,
+                _ => DEAD,
+            },
+            Automaton::Wireworld => match state {
+                HEAD => TAIL,
+                TAIL => CONDUCTOR,
+                CONDUCTOR if neighbours == 1 || neighbours == 2 => HEAD,
+                _ => state,
+            },
+            Automaton::LangtonsAnt => state,
+        }
+    }
+
+    // Function returns the state a cell gets when it's clicked
+    // Clicks go through all states of Wireworld one by one, put and turn ants of Langton's Ant
+    // and revive or kill cells of other automata
+    // A click on a wall or an immortal cell erases it
+    pub fn clicked(self, state: u8) -> u8 {
+        match (self, state) {
+            (_, WALL | IMMORTAL) => DEAD,
+            (Automaton::Wireworld, _) => (state + 1) % (TAIL + 1),
+            (Automaton::LangtonsAnt, _) => ant::clicked(state),
+            (_, ALIVE) => DEAD,
+            _ => ALIVE,
+        }
+    }
+
+    // Function returns the state a cell gets when it's clicked with the right button
+    // It paints and erases blue cells of Immigration and erases cells of other automata
+    pub fn right_clicked(self, state: u8) -> u8 {
+        match (self, state) {
+            (Automaton::Immigration, BLUE) => DEAD,
+            (Automaton::Immigration, _) => BLUE,
+            _ => DEAD,
+        }
+    }
+
+    // Does the automaton have cells of two colors
+    pub fn has_colors(self) -> bool {
+        self == Automaton::Immigration
+    }
+
+    // Can noise flip cells of the automaton between dead and alive
+    // Wires of Wireworld and black cells of Langton's Ant aren't alive, so flipping them would only break the automaton
+    pub fn has_noise(self) -> bool {
+        !matches!(self, Automaton::Wireworld | Automaton::LangtonsAnt)
+    }
+}
+
+// Function checks if a cell in the state is alive (in any state but `DEAD` and `WALL`)
+pub fn is_alive(state: u8) -> bool {
+    state != DEAD && state != WALL
+}
+
+// Function returns the other color of a cell of Immigration
+pub fn other_color(state: u8) -> u8 {
+    match state {
+        RED => BLUE,
+        _ => RED,
+    }
+}
+
+// Function checks if a cell in the state never changes by itself (is a wall or an immortal cell)
+pub fn is_fixed(state: u8) -> bool {
+    state == WALL || state == IMMORTAL
+}
+
+// Function returns the next state of a cell of an automaton of the Generations family
+// (see https://conwaylife.com/wiki/Generations) with the given number of states
+// Alive cells that don't survive go through the dying states 2, 3, ... before they are dead
+// Only alive cells are counted as neighbours, and cells that aren't dead can't be born
+fn generations_next(rule: Rule, states: u8, state: u8, neighbours: u32) -> u8 {
+    match state {
+        DEAD => u8::from(rule.born(neighbours)),
+        ALIVE if rule.survives(neighbours) => ALIVE,
+        _ => (state + 1) % states,
+    }
+}
+
+impl FromStr for Automaton {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Automaton, String> {
+        match text {
+            "life" => Ok(Automaton::Life),
+            "wireworld" => Ok(Automaton::Wireworld),
+            "briansbrain" => Ok(Automaton::BriansBrain),
+            "immigration" => Ok(Automaton::Immigration),
+            "langtonsant" => Ok(Automaton::LangtonsAnt),
+            _ => Err(format!("Unknown automaton {}, expected life, wireworld, briansbrain, immigration or langtonsant", text)),
+        }
+    }
+}
+
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Automaton::Life => write!(f, "life"),
+            Automaton::Wireworld => write!(f, "wireworld"),
+            Automaton::BriansBrain => write!(f, "briansbrain"),
+            Automaton::Immigration => write!(f, "immigration"),
+            Automaton::LangtonsAnt => write!(f, "langtonsant"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    // A diode that lets electrons through from the left to the right only
+    // The wire is cut in column 4 and the cut is bridged by two blocks above and below it
+    const DIODE: [&str; 3] = [
+        "...##.....",
+        "####.####.",
+        "...##.....",
+    ];
+
+    // Function builds a Wireworld board of the diode with an electron at the given cells
+    fn diode_with_electron(head: (i32, i32), tail: (i32, i32)) -> Board {
+        let mut board = Board::new(10, 3);
+        board.set_automaton(Automaton::Wireworld);
+        for (y, line) in DIODE.iter().enumerate() {
+            for (x, cell) in line.chars().enumerate() {
+                if cell == '#' {
+                    board.set_state(x as i32, y as i32, CONDUCTOR);
+                }
+            }
+        }
+        board.set_state(head.0, head.1, HEAD);
+        board.set_state(tail.0, tail.1, TAIL);
+        board
+    }
+
+    #[test]
+    fn electrons_run_along_wires() {
+        let mut board = diode_with_electron((1, 1), (0, 1));
+        board.step();
+        assert_eq!((board.state(2, 1), board.state(1, 1), board.state(0, 1)), (HEAD, TAIL, CONDUCTOR));
+    }
+
+    #[test]
+    fn diode_conducts_one_way() {
+        // Forward: the electron crosses the cut and reaches the end of the wire
+        let mut board = diode_with_electron((1, 1), (0, 1));
+        for _ in 0..7 {
+            board.step();
+        }
+        assert_eq!((board.state(8, 1), board.state(7, 1)), (HEAD, TAIL));
+
+        // Backward: the electron dies at the cut
+        let mut board = diode_with_electron((7, 1), (8, 1));
+        for _ in 0..9 {
+            board.step();
+        }
+        let states = board.cells();
+        assert!(states.iter().all(|state| *state == DEAD || *state == CONDUCTOR));
+    }
+
+    #[test]
+    fn immigrants_take_color_of_most_parents() {
+        let mut board = Board::new(9, 9);
+        board.set_automaton(Automaton::Immigration);
+        board.set_state(3, 4, BLUE);
+        board.set_state(4, 4, RED);
+        board.set_state(5, 4, BLUE);
+        board.step();
+        // The blinker turns, the middle cell survives with its color and both newborn cells are blue
+        assert_eq!((board.state(4, 3), board.state(4, 4), board.state(4, 5)), (BLUE, RED, BLUE));
+        assert_eq!((board.population_of(RED), board.population_of(BLUE)), (1, 2));
+        board.step();
+        assert_eq!((board.state(3, 4), board.state(4, 4), board.state(5, 4)), (BLUE, RED, BLUE));
+        assert_eq!(Automaton::Immigration.right_clicked(RED), BLUE);
+    }
+
+    #[test]
+    fn surrounded_immigrants_are_captured() {
+        // A red block and a blue domino next to it make a 3x2 rectangle,
+        // whose blue corners survive with 2 red neighbours and a blue one
+        for (capture, color) in [(false, BLUE), (true, RED)] {
+            let mut board = Board::new(9, 9);
+            board.set_automaton(Automaton::Immigration);
+            board.set_capture(capture);
+            for (x, y, state) in [(3, 3, RED), (4, 3, RED), (3, 4, RED), (4, 4, RED), (5, 3, BLUE), (5, 4, BLUE)] {
+                board.set_state(x, y, state);
+            }
+            board.step();
+            assert_eq!((board.state(5, 3), board.state(5, 4)), (color, color));
+            // Red corners have only red neighbours and stay red either way
+            assert_eq!((board.state(3, 3), board.state(3, 4)), (RED, RED));
+        }
+        assert_eq!(other_color(RED), BLUE);
+    }
+
+    #[test]
+    fn star_wars_cells_are_dying_for_two_generations() {
+        let mut board = Board::new(8, 8);
+        board.set_rule("345/2/4".parse().unwrap());
+        board.set_state(3, 3, ALIVE);
+        board.set_state(4, 3, ALIVE);
+        board.step();
+        // Neither cell of the domino survives with 1 neighbour, and 4 cells next to both are born
+        assert_eq!((board.state(3, 3), board.state(4, 3)), (2, 2));
+        assert_eq!((board.state(3, 2), board.state(4, 2), board.state(3, 4), board.state(4, 4)), (ALIVE, ALIVE, ALIVE, ALIVE));
+        board.step();
+        // Dying cells aren't born again with 4 alive neighbours and don't count as neighbours,
+        // so the cells beside them are born with 2 alive neighbours
+        assert_eq!((board.state(3, 3), board.state(4, 3)), (3, 3));
+        assert_eq!((board.state(2, 3), board.state(5, 3)), (ALIVE, ALIVE));
+        board.step();
+        assert_eq!((board.state(3, 3), board.state(4, 3)), (DEAD, DEAD));
+    }
+
+    #[test]
+    fn clicks_cycle_states() {
+        assert_eq!(Automaton::Life.clicked(DEAD), ALIVE);
+        assert_eq!(Automaton::Life.clicked(ALIVE), DEAD);
+        assert_eq!(Automaton::Wireworld.clicked(CONDUCTOR), HEAD);
+        assert_eq!(Automaton::Wireworld.clicked(TAIL), DEAD);
+        assert_eq!("wireworld".parse(), Ok(Automaton::Wireworld));
+        // Painting Brian's Brain makes cells fire, even refractory ones
+        assert_eq!(Automaton::BriansBrain.clicked(REFRACTORY), ALIVE);
+    }
+
+    #[test]
+    fn brians_brain_cells_fire_and_rest() {
+        let mut board = Board::new(8, 8);
+        board.set_automaton(Automaton::BriansBrain);
+        board.set_state(3, 3, ALIVE);
+        board.set_state(4, 3, ALIVE);
+        board.step();
+        // Both firing cells rest, and the 4 ready cells next to both of them fire
+        assert_eq!((board.state(3, 3), board.state(4, 3)), (REFRACTORY, REFRACTORY));
+        let firing: Vec<(i32, i32)> = board.alive_cells().into_iter().filter(|(x, y)| board.state(*x, *y) == ALIVE).collect();
+        assert_eq!(firing, vec![(3, 2), (4, 2), (3, 4), (4, 4)]);
+        board.step();
+        assert_eq!((board.state(3, 3), board.state(4, 3)), (DEAD, DEAD));
+        assert_eq!(board.state(3, 2), REFRACTORY);
+    }
+}
@@ -0,0 +1,1352 @@
+use std::collections::HashSet;
+use std::mem;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use crate::ant::{self, Heading};
+use crate::automaton::{self, Automaton, ALIVE, BLUE, DEAD, IMMORTAL, RED};
+use crate::engine::{self, BitRows, Engine};
+use crate::neighbourhood::Neighbourhood;
+use crate::pattern_io;
+use crate::rule::Rule;
+
+// A field of cells living by the rules of the game
+pub struct Board {
+    // Number of cells in a single row
+    width: i32,
+    // Number of cells in a single column
+    height: i32,
+    // Does the board wrap around its edges
+    wrap: bool,
+    // Rule that decides which cells live in the next generation
+    rule: Rule,
+    // Automaton cells change their states by
+    automaton: Automaton,
+    // Surviving cells of automata with colors take the color most of their neighbours have
+    capture: bool,
+    // Cells counted as neighbours and their offsets from the cell
+    neighbourhood: Neighbourhood,
+    offsets: Vec<(i32, i32)>,
+    // Engine chosen by hand (otherwise it is chosen by the size of the board)
+    engine: Option<Engine>,
+    // Rows of the dense engine are split between threads (chosen by the size of the board if not set)
+    parallel: Option<bool>,
+    // Number of times the rules were applied
+    generation: u32,
+    // States of all cells stored row by row (`DEAD` or `ALIVE` in Life)
+    cells: Vec<u8>,
+    // States of the next generation computed by the dense engine
+    // The buffer is swapped with `cells` after every generation, so neither of them is allocated again
+    next: Vec<u8>,
+    // Coordinates of alive cells, kept only for the sparse engine
+    alive: HashSet<(i32, i32)>,
+    // Alive cells packed into words, kept only for the bitwise engine
+    bits: BitRows,
+    // Generation every alive cell came to life in
+    born: Vec<u32>,
+    // Generation every dead cell died in (None for cells that never died or were killed by hand)
+    died: Vec<Option<u32>>,
+    // Numbers of cells that came to life and died in the last generation
+    births: usize,
+    deaths: usize,
+    // Cells were changed since the board was drawn the last time
+    changed: bool,
+    // Chance of the outcome of the rules for every cell to be flipped in a generation (0 turns the noise off)
+    noise: f64,
+    // Generator of the noise, seeded so that noisy runs can be repeated
+    noise_rng: StdRng,
+}
+
+impl Board {
+    // Constructor for an empty board
+    pub fn new(width: i32, height: i32) -> Board {
+        let cells = vec![DEAD; (width * height) as usize];
+        let born = vec![0; cells.len()];
+        let died = vec![None; cells.len()];
+        Board{width, height, wrap: false, rule: Rule::default(), automaton: Automaton::Life, capture: false, neighbourhood: Neighbourhood::Moore, offsets: Neighbourhood::Moore.offsets(), engine: None, parallel: None, generation: 0, cells, next: Vec::new(), alive: HashSet::new(), bits: BitRows::default(), born, died, births: 0, deaths: 0, changed: true, noise: 0.0, noise_rng: StdRng::seed_from_u64(0)}
+    }
+
+    // Number of cells in a single row
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    // Number of cells in a single column
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    // Does the board wrap around its edges
+    pub fn wrap(&self) -> bool {
+        self.wrap
+    }
+
+    // Function makes cells on the opposite edges neighbours (or not)
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    // Rule that decides which cells live in the next generation
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    // Function changes the rule of the game
+    // Cells in states the new rule doesn't have become alive
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.fit_states();
+    }
+
+    // Automaton cells change their states by
+    pub fn automaton(&self) -> Automaton {
+        self.automaton
+    }
+
+    // Function changes the automaton of the board
+    // Cells in states the new automaton doesn't have become alive
+    pub fn set_automaton(&mut self, automaton: Automaton) {
+        self.automaton = automaton;
+        self.fit_states();
+    }
+
+    // Do surviving cells of automata with colors change their colors when more neighbours have the other one
+    pub fn capture(&self) -> bool {
+        self.capture
+    }
+
+    // Function turns captures of surviving cells of automata with colors on or off
+    pub fn set_capture(&mut self, capture: bool) {
+        self.capture = capture;
+    }
+
+    // Function makes cells in states the automaton doesn't have alive (walls and immortal cells stay)
+    // Colors of states depend on the number of them, so the board is drawn again anyway
+    fn fit_states(&mut self) {
+        self.changed = true;
+        let states = self.automaton.states(self.rule);
+        for cell in self.cells.iter_mut() {
+            if *cell >= states && !automaton::is_fixed(*cell) {
+                *cell = ALIVE;
+            }
+        }
+        self.collect_alive();
+    }
+
+    // Cells counted as neighbours
+    pub fn neighbourhood(&self) -> Neighbourhood {
+        self.neighbourhood
+    }
+
+    // Function changes the cells counted as neighbours
+    pub fn set_neighbourhood(&mut self, neighbourhood: Neighbourhood) {
+        self.neighbourhood = neighbourhood;
+        self.offsets = neighbourhood.offsets();
+        self.collect_alive();
+    }
+
+    // Engine that computes the next generation
+    // Only Life with 2 states and the Moore neighbourhood can be computed by the sparse and the bitwise engines
+    pub fn engine(&self) -> Engine {
+        match self.automaton {
+            Automaton::Life if self.rule.states() == 2 && self.neighbourhood == Neighbourhood::Moore => self.engine.unwrap_or_else(|| Engine::for_size(self.width, self.height)),
+            _ => Engine::Dense,
+        }
+    }
+
+    // Function selects the engine (or lets the board choose it by its size)
+    pub fn set_engine(&mut self, engine: Option<Engine>) {
+        self.engine = engine;
+        self.collect_alive();
+    }
+
+    // Are rows of the dense engine split between threads
+    pub fn parallel(&self) -> bool {
+        self.parallel.unwrap_or_else(|| engine::runs_parallel(self.width, self.height))
+    }
+
+    // Function splits rows of the dense engine between threads (or lets the board choose it by its size)
+    // Generations are the same either way, only the time they take changes
+    pub fn set_parallel(&mut self, parallel: Option<bool>) {
+        self.parallel = parallel;
+    }
+
+    // Chance of the outcome of the rules for a cell to be flipped in a generation
+    // Noise is off for automata it doesn't apply to
+    pub fn noise(&self) -> f64 {
+        match self.automaton.has_noise() {
+            true => self.noise,
+            false => 0.0,
+        }
+    }
+
+    // Chance of flipped outcomes set for the board, kept for automata the noise doesn't apply to
+    pub fn noise_probability(&self) -> f64 {
+        self.noise
+    }
+
+    // Function sets the chance of flipped outcomes and starts the noise from the seed
+    // The same seed gives the same noise, so noisy runs from the same cells can be repeated
+    pub fn set_noise(&mut self, noise: f64, seed: u64) {
+        self.noise = noise;
+        self.noise_rng = StdRng::seed_from_u64(seed);
+    }
+
+    // Function checks if the cell is inside of the board
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (0..self.width).contains(&x) && (0..self.height).contains(&y)
+    }
+
+    // Function returns the cell the given number of columns and rows away from the cell
+    // Cells beyond an edge are taken from the opposite edge if the board wraps and from the edge itself if it doesn't
+    pub fn moved_cell(&self, cell: (i32, i32), offset: (i32, i32)) -> (i32, i32) {
+        let (x, y) = (cell.0 + offset.0, cell.1 + offset.1);
+        match self.wrap {
+            true => (x.rem_euclid(self.width), y.rem_euclid(self.height)),
+            false => (x.clamp(0, self.width - 1), y.clamp(0, self.height - 1)),
+        }
+    }
+
+    // Function checks if the cell is alive (in any state but `DEAD` and `WALL`)
+    // Cells outside of the board are always dead
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        automaton::is_alive(self.state(x, y))
+    }
+
+    // Function returns the state of the cell
+    // Cells outside of the board are always dead
+    pub fn state(&self, x: i32, y: i32) -> u8 {
+        match self.contains(x, y) {
+            true => self.cells[self.index(x, y)],
+            false => DEAD,
+        }
+    }
+
+    // Function returns the number of generations the cell has been alive in a row
+    // A cell that has just come to life has age 1, dead cells have age 0
+    pub fn age(&self, x: i32, y: i32) -> u32 {
+        match self.get(x, y) {
+            true => self.generation - self.born[self.index(x, y)] + 1,
+            false => 0,
+        }
+    }
+
+    // Function checks if the cell came to life in the last generation or was revived by hand after it
+    pub fn is_newborn(&self, x: i32, y: i32) -> bool {
+        self.age(x, y) == 1
+    }
+
+    // Function returns the number of generations since the cell died
+    // Alive cells, cells killed by hand and cells that never died return 0
+    pub fn since_death(&self, x: i32, y: i32) -> u32 {
+        match self.contains(x, y) {
+            true => match self.died[self.index(x, y)] {
+                Some(died) => self.generation - died + 1,
+                None => 0,
+            },
+            false => 0,
+        }
+    }
+
+    // Function makes the cell alive or dead
+    // A revived cell is as old as a newborn one
+    // Cells outside of the board are ignored
+    pub fn set(&mut self, x: i32, y: i32, alive: bool) {
+        self.set_state(x, y, u8::from(alive));
+    }
+
+    // Function changes the state of the cell
+    // A cell in a new state is as old as a newborn one
+    // Cells outside of the board are ignored
+    pub fn set_state(&mut self, x: i32, y: i32, state: u8) {
+        if self.contains(x, y) {
+            let index = self.index(x, y);
+            if self.cells[index] != state {
+                self.born[index] = self.generation;
+                self.died[index] = None;
+                self.changed = true;
+                self.track(x, y, automaton::is_alive(state));
+            }
+            self.cells[index] = state;
+        }
+    }
+
+    // Were cells changed (or did they get older) since the board was drawn the last time
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    // Function marks the board as drawn, so it's not drawn again until its cells change
+    pub fn mark_drawn(&mut self) {
+        self.changed = false;
+    }
+
+    // Number of cells that came to life in the last generation
+    // Cells changed between generations are not counted, and there are none after the cells are replaced
+    pub fn births(&self) -> usize {
+        self.births
+    }
+
+    // Number of cells that died in the last generation
+    pub fn deaths(&self) -> usize {
+        self.deaths
+    }
+
+    // States of all cells stored row by row
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    // Function replaces states of all cells with the ones taken from `cells`
+    // The board keeps its size, so `cells` must be taken from a board of the same size
+    pub fn restore(&mut self, cells: &[u8]) {
+        assert_eq!(cells.len(), self.cells.len(), "Cells of a board of another size");
+        self.cells.copy_from_slice(cells);
+        // Ages are not stored with cells, so all cells become newborn
+        self.start_over();
+    }
+
+    // Number of alive cells on the board
+    pub fn population(&self) -> usize {
+        match self.engine() {
+            Engine::Dense => self.cells.iter().filter(|state| automaton::is_alive(**state)).count(),
+            Engine::Sparse => self.alive.len(),
+            Engine::Bitwise => self.bits.population(),
+        }
+    }
+
+    // Number of cells in the state
+    pub fn population_of(&self, state: u8) -> usize {
+        self.cells.iter().filter(|cell| **cell == state).count()
+    }
+
+    // Function revives cells of the pattern with its upper left corner at the given cell
+    // Cells of the pattern outside of the board are ignored
+    pub fn stamp(&mut self, x: i32, y: i32, cells: &[(i32, i32)]) {
+        for (dx, dy) in cells {
+            self.set(x + dx, y + dy, true);
+        }
+    }
+
+    // Function puts cells of the pattern in their states like `stamp` does (like colors of Immigration)
+    // Cells without states and in states the automaton doesn't have are alive
+    pub fn stamp_states(&mut self, x: i32, y: i32, cells: &[(i32, i32)], states: &[u8]) {
+        let count = self.automaton.states(self.rule);
+        for (i, (dx, dy)) in cells.iter().enumerate() {
+            let state = states.get(i).copied().filter(|state| *state < count).unwrap_or(ALIVE);
+            self.set_state(x + dx, y + dy, state);
+        }
+    }
+
+    // Coordinates of all alive cells of the board row by row
+    pub fn alive_cells(&self) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y) {
+                    cells.push((x, y));
+                }
+            }
+        }
+        cells
+    }
+
+    // Coordinates and states of all alive cells row by row
+    // Immortal cells are given as `ALIVE`
+    pub fn alive_states(&self) -> Vec<(i32, i32, u8)> {
+        self.alive_cells().into_iter()
+            .map(|(x, y)| match self.state(x, y) {
+                IMMORTAL => (x, y, ALIVE),
+                state => (x, y, state),
+            })
+            .collect()
+    }
+
+    // Function returns the upper left and the lower right cells of the smallest rectangle containing all alive cells
+    // It's the rectangle RLE files of the board are cut to
+    // Returns `None` if there are no alive cells
+    pub fn bounding_box(&self) -> Option<((i32, i32), (i32, i32))> {
+        let cells = self.alive_cells();
+        if cells.is_empty() {
+            return None;
+        }
+        let (x, y, width, height) = pattern_io::bounding_box(&cells);
+        Some(((x, y), (x + width - 1, y + height - 1)))
+    }
+
+    // Function creates a board of another size with the same picture of cells
+    // Every new cell takes the state of the old cell under its center
+    pub fn resized(&self, width: i32, height: i32) -> Board {
+        let mut board = Board::new(width, height);
+        board.wrap = self.wrap;
+        board.rule = self.rule;
+        board.automaton = self.automaton;
+        board.neighbourhood = self.neighbourhood;
+        board.offsets = self.offsets.clone();
+        board.set_engine(self.engine);
+        board.parallel = self.parallel;
+        board.noise = self.noise;
+        board.noise_rng = self.noise_rng.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let old_x = (2 * x + 1) * self.width / (2 * width);
+                let old_y = (2 * y + 1) * self.height / (2 * height);
+                board.set_state(x, y, self.state(old_x, old_y));
+            }
+        }
+        board
+    }
+
+    // Function kills all cells on the board, walls and immortal cells are kept
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut().filter(|cell| !automaton::is_fixed(**cell)) {
+            *cell = DEAD;
+        }
+        self.start_over();
+    }
+
+    // Function kills all cells including immortal ones and removes all walls
+    pub fn wipe(&mut self) {
+        for cell in self.cells.iter_mut() {
+            *cell = DEAD;
+        }
+        self.start_over();
+    }
+
+    // Coordinates of all cells in the state row by row
+    pub fn cells_in(&self, state: u8) -> Vec<(i32, i32)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|(x, y)| self.state(*x, *y) == state)
+            .collect()
+    }
+
+    // Function fills the board with random cells
+    // Each cell is alive with the probability of `density`
+    // Alive cells of automata with two colors get one of them with equal chances
+    // Walls and immortal cells are kept
+    pub fn randomize<R: Rng>(&mut self, density: f64, rng: &mut R) {
+        let colors = self.automaton.has_colors();
+        for cell in self.cells.iter_mut().filter(|cell| !automaton::is_fixed(**cell)) {
+            *cell = match (rng.gen_bool(density), colors) {
+                (true, true) => match rng.gen_bool(0.5) {
+                    true => RED,
+                    false => BLUE,
+                },
+                (alive, _) => u8::from(alive),
+            };
+        }
+        self.start_over();
+    }
+
+    // Function applies the rules of the game to all cells once
+    // Returns the numbers of cells born and died in the generation (see `births` and `deaths`)
+    pub fn step(&mut self) -> (usize, usize) {
+        // The same engine as `compute_next` is used, but the dense one writes the whole next generation
+        // to the second buffer of cells instead of listing the changes, so a step allocates nothing
+        let changed = match self.engine() {
+            // Noise can flip any cell, so the whole next generation is written and flipped cells are changed there
+            _ if self.noise() > 0.0 => {
+                self.fill_next();
+                self.add_noise();
+                None
+            },
+            _ if self.automaton == Automaton::LangtonsAnt => Some(self.ant_changes()),
+            Engine::Sparse if !self.rule.born(0) => Some(self.sparse_changes()),
+            Engine::Bitwise => Some(self.bitwise_changes()),
+            _ => {
+                self.fill_next();
+                None
+            },
+        };
+
+        self.generation += 1;
+        self.births = 0;
+        self.deaths = 0;
+        // Cells get older and trails fade with every generation even if no cell changes
+        self.changed = true;
+        match changed {
+            Some(changed) => for (x, y, state) in changed {
+                let index = self.index(x, y);
+                self.count_change(index, state);
+                self.cells[index] = state;
+            },
+            None => {
+                for index in 0..self.cells.len() {
+                    if self.next[index] != self.cells[index] {
+                        self.count_change(index, self.next[index]);
+                    }
+                }
+                mem::swap(&mut self.cells, &mut self.next);
+            },
+        }
+        (self.births, self.deaths)
+    }
+
+    // Function updates the age, the births and deaths and the engine's data of the cell changing to the state
+    // The state of the cell itself is left as it is
+    fn count_change(&mut self, index: usize, state: u8) {
+        let was_alive = automaton::is_alive(self.cells[index]);
+        let alive = automaton::is_alive(state);
+        // Newborn cells start counting their age and dead ones generations since death
+        // Cells that only change from one alive state to another keep their age
+        match (was_alive, alive) {
+            (false, true) => {
+                self.born[index] = self.generation;
+                self.died[index] = None;
+                self.births += 1;
+            },
+            (true, false) => {
+                self.died[index] = Some(self.generation);
+                self.deaths += 1;
+            },
+            _ => (),
+        }
+        let width = self.width as usize;
+        self.track((index % width) as i32, (index / width) as i32, alive);
+    }
+
+    // Function returns cells that change in the next generation with their new states
+    // The board itself is not changed
+    pub fn compute_next(&self) -> Vec<(i32, i32, u8)> {
+        // Cells are born without neighbours in B0 rules, so all of them have to be checked
+        match self.engine() {
+            _ if self.automaton == Automaton::LangtonsAnt => self.ant_changes(),
+            Engine::Sparse if !self.rule.born(0) => self.sparse_changes(),
+            Engine::Bitwise => self.bitwise_changes(),
+            _ => self.dense_changes(),
+        }
+    }
+
+    // Function returns cells that change in the next generation, checking every cell
+    fn dense_changes(&self) -> Vec<(i32, i32, u8)> {
+        let mut changed = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let next = self.next_state(x, y);
+                if next != self.state(x, y) {
+                    changed.push((x, y, next));
+                }
+            }
+        }
+        changed
+    }
+
+    // Function writes the next states of all cells to the buffer of the next generation
+    // Rows are split between threads on large boards
+    fn fill_next(&mut self) {
+        let mut next = mem::take(&mut self.next);
+        // The buffer is only allocated by the first generation
+        next.resize(self.cells.len(), DEAD);
+        let width = self.width as usize;
+        match self.parallel() {
+            true => next.par_chunks_mut(width).enumerate().for_each(|(y, row)| self.fill_row(y as i32, row)),
+            false => next.chunks_mut(width).enumerate().for_each(|(y, row)| self.fill_row(y as i32, row)),
+        }
+        self.next = next;
+    }
+
+    // Function flips the next states of random cells of the buffer of the next generation
+    // with the chance of the noise: cells that would be alive die and dead ones are born
+    // Dying and refractory cells go on as they would, and newborn cells of Immigration get a random color
+    // Walls and immortal cells are never flipped
+    fn add_noise(&mut self) {
+        // Gaps between flipped cells are drawn instead of a number for every cell, so a low noise costs little
+        // The gaps follow the geometric distribution, as the number of cells until the next flip does
+        let mut index = 0;
+        loop {
+            let chance: f64 = 1.0 - self.noise_rng.gen::<f64>();
+            let gap = chance.ln() / (1.0 - self.noise).ln();
+            index += match gap < self.cells.len() as f64 {
+                true => gap as usize,
+                false => break,
+            };
+            if index >= self.cells.len() {
+                break;
+            }
+            let state = self.next[index];
+            if !automaton::is_fixed(self.cells[index]) {
+                self.next[index] = match state {
+                    DEAD if self.automaton.has_colors() => match self.noise_rng.gen::<bool>() {
+                        true => RED,
+                        false => BLUE,
+                    },
+                    DEAD => ALIVE,
+                    _ if self.automaton.counts(state) => DEAD,
+                    _ => state,
+                };
+            }
+            index += 1;
+        }
+    }
+
+    // Function writes the next states of the cells of the row
+    fn fill_row(&self, y: i32, row: &mut [u8]) {
+        for (x, state) in row.iter_mut().enumerate() {
+            *state = self.next_state(x as i32, y);
+        }
+    }
+
+    // State of the cell in the next generation
+    fn next_state(&self, x: i32, y: i32) -> u8 {
+        let state = self.state(x, y);
+        // Walls and immortal cells never change
+        if automaton::is_fixed(state) {
+            return state;
+        }
+        if self.automaton == Automaton::LangtonsAnt {
+            return ant::next_state(state, |dx, dy| self.offset_state(x, y, dx, dy));
+        }
+        match self.automaton.next_state(self.rule, state, self.alive_neighbours(x, y)) {
+            // Newborn cells of automata with colors take the color most of their parents have
+            next if state == DEAD && next != DEAD && self.automaton.has_colors() => self.majority_state(x, y),
+            // Surviving cells are captured by the other color if more of their neighbours have it
+            next if next == state && next != DEAD && self.capture && self.automaton.has_colors() => self.captured_state(x, y, state),
+            next => next,
+        }
+    }
+
+    // Function returns cells that change in the next generation, checking only
+    // alive cells and their neighbours
+    // Walls aren't alive and immortal cells are, so they only have to be kept from changing
+    fn sparse_changes(&self) -> Vec<(i32, i32, u8)> {
+        let next = engine::sparse_step(&self.alive, self.width, self.height, self.wrap, self.rule);
+        self.alive.symmetric_difference(&next)
+            .filter(|(x, y)| !automaton::is_fixed(self.state(*x, *y)))
+            .map(|(x, y)| (*x, *y, u8::from(next.contains(&(*x, *y)))))
+            .collect()
+    }
+
+    // Function returns cells that change in the next generation of Langton's Ant,
+    // checking only cells with ants and the cells next to them
+    fn ant_changes(&self) -> Vec<(i32, i32, u8)> {
+        let mut cells = Vec::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if ant::ant(self.state(x, y)).is_some() {
+                    cells.push((x, y));
+                    cells.extend(Heading::ALL.iter().map(|heading| self.moved_neighbour(x, y, heading.offset())).filter(|(x, y)| self.contains(*x, *y)));
+                }
+            }
+        }
+        cells.sort_unstable();
+        cells.dedup();
+        cells.into_iter()
+            .map(|(x, y)| (x, y, self.next_state(x, y)))
+            .filter(|(x, y, next)| *next != self.state(*x, *y))
+            .collect()
+    }
+
+    // Function returns cells that change in the next generation, computing 64 cells at once
+    // Walls are dead and immortal cells are alive in the packed rows, so they only have to be kept from changing
+    fn bitwise_changes(&self) -> Vec<(i32, i32, u8)> {
+        let next = self.bits.step(self.wrap, self.rule);
+        self.bits.differences(&next).into_iter()
+            .filter(|(x, y)| !automaton::is_fixed(self.state(*x, *y)))
+            .map(|(x, y)| (x, y, u8::from(next.get(x, y))))
+            .collect()
+    }
+
+    // Function makes all alive cells newborn and forgets when cells died
+    // Births and deaths of the last generation don't belong to the new cells
+    fn start_over(&mut self) {
+        self.births = 0;
+        self.deaths = 0;
+        self.changed = true;
+        for born in self.born.iter_mut() {
+            *born = self.generation;
+        }
+        for died in self.died.iter_mut() {
+            *died = None;
+        }
+        self.collect_alive();
+    }
+
+    // Function fills the set of alive cells used by the sparse engine or the packed rows of the bitwise one
+    fn collect_alive(&mut self) {
+        self.alive.clear();
+        self.bits = BitRows::default();
+        match self.engine() {
+            Engine::Sparse => {
+                let alive = self.alive_cells();
+                self.alive.extend(alive);
+            },
+            Engine::Bitwise => {
+                self.bits = BitRows::new(self.width, self.height);
+                for (x, y) in self.alive_cells() {
+                    self.bits.set(x, y, true);
+                }
+            },
+            Engine::Dense => (),
+        }
+    }
+
+    // Function keeps the cell in the set of alive cells or the packed rows of the engine
+    fn track(&mut self, x: i32, y: i32, alive: bool) {
+        match (self.engine(), alive) {
+            (Engine::Sparse, true) => {
+                self.alive.insert((x, y));
+            },
+            (Engine::Sparse, false) => {
+                self.alive.remove(&(x, y));
+            },
+            (Engine::Bitwise, _) => self.bits.set(x, y, alive),
+            (Engine::Dense, _) => (),
+        }
+    }
+
+    // Function counts neighbours of the cell that count for the automaton
+    // (alive cells in Life and electron heads in Wireworld) among the cells of its neighbourhood
+    // Neighbours across the edges are counted only if the board wraps
+    pub fn alive_neighbours(&self, x: i32, y: i32) -> u32 {
+        self.neighbour_states(x, y).filter(|state| self.automaton.counts(*state)).count() as u32
+    }
+
+    // Function returns the state most of the neighbours of the cell that count are in
+    // Of states with equal numbers of neighbours the lowest one is chosen
+    fn majority_state(&self, x: i32, y: i32) -> u8 {
+        let mut counts = vec![0; self.automaton.states(self.rule) as usize];
+        // Immortal cells have no color, so they count as neighbours but not as parents of a color
+        for state in self.neighbour_states(x, y).filter(|state| self.automaton.counts(*state)) {
+            if let Some(count) = counts.get_mut(state as usize) {
+                *count += 1;
+            }
+        }
+        // The first of the largest counts is the last one in reverse order
+        // Cells born without neighbours (in B0 rules) are `ALIVE`
+        counts.iter().enumerate().skip(1).rev().max_by_key(|(_, count)| **count).map_or(ALIVE, |(state, _)| state as u8)
+    }
+
+    // Function returns the color a surviving cell of an automaton with colors has after captures:
+    // the other color if more neighbours have it than the color of the cell
+    fn captured_state(&self, x: i32, y: i32, state: u8) -> u8 {
+        let enemy = automaton::other_color(state);
+        let (friends, enemies) = self.neighbour_states(x, y)
+            .fold((0, 0), |(friends, enemies), neighbour| (friends + u32::from(neighbour == state), enemies + u32::from(neighbour == enemy)));
+        match enemies > friends {
+            true => enemy,
+            false => state,
+        }
+    }
+
+    // Function returns states of all neighbours of the cell
+    fn neighbour_states(&self, x: i32, y: i32) -> impl Iterator<Item = u8> + '_ {
+        self.offsets.iter().map(move |(dx, dy)| self.offset_state(x, y, *dx, *dy))
+    }
+
+    // Function returns the state of the neighbour of the cell at the offset
+    fn offset_state(&self, x: i32, y: i32, dx: i32, dy: i32) -> u8 {
+        let (n_x, n_y) = self.moved_neighbour(x, y, (dx, dy));
+        // Neighbours outside of the board are always dead
+        self.state(n_x, n_y)
+    }
+
+    // Position of the neighbour of the cell at the offset, which may be outside of a board that doesn't wrap
+    fn moved_neighbour(&self, x: i32, y: i32, (dx, dy): (i32, i32)) -> (i32, i32) {
+        match self.wrap {
+            // Neighbours outside of the board are taken from the opposite edge
+            true => ((x + dx).rem_euclid(self.width), (y + dy).rem_euclid(self.height)),
+            false => (x + dx, y + dy),
+        }
+    }
+
+    // Position of the cell in the vector of cells
+    fn index(&self, x: i32, y: i32) -> usize {
+        (y * self.width + x) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{CONDUCTOR, IMMORTAL, REFRACTORY, WALL};
+    use crate::pattern_io::rle;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use proptest::prelude::{prop, prop_assert, prop_assert_eq, proptest, ProptestConfig, Strategy};
+
+    // Creates a board with given alive cells
+    fn board_with(width: i32, height: i32, cells: &[(i32, i32)]) -> Board {
+        let mut board = Board::new(width, height);
+        for (x, y) in cells {
+            board.set(*x, *y, true);
+        }
+        board
+    }
+
+    // Moves all cells by the same offset
+    fn shifted(cells: &[(i32, i32)], dx: i32, dy: i32) -> Vec<(i32, i32)> {
+        let mut cells: Vec<(i32, i32)> = cells.iter().map(|(x, y)| (x + dx, y + dy)).collect();
+        cells.sort_by_key(|(x, y)| (*y, *x));
+        cells
+    }
+
+    const GLIDER: [(i32, i32); 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+    #[test]
+    fn block_stays_still() {
+        let block = [(4, 4), (5, 4), (4, 5), (5, 5)];
+        let mut board = board_with(10, 10, &block);
+        board.step();
+        assert_eq!(board.alive_cells(), block.to_vec());
+    }
+
+    #[test]
+    fn blinker_oscillates() {
+        let vertical = [(5, 4), (5, 5), (5, 6)];
+        let horizontal = [(4, 5), (5, 5), (6, 5)];
+        let mut board = board_with(10, 10, &vertical);
+
+        board.step();
+        assert_eq!(board.alive_cells(), horizontal.to_vec());
+        board.step();
+        assert_eq!(board.alive_cells(), vertical.to_vec());
+    }
+
+    #[test]
+    fn next_generation_is_computed_without_changes() {
+        let mut board = board_with(10, 10, &[(5, 4), (5, 5), (5, 6)]);
+        let mut changes = board.compute_next();
+        changes.sort();
+        assert_eq!(changes, vec![(4, 5, 1), (5, 4, 0), (5, 6, 0), (6, 5, 1)]);
+        assert_eq!(board.alive_cells(), vec![(5, 4), (5, 5), (5, 6)]);
+
+        board.step();
+        assert_eq!(board.alive_cells(), vec![(4, 5), (5, 5), (6, 5)]);
+    }
+
+    #[test]
+    fn neighbours_across_edges_count_only_with_wrap() {
+        let mut board = board_with(5, 4, &[(0, 0), (1, 0), (4, 3)]);
+        assert_eq!(board.alive_neighbours(0, 0), 1);
+        assert_eq!(board.alive_neighbours(1, 1), 2);
+        board.set_wrap(true);
+        assert_eq!(board.alive_neighbours(0, 0), 2);
+        assert_eq!(board.alive_neighbours(4, 0), 2);
+    }
+
+    #[test]
+    fn walls_are_never_born_and_survive_clearing() {
+        for engine in [Engine::Dense, Engine::Sparse] {
+            let mut board = board_with(10, 10, &[(4, 4), (5, 4), (6, 4)]);
+            board.set_engine(Some(engine));
+            board.set_state(5, 3, WALL);
+            board.set_state(4, 5, WALL);
+            // The wall in the place of a newborn cell of the blinker stays a wall and other walls don't count
+            board.step();
+            assert_eq!(board.alive_cells(), vec![(5, 4), (5, 5)]);
+            assert_eq!(board.cells_in(WALL), vec![(5, 3), (4, 5)]);
+            board.clear();
+            assert_eq!((board.population(), board.cells_in(WALL).len()), (0, 2));
+            board.wipe();
+            assert!(board.cells_in(WALL).is_empty());
+        }
+    }
+
+    #[test]
+    fn immortal_cells_never_die_and_survive_clearing() {
+        for engine in [Engine::Dense, Engine::Sparse] {
+            let mut board = board_with(10, 10, &[(4, 4), (6, 4)]);
+            board.set_engine(Some(engine));
+            board.set_state(5, 4, IMMORTAL);
+            board.set_state(1, 1, IMMORTAL);
+            // The immortal cell in the middle of the blinker counts as its alive cell,
+            // and the lonely one doesn't die
+            board.step();
+            assert_eq!(board.alive_cells(), vec![(1, 1), (5, 3), (5, 4), (5, 5)]);
+            board.step();
+            assert_eq!(board.alive_cells(), vec![(1, 1), (4, 4), (5, 4), (6, 4)]);
+            board.clear();
+            assert_eq!(board.alive_cells(), vec![(1, 1), (5, 4)]);
+            board.wipe();
+            assert_eq!(board.population(), 0);
+        }
+    }
+
+    #[test]
+    fn other_neighbourhoods_count_across_edges_too() {
+        let mut board = board_with(6, 6, &[(0, 0), (1, 0), (4, 4), (5, 5)]);
+        board.set_neighbourhood(Neighbourhood::VonNeumann);
+        assert_eq!(board.alive_neighbours(1, 1), 1);
+        assert_eq!(board.alive_neighbours(0, 1), 1);
+        board.set_neighbourhood(Neighbourhood::Moore2);
+        assert_eq!(board.alive_neighbours(0, 0), 1);
+        assert_eq!(board.alive_neighbours(2, 2), 3);
+        // Cells 2 columns and rows away are taken from the opposite edges
+        board.set_wrap(true);
+        assert_eq!(board.alive_neighbours(0, 0), 3);
+        assert_eq!(board.resized(12, 12).neighbourhood(), Neighbourhood::Moore2);
+    }
+
+    #[test]
+    fn cells_get_older_while_alive() {
+        let vertical = [(5, 4), (5, 5), (5, 6)];
+        let mut board = board_with(10, 10, &vertical);
+        assert_eq!(board.age(5, 5), 1);
+
+        board.step();
+        board.step();
+        // The center of a blinker never dies, the ends are reborn every 2 generations
+        assert_eq!(board.age(5, 5), 3);
+        assert_eq!(board.age(5, 4), 1);
+        assert_eq!(board.age(4, 5), 0);
+        assert!(!board.is_newborn(5, 5));
+        assert!(board.is_newborn(5, 4));
+        assert!(!board.is_newborn(4, 5));
+
+        board.set(5, 5, false);
+        board.set(5, 5, true);
+        assert_eq!(board.age(5, 5), 1);
+        assert!(board.is_newborn(5, 5));
+    }
+
+    #[test]
+    fn dead_cells_count_generations_since_death() {
+        let mut board = board_with(10, 10, &[(5, 4), (5, 5), (5, 6)]);
+        board.step();
+        assert_eq!(board.since_death(5, 4), 1);
+        assert_eq!(board.since_death(5, 5), 0);
+        // A cell that has never been alive is not a trail
+        assert_eq!(board.since_death(0, 0), 0);
+
+        // The lone center dies and the ends of the blinker some time earlier
+        board.set(4, 5, false);
+        board.set(6, 5, false);
+        board.step();
+        board.step();
+        assert_eq!(board.since_death(5, 5), 2);
+        assert_eq!(board.since_death(5, 4), 3);
+    }
+
+    #[test]
+    fn glider_translates_every_4_generations() {
+        let mut board = board_with(20, 20, &GLIDER);
+        for generation in 1..=3 {
+            for _ in 0..4 {
+                board.step();
+            }
+            assert_eq!(board.alive_cells(), shifted(&GLIDER, generation, generation));
+        }
+    }
+
+    #[test]
+    fn drawn_board_is_changed_by_edits_and_generations() {
+        let mut board = board_with(5, 5, &GLIDER);
+        assert!(board.changed());
+        board.mark_drawn();
+        // Setting a cell to its own state changes nothing
+        board.set(1, 0, true);
+        board.set(4, 4, false);
+        assert!(!board.changed());
+        board.set(4, 4, true);
+        assert!(board.changed());
+
+        board.mark_drawn();
+        board.step();
+        assert!(board.changed());
+        board.mark_drawn();
+        board.clear();
+        assert!(board.changed());
+    }
+
+    #[test]
+    fn counts_births_and_deaths() {
+        let mut board = board_with(20, 20, &GLIDER);
+        board.step();
+        // Two cells of the glider die and two are born every generation
+        assert_eq!((board.births(), board.deaths()), (2, 2));
+
+        let mut board = board_with(10, 10, &[(5, 5)]);
+        board.step();
+        assert_eq!((board.births(), board.deaths()), (0, 1));
+    }
+
+    #[test]
+    fn moved_cells_stop_at_edges_or_wrap() {
+        let mut board = Board::new(10, 8);
+        assert_eq!(board.moved_cell((4, 4), (1, -1)), (5, 3));
+        assert_eq!(board.moved_cell((0, 7), (-1, 1)), (0, 7));
+        board.set_wrap(true);
+        assert_eq!(board.moved_cell((0, 7), (-1, 1)), (9, 0));
+    }
+
+    #[test]
+    fn bounding_box_reaches_edges_of_field() {
+        let board = Board::new(10, 8);
+        assert_eq!(board.bounding_box(), None);
+
+        // A glider in the upper left corner and a cell in the lower right one
+        let mut board = board_with(10, 8, &GLIDER);
+        assert_eq!(board.bounding_box(), Some(((0, 0), (2, 2))));
+        board.set(9, 7, true);
+        assert_eq!(board.bounding_box(), Some(((0, 0), (9, 7))));
+        // RLE files are cut to the same box
+        assert!(rle::encode(&board.alive_cells(), "B3/S23").starts_with("x = 10, y = 8"));
+
+        // The box follows the cells as they change
+        board.step();
+        assert_eq!(board.bounding_box(), Some(((0, 1), (2, 3))));
+    }
+
+    #[test]
+    fn blinker_has_two_births_and_deaths_every_generation() {
+        let mut board = board_with(10, 10, &[(5, 4), (5, 5), (5, 6)]);
+        for _ in 0..4 {
+            assert_eq!(board.step(), (2, 2));
+            assert_eq!((board.births(), board.deaths()), (2, 2));
+        }
+        // Edits don't count as births
+        board.set(1, 1, true);
+        assert_eq!((board.births(), board.deaths()), (2, 2));
+        board.clear();
+        assert_eq!((board.births(), board.deaths()), (0, 0));
+    }
+
+    #[test]
+    fn population_of_known_patterns() {
+        let mut board = board_with(20, 20, &GLIDER);
+        assert_eq!(board.population(), 5);
+        for _ in 0..10 {
+            board.step();
+            assert_eq!(board.population(), 5);
+        }
+
+        // R-pentomino grows during the first generations
+        let mut board = board_with(20, 20, &[(10, 9), (11, 9), (9, 10), (10, 10), (10, 11)]);
+        let populations: Vec<usize> = (0..4).map(|_| { board.step(); board.population() }).collect();
+        assert_eq!(populations, vec![6, 7, 9, 8]);
+    }
+
+    #[test]
+    fn highlife_replicator_copies_itself() {
+        let replicator = [(2, 0), (3, 0), (4, 0), (1, 1), (4, 1), (0, 2), (4, 2), (0, 3), (3, 3), (0, 4), (1, 4), (2, 4)];
+        let mut board = board_with(40, 40, &shifted(&replicator, 18, 18));
+        board.set_rule("B36/S23".parse().unwrap());
+        for _ in 0..12 {
+            board.step();
+        }
+
+        // Two copies of the replicator are moved diagonally
+        let mut copies = shifted(&replicator, 16, 16);
+        copies.extend(shifted(&replicator, 20, 20));
+        copies.sort_by_key(|(x, y)| (*y, *x));
+        assert_eq!(board.alive_cells(), copies);
+    }
+
+    #[test]
+    fn seeds_cells_never_survive() {
+        let domino = [(4, 4), (5, 4)];
+        let mut board = board_with(10, 10, &domino);
+        board.set_rule(Rule::SEEDS);
+        board.step();
+
+        // Both cells die and cells with exactly 2 neighbours are born above and below them
+        assert_eq!(board.alive_cells(), vec![(4, 3), (5, 3), (4, 5), (5, 5)]);
+        board.step();
+        assert!(board.alive_cells().iter().all(|cell| !domino.contains(cell)));
+    }
+
+    #[test]
+    fn randomize_follows_density() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut board = Board::new(20, 20);
+
+        board.randomize(0.0, &mut rng);
+        assert_eq!(board.population(), 0);
+        board.randomize(1.0, &mut rng);
+        assert_eq!(board.population(), 400);
+    }
+
+    #[test]
+    fn randomize_is_reproducible_with_seed() {
+        let mut first = Board::new(20, 20);
+        let mut second = Board::new(20, 20);
+        first.randomize(0.3, &mut StdRng::seed_from_u64(7));
+        second.randomize(0.3, &mut StdRng::seed_from_u64(7));
+        assert_eq!(first.alive_cells(), second.alive_cells());
+    }
+
+    #[test]
+    fn noise_flips_outcomes_reproducibly() {
+        // A block would stay still forever, noise keeps changing it and the cells around it
+        let block = [(49, 49), (50, 49), (49, 50), (50, 50)];
+        let noisy = |seed| {
+            let mut board = board_with(100, 100, &block);
+            board.set_noise(0.01, seed);
+            board.set_state(0, 0, WALL);
+            board.step();
+            board
+        };
+        let board = noisy(7);
+        // About 100 of 10000 outcomes are flipped
+        assert!((50..150).contains(&(board.births() + board.deaths())));
+        assert_eq!(board.state(0, 0), WALL);
+        assert_eq!(noisy(7).cells(), board.cells());
+        assert_ne!(noisy(8).cells(), board.cells());
+
+        // Every outcome is flipped with a noise of 1 and none without noise
+        let mut board = board_with(100, 100, &block);
+        board.set_noise(1.0, 7);
+        board.step();
+        assert_eq!(board.population(), 10000 - 4);
+        // Only the corners with 3 neighbours survive the crowd without noise
+        board.set_noise(0.0, 7);
+        board.step();
+        assert_eq!(board.alive_cells(), vec![(0, 0), (99, 0), (0, 99), (99, 99)]);
+    }
+
+    #[test]
+    fn noise_keeps_to_the_states_of_the_automaton() {
+        // Cells of Immigration are born in both colors
+        let mut board = Board::new(100, 100);
+        board.set_automaton(Automaton::Immigration);
+        board.set_noise(1.0, 7);
+        board.step();
+        let reds = board.cells().iter().filter(|state| **state == RED).count();
+        assert_eq!(board.population(), 10000);
+        assert!((4500..5500).contains(&reds));
+
+        // A firing cell of Brian's Brain still rests, while a resting one fires instead of getting ready
+        let mut board = Board::new(10, 10);
+        board.set_automaton(Automaton::BriansBrain);
+        board.set_state(2, 2, ALIVE);
+        board.set_state(7, 7, REFRACTORY);
+        board.set_noise(1.0, 7);
+        board.step();
+        assert_eq!(board.state(2, 2), REFRACTORY);
+        assert_eq!(board.state(7, 7), ALIVE);
+
+        // Wireworld and Langton's Ant run as they would without noise
+        for (automaton, state) in [(Automaton::Wireworld, CONDUCTOR), (Automaton::LangtonsAnt, ant::state(DEAD, Some(Heading::Up)))] {
+            let quiet = |noise| {
+                let mut board = Board::new(10, 10);
+                board.set_automaton(automaton);
+                board.set_state(4, 4, state);
+                board.set_noise(noise, 7);
+                board.step();
+                board
+            };
+            assert_eq!(quiet(1.0).noise(), 0.0);
+            assert_eq!(quiet(1.0).cells(), quiet(0.0).cells());
+        }
+
+        // The chance of the noise survives starting the noise over while it's off
+        let mut board = Board::new(10, 10);
+        board.set_noise(0.01, 7);
+        board.set_automaton(Automaton::Wireworld);
+        board.set_noise(board.noise_probability(), 8);
+        board.set_automaton(Automaton::Life);
+        assert_eq!(board.noise(), 0.01);
+    }
+
+    #[test]
+    fn resized_board_scales_cells() {
+        let block = [(4, 4), (5, 4), (4, 5), (5, 5)];
+        let board = board_with(10, 10, &block);
+
+        // Every cell becomes a 2x2 square and back
+        let finer = board.resized(20, 20);
+        assert_eq!(finer.population(), 16);
+        assert_eq!(finer.resized(10, 10).alive_cells(), block.to_vec());
+    }
+
+    #[test]
+    fn glider_travels_around_wrapped_board() {
+        let mut board = board_with(20, 20, &GLIDER);
+        board.set_wrap(true);
+        // Glider comes back to the same place after crossing the whole board
+        for _ in 0..4 * 20 {
+            board.step();
+        }
+        assert_eq!(board.alive_cells(), shifted(&GLIDER, 0, 0));
+
+        // Without wrapping it dies at the border instead
+        let mut board = board_with(20, 20, &GLIDER);
+        for _ in 0..4 * 20 {
+            board.step();
+        }
+        assert_ne!(board.alive_cells().len(), GLIDER.len());
+    }
+
+    #[test]
+    fn glider_wraps_around_all_edges_of_wide_board() {
+        // Start next to the lower right corner, so the glider crosses both edges soon
+        let start = shifted(&GLIDER, 37, 17);
+        let mut board = board_with(40, 20, &start);
+        board.set_wrap(true);
+        // It moves by a cell in both directions every 4 generations,
+        // so it needs 40 moves to come back to the same place on a 40x20 board
+        for _ in 0..4 * 40 {
+            board.step();
+        }
+        assert_eq!(board.alive_cells(), start);
+
+        // After 8 moves it is in the upper left corner
+        for _ in 0..4 * 8 {
+            board.step();
+        }
+        assert_eq!(board.alive_cells(), shifted(&GLIDER, 5, 5));
+    }
+    #[test]
+    fn sparse_engine_matches_dense_engine() {
+        for (rule, wrap) in [(Rule::CONWAY, false), (Rule::CONWAY, true), (Rule::HIGHLIFE, true), ("B0/S8".parse().unwrap(), false)] {
+            let mut dense = Board::new(48, 32);
+            dense.randomize(0.3, &mut StdRng::seed_from_u64(11));
+            dense.set_rule(rule);
+            dense.set_wrap(wrap);
+            dense.set_engine(Some(Engine::Dense));
+            let mut sparse = dense.resized(48, 32);
+            sparse.set_engine(Some(Engine::Sparse));
+            assert_eq!(sparse.cells(), dense.cells());
+
+            for _ in 0..100 {
+                dense.step();
+                sparse.step();
+                assert_eq!(sparse.cells(), dense.cells());
+                assert_eq!(sparse.population(), dense.population());
+                for (x, y) in [(0, 0), (10, 5), (24, 16), (47, 31)] {
+                    assert_eq!(sparse.age(x, y), dense.age(x, y));
+                    assert_eq!(sparse.since_death(x, y), dense.since_death(x, y));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bitwise_engine_matches_dense_engine() {
+        let mut rng = StdRng::seed_from_u64(7);
+        // Rows of a single word, of whole words and of words with a few cells left over, and a single row
+        let sizes = [(1, 1), (5, 1), (63, 7), (64, 20), (65, 3), (130, 40)];
+        let rules = [Rule::CONWAY, Rule::HIGHLIFE, "B0/S8".parse().unwrap(), "B1/S0".parse().unwrap()];
+        for ((width, height), rule) in sizes.into_iter().flat_map(|size| rules.into_iter().map(move |rule| (size, rule))) {
+            for wrap in [false, true] {
+                let mut dense = Board::new(width, height);
+                dense.randomize(rng.gen_range(0.1..0.6), &mut rng);
+                dense.set_state(0, 0, automaton::IMMORTAL);
+                dense.set_state(width - 1, height - 1, automaton::WALL);
+                dense.set_rule(rule);
+                dense.set_wrap(wrap);
+                dense.set_engine(Some(Engine::Dense));
+                let mut bitwise = dense.resized(width, height);
+                bitwise.set_engine(Some(Engine::Bitwise));
+
+                for _ in 0..30 {
+                    assert_eq!(bitwise.step(), dense.step());
+                    assert_eq!(bitwise.cells(), dense.cells(), "{}x{} {} wrap: {}", width, height, rule, wrap);
+                    assert_eq!(bitwise.population(), dense.population());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parallel_rows_match_serial_engine() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let automata = [Automaton::Life, Automaton::Wireworld, Automaton::BriansBrain, Automaton::Immigration];
+        for (automaton, wrap) in automata.into_iter().flat_map(|automaton| [(automaton, false), (automaton, true)]) {
+            let (width, height) = (rng.gen_range(1..60), rng.gen_range(1..60));
+            let mut serial = Board::new(width, height);
+            serial.set_automaton(automaton);
+            serial.randomize(0.4, &mut rng);
+            serial.set_state(0, 0, automaton::WALL);
+            serial.set_wrap(wrap);
+            serial.set_engine(Some(Engine::Dense));
+            serial.set_parallel(Some(false));
+            let mut parallel = serial.resized(width, height);
+            parallel.set_parallel(Some(true));
+
+            for _ in 0..30 {
+                assert_eq!(parallel.step(), serial.step());
+                assert_eq!(parallel.cells(), serial.cells(), "{:?} {}x{} wrap: {}", automaton, width, height, wrap);
+            }
+        }
+    }
+
+    // Engines every property is checked with, the dense one with and without threads
+    const ENGINES: [(Engine, bool); 4] = [(Engine::Dense, false), (Engine::Dense, true), (Engine::Sparse, false), (Engine::Bitwise, false)];
+
+    // Creates a board of Conway's Life with the given alive cells (taken modulo the size) computed by the engine
+    fn board_of(width: i32, height: i32, wrap: bool, cells: &[(i32, i32)], (engine, parallel): (Engine, bool)) -> Board {
+        let mut board = Board::new(width, height);
+        board.set_wrap(wrap);
+        board.set_engine(Some(engine));
+        board.set_parallel(Some(parallel));
+        for (x, y) in cells {
+            board.set(x.rem_euclid(width), y.rem_euclid(height), true);
+        }
+        board
+    }
+
+    // Random cells of a board up to 40x40 (larger coordinates are taken modulo its size)
+    fn any_cells() -> impl Strategy<Value = Vec<(i32, i32)>> {
+        prop::collection::vec((0..40, 0..40), 0..120)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn empty_board_stays_empty(width in 1..40, height in 1..40, wrap: bool) {
+            for engine in ENGINES {
+                let mut board = board_of(width, height, wrap, &[], engine);
+                board.step();
+                prop_assert_eq!(board.population(), 0);
+            }
+        }
+
+        // Boards of 3 cells at least in both directions, as a lone cell of a narrower wrapped board is its own neighbour
+        #[test]
+        fn lone_cell_dies(width in 3..40, height in 3..40, x in 0..40, y in 0..40, wrap: bool) {
+            for engine in ENGINES {
+                let mut board = board_of(width, height, wrap, &[(x, y)], engine);
+                board.step();
+                prop_assert_eq!(board.population(), 0);
+            }
+        }
+
+        #[test]
+        fn step_commutes_with_translation_on_wrapped_board(width in 1..40, height in 1..40, cells in any_cells(), dx in 0..40, dy in 0..40) {
+            let moved: Vec<(i32, i32)> = cells.iter().map(|(x, y)| (x.rem_euclid(width) + dx, y.rem_euclid(height) + dy)).collect();
+            for engine in ENGINES {
+                let mut board = board_of(width, height, true, &cells, engine);
+                let mut moved_board = board_of(width, height, true, &moved, engine);
+                for _ in 0..3 {
+                    board.step();
+                    moved_board.step();
+                }
+                let stepped_then_moved = board_of(width, height, true, &board.alive_cells().iter().map(|(x, y)| (x + dx, y + dy)).collect::<Vec<_>>(), engine);
+                prop_assert_eq!(moved_board.cells(), stepped_then_moved.cells());
+            }
+        }
+
+        // A pattern `GENERATIONS + 1` cells away from the edges never reaches cells whose neighbours are across them
+        #[test]
+        fn wrapped_and_bounded_boards_agree_far_from_edges(width in 10..40, height in 10..40, cells in any_cells()) {
+            const GENERATIONS: i32 = 3;
+            let margin = GENERATIONS + 1;
+            let inner: Vec<(i32, i32)> = cells.iter()
+                .map(|(x, y)| (margin + x.rem_euclid(width - 2 * margin), margin + y.rem_euclid(height - 2 * margin)))
+                .collect();
+            for engine in ENGINES {
+                let mut wrapped = board_of(width, height, true, &inner, engine);
+                let mut bounded = board_of(width, height, false, &inner, engine);
+                for _ in 0..GENERATIONS {
+                    prop_assert_eq!(wrapped.step(), bounded.step());
+                    prop_assert_eq!(wrapped.cells(), bounded.cells());
+                }
+            }
+        }
+
+        #[test]
+        fn population_never_exceeds_cells(width in 1..40, height in 1..40, cells in any_cells(), wrap: bool) {
+            for engine in ENGINES {
+                let mut board = board_of(width, height, wrap, &cells, engine);
+                for _ in 0..5 {
+                    board.step();
+                    prop_assert!(board.population() <= (width * height) as usize);
+                    prop_assert_eq!(board.population(), board.alive_cells().len());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// Command line arguments of the game
+use crate::automaton::Automaton;
+use crate::neighbourhood::Neighbourhood;
+use crate::engine::Engine;
+use crate::picture;
+use crate::rule::Rule;
+use crate::soup::SoupSearch;
+
+// Help message with all arguments
+pub const USAGE: &str = "\
+Usage: game_of_life [OPTIONS]
+
+Options:
+    --cols <N>          Number of cells in a single row (1-320, default 20)
+    --rows <N>          Number of cells in a single column (1-320, default 20)
+    --speed <N>         Generations per second (1-60, default 5)
+    --pattern <FILE>    Pattern file (.rle or .cells) to load on start
+    --image <FILE>      Picture (.png, .jpg or .bmp) whose dark parts become alive cells on start
+    --threshold <N>     Luminance of pixels below which cells of --image are alive (0-255, default 128)
+    --wrap              Wrap the field around its edges
+    --no-grid           Hide lines between cells (G shows them)
+    --theme <NAME>      Color theme to start with: classic, dark, light or the one of theme.toml (default classic)
+    --rule <RULE>       Rule of the game like B36/S23 or 23/36 (default B3/S23)
+    --automaton <NAME>  Automaton of the field: life, wireworld, briansbrain, immigration or langtonsant (default life)
+    --capture           Surviving cells of immigration take the color most of their neighbours have (I toggles it)
+    --neighbours <NAME> Cells counted as neighbours: moore, vonneumann or moore2 (default moore)
+    --engine <ENGINE>   Engine computing generations: dense, sparse or bitwise (default: sparse for boards over 10000 cells)
+    --history <N>       Generations that can be stepped back (0-10000, default 100)
+    --turbo <N>         Generations made at once in turbo mode (2-1000, default 10)
+    --stable-cap <N>    Generations U runs for at most waiting for the field to stabilize (1-1000000, default 10000)
+    --trail <N>         Generations a dead cell leaves a trail for (1-100, default 10)
+    --font <FILE>       TrueType font file used instead of the built-in font
+    --font-size <N>     Font size of the status, other texts are scaled with it (8-40, default 21)
+    --fresh             Start a new game instead of continuing the last session
+    --resume-on-focus   Resume the game paused by switching to another window when switching back
+    --headless          Run without a window and print the result (needs --pattern or --image)
+    --generations <N>   Generations made in the headless mode (0-1000000, default 100)
+    --out <FILE>        RLE file the headless mode writes the result to instead of stdout
+    --dump-ascii        Write the result of the headless mode as ASCII art of . and # instead of RLE
+    --full-field        Write the whole field as ASCII art instead of the bounding box of alive cells
+    --stats <FILE>      CSV file a row of population stats is appended to every generation
+    --seed <N>          Seed the field is filled with randomly on start (R fills it again)
+    --density <P>       Chance of a cell to be alive in random fills (0-1, default 0.3)
+    --noise [P]         Flip the outcome of the rule for every cell with chance P each generation (0-1, default 0.001),
+                        the noise follows --seed, so runs with the same seed are the same,
+                        wireworld and langtonsant ignore it
+    --soup-search [n=<N>] [density=<P>]
+                        Run N random soups (1-100000, default 100) until they settle or --stable-cap
+                        generations, print them and write them sorted by longevity to --out (default soups.csv)
+    -v, --verbose       Log loads and saves of files, twice (-vv) to log every generation too
+    -h, --help          Print this message";
+
+// Limits of the number of cells in a row or a column
+pub const MIN_CELLS: i32 = 1;
+pub const MAX_CELLS: i32 = 320;
+
+// Limits of the speed of the game (generations per second)
+pub const MIN_SPEED: f64 = 1.0;
+pub const MAX_SPEED: f64 = 60.0;
+
+// Limit of the number of generations kept to step back
+pub const MAX_HISTORY: usize = 10000;
+
+// Limit of the number of generations made at once in turbo mode
+pub const MAX_TURBO: u32 = 1000;
+
+// Limit of the number of generations a dead cell leaves a trail for
+pub const MAX_TRAIL: u32 = 100;
+
+// Font size of the status and its limits
+pub const DEFAULT_FONT_SIZE: f32 = 21.0;
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 40.0;
+
+// Limit of the number of generations made in the headless mode
+pub const MAX_GENERATIONS: u32 = 1_000_000;
+
+// Chance of a cell to be alive in random fills by default
+pub const DEFAULT_DENSITY: f64 = 0.3;
+
+// Chance of an outcome of the rule to be flipped given by --noise without a value
+pub const DEFAULT_NOISE: f64 = 0.001;
+
+// Number of soups tried by the soup search by default and its limit
+pub const DEFAULT_SOUPS: u32 = 100;
+pub const MAX_SOUPS: u32 = 100_000;
+
+// Settings of the game given by arguments
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub cols: i32,
+    pub rows: i32,
+    pub speed: f64,
+    pub pattern: Option<String>,
+    // Picture that seeds the board instead of a pattern
+    pub image: Option<String>,
+    // Luminance below which pixels of the picture make alive cells
+    pub threshold: u8,
+    pub wrap: bool,
+    // Draw lines between cells
+    pub grid: bool,
+    // Name of the color theme to start with
+    pub theme: Option<String>,
+    pub rule: Rule,
+    pub automaton: Automaton,
+    // Surviving cells of automata with colors are captured by the other color
+    pub capture: bool,
+    pub neighbourhood: Neighbourhood,
+    // Engine computing generations (chosen by the size of the board if not given)
+    pub engine: Option<Engine>,
+    // Number of generations that can be stepped back
+    pub history: usize,
+    // Number of generations made at once in turbo mode
+    pub turbo: u32,
+    // Maximal number of generations made while waiting for the board to stabilize
+    pub stable_cap: u32,
+    // Number of generations a dead cell leaves a trail for
+    pub trail: u32,
+    // Font file used instead of the built-in font
+    pub font: Option<String>,
+    // Font size of the status, other texts are scaled with it
+    pub font_size: f32,
+    // Don't continue the last session
+    pub fresh: bool,
+    // Resume the game paused by a focus loss of the window when the focus comes back
+    pub resume_on_focus: bool,
+    // Run the simulation without a window
+    pub headless: bool,
+    // Number of generations made in the headless mode
+    pub generations: u32,
+    // File the headless mode writes the result to
+    pub out: Option<String>,
+    // Write the result as ASCII art instead of RLE
+    pub dump_ascii: bool,
+    // Write the whole field as ASCII art instead of the bounding box of alive cells
+    pub full_field: bool,
+    // CSV file population stats are appended to
+    pub stats: Option<String>,
+    // Seed of the random fill on start
+    pub seed: Option<u64>,
+    // Chance of a cell to be alive in random fills
+    pub density: f64,
+    // Chance of the outcome of the rule for a cell to be flipped every generation (0 without noise)
+    pub noise: f64,
+    // Search of long-lived soups made instead of the game
+    pub soup_search: Option<SoupSearch>,
+    // Number of times --verbose is given: 1 logs loads and saves of files, 2 every generation too
+    pub verbose: u8,
+    // Only print the help message
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options{cols: 20, rows: 20, speed: 5.0, pattern: None, image: None, threshold: picture::DEFAULT_THRESHOLD, wrap: false, grid: true, theme: None, rule: Rule::default(), automaton: Automaton::Life, capture: false, neighbourhood: Neighbourhood::Moore, engine: None, history: 100, turbo: 10, stable_cap: 10000, trail: 10, font: None, font_size: DEFAULT_FONT_SIZE, fresh: false, resume_on_focus: false, headless: false, generations: 100, out: None, dump_ascii: false, full_field: false, stats: None, seed: None, density: DEFAULT_DENSITY, noise: 0.0, soup_search: None, verbose: 0, help: false}
+    }
+}
+
+// Function reads settings from arguments (without the name of the program)
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+    parse_with(Options::default(), args)
+}
+
+// Function reads settings from arguments over the given defaults (like the ones of the config file)
+pub fn parse_with<I: IntoIterator<Item = String>>(defaults: Options, args: I) -> Result<Options, String> {
+    let mut options = defaults;
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cols" => options.cols = parse_number(&arg, args.next(), MIN_CELLS, MAX_CELLS)?,
+            "--rows" => options.rows = parse_number(&arg, args.next(), MIN_CELLS, MAX_CELLS)?,
+            "--speed" => options.speed = parse_number(&arg, args.next(), MIN_SPEED, MAX_SPEED)?,
+            "--pattern" => options.pattern = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--image" => options.image = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--threshold" => options.threshold = parse_number(&arg, args.next(), 0, u8::MAX)?,
+            "--wrap" => options.wrap = true,
+            "--capture" => options.capture = true,
+            "--no-grid" => options.grid = false,
+            "--theme" => options.theme = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--rule" => {
+                let value = args.next().ok_or(format!("Missing value of {}", arg))?;
+                options.rule = value.parse().map_err(|e| format!("{}", e))?;
+            },
+            "--automaton" => {
+                let value = args.next().ok_or(format!("Missing value of {}", arg))?;
+                options.automaton = value.parse()?;
+            },
+            "--neighbours" => {
+                let value = args.next().ok_or(format!("Missing value of {}", arg))?;
+                options.neighbourhood = value.parse()?;
+            },
+            "--engine" => {
+                let value = args.next().ok_or(format!("Missing value of {}", arg))?;
+                options.engine = Some(value.parse()?);
+            },
+            "--history" => options.history = parse_number(&arg, args.next(), 0, MAX_HISTORY)?,
+            "--turbo" => options.turbo = parse_number(&arg, args.next(), 2, MAX_TURBO)?,
+            "--stable-cap" => options.stable_cap = parse_number(&arg, args.next(), 1, MAX_GENERATIONS)?,
+            "--trail" => options.trail = parse_number(&arg, args.next(), 1, MAX_TRAIL)?,
+            "--font" => options.font = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--font-size" => options.font_size = parse_number(&arg, args.next(), MIN_FONT_SIZE, MAX_FONT_SIZE)?,
+            "--fresh" => options.fresh = true,
+            "--resume-on-focus" => options.resume_on_focus = true,
+            "--headless" => options.headless = true,
+            "--generations" => options.generations = parse_number(&arg, args.next(), 0, MAX_GENERATIONS)?,
+            "--out" => options.out = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--dump-ascii" => options.dump_ascii = true,
+            "--full-field" => options.full_field = true,
+            "--stats" => options.stats = Some(args.next().ok_or(format!("Missing value of {}", arg))?),
+            "--seed" => options.seed = Some(parse_number(&arg, args.next(), 0, u64::MAX)?),
+            "--density" => options.density = parse_number(&arg, args.next(), 0.0, 1.0)?,
+            // The chance may be left out for the default one
+            "--noise" => options.noise = match args.next_if(|value| !value.starts_with('-')) {
+                Some(value) => parse_number(&arg, Some(value), 0.0, 1.0)?,
+                None => DEFAULT_NOISE,
+            },
+            "--soup-search" => {
+                // Parameters like n=100 follow the argument
+                let mut search = SoupSearch{soups: DEFAULT_SOUPS, density: DEFAULT_DENSITY};
+                while let Some(param) = args.next_if(|param| param.contains('=') && !param.starts_with('-')) {
+                    match param.split_once('=') {
+                        Some(("n", value)) => search.soups = parse_number("n", Some(String::from(value)), 1, MAX_SOUPS)?,
+                        Some(("density", value)) => search.density = parse_number("density", Some(String::from(value)), 0.0, 1.0)?,
+                        _ => return Err(format!("Unknown parameter {} of {}, expected n or density", param, arg)),
+                    }
+                }
+                options.soup_search = Some(search);
+            },
+            "-v" | "--verbose" => options.verbose = options.verbose.saturating_add(1),
+            "-vv" => options.verbose = options.verbose.saturating_add(2),
+            "-h" | "--help" => options.help = true,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+// Function reads a value of the argument and checks that it is in the given range
+fn parse_number<T>(arg: &str, value: Option<String>, min: T, max: T) -> Result<T, String>
+where T: std::str::FromStr + PartialOrd + std::fmt::Display {
+    let value = value.ok_or(format!("Missing value of {}", arg))?;
+    match value.parse::<T>() {
+        Ok(number) if number >= min && number <= max => Ok(number),
+        _ => Err(format!("Value of {} should be a number from {} to {}, got {}", arg, min, max, value)),
+    }
+}
@@ -0,0 +1,240 @@
+// Defaults of the game read from `gameoflife.toml` before the arguments, which override them
+use directories::ProjectDirs;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use crate::cli::{Options, MAX_CELLS, MAX_SPEED, MIN_CELLS, MIN_SPEED};
+use crate::rule::Rule;
+
+// Name of the config file
+pub const FILE_NAME: &str = "gameoflife.toml";
+
+// First lines of the config file written on the first run
+const HEADER: &str = "\
+# Defaults of the Game of Life, arguments override them
+# Actions of the [keys] table are bound to keys like \"Ctrl+Shift+E\"
+";
+
+// Settings of the config file, the missing ones are left as they are
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cols: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<Rule>,
+    // Name of the theme to start with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub grid: Option<bool>,
+    // Chance of outcomes of the rule to be flipped every generation (0 turns the noise off)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub noise: Option<f64>,
+    // Resume the game paused by a focus loss of the window
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resume_on_focus: Option<bool>,
+    // Keys of actions like `ToggleGrid = "G"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, Keys>,
+}
+
+// Keys of an action: a single one like "G" or a list like ["G", "Ctrl+G"]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Keys {
+    One(String),
+    Several(Vec<String>),
+}
+
+impl Keys {
+    // All keys of the action
+    pub fn list(&self) -> Vec<&str> {
+        match self {
+            Keys::One(key) => vec![key.as_str()],
+            Keys::Several(keys) => keys.iter().map(|key| key.as_str()).collect(),
+        }
+    }
+}
+
+impl Config {
+    // Constructor for a config of all settings of the options and the given keys
+    pub fn of(options: &Options, keys: BTreeMap<String, Keys>) -> Config {
+        Config{
+            cols: Some(options.cols),
+            rows: Some(options.rows),
+            speed: Some(options.speed),
+            rule: Some(options.rule),
+            theme: Some(options.theme.clone().unwrap_or_else(|| String::from("classic"))),
+            wrap: Some(options.wrap),
+            grid: Some(options.grid),
+            noise: Some(options.noise),
+            resume_on_focus: Some(options.resume_on_focus),
+            keys,
+        }
+    }
+
+    // Function replaces the options with the settings of the config
+    pub fn apply(&self, options: &mut Options) {
+        options.cols = self.cols.unwrap_or(options.cols);
+        options.rows = self.rows.unwrap_or(options.rows);
+        options.speed = self.speed.unwrap_or(options.speed);
+        options.rule = self.rule.unwrap_or(options.rule);
+        options.theme = self.theme.clone().or(options.theme.take());
+        options.wrap = self.wrap.unwrap_or(options.wrap);
+        options.grid = self.grid.unwrap_or(options.grid);
+        options.noise = self.noise.unwrap_or(options.noise);
+        options.resume_on_focus = self.resume_on_focus.unwrap_or(options.resume_on_focus);
+    }
+
+    // Function writes the config as the contents of a TOML file
+    pub fn to_toml(&self) -> String {
+        // A config has no values TOML can't write
+        format!("{}\n{}", HEADER, toml::to_string(self).unwrap_or_default())
+    }
+}
+
+// Function reads a config from the text of a TOML file
+// Every bad setting is skipped with a warning instead of failing the whole file
+// Returns the config with the warnings
+pub fn parse(text: &str) -> (Config, Vec<String>) {
+    let mut warnings = Vec::new();
+    let table: toml::value::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(e) => return (Config::default(), vec![e.to_string()]),
+    };
+    let mut config = Config::default();
+    for (key, value) in table {
+        match key.as_str() {
+            "cols" => config.cols = checked(&key, value, MIN_CELLS..=MAX_CELLS, &mut warnings),
+            "rows" => config.rows = checked(&key, value, MIN_CELLS..=MAX_CELLS, &mut warnings),
+            "speed" => config.speed = checked(&key, value, MIN_SPEED..=MAX_SPEED, &mut warnings),
+            "rule" => config.rule = value_of(&key, value, &mut warnings),
+            "theme" => config.theme = value_of(&key, value, &mut warnings),
+            "wrap" => config.wrap = value_of(&key, value, &mut warnings),
+            "grid" => config.grid = value_of(&key, value, &mut warnings),
+            "noise" => config.noise = checked(&key, value, 0.0..=1.0, &mut warnings),
+            "resume_on_focus" => config.resume_on_focus = value_of(&key, value, &mut warnings),
+            "keys" => config.keys = keys_of(value, &mut warnings),
+            _ => warnings.push(format!("Unknown setting {}", key)),
+        }
+    }
+    (config, warnings)
+}
+
+// Function reads the value of the setting, or warns about it if it has a wrong type
+fn value_of<T: DeserializeOwned>(key: &str, value: toml::Value, warnings: &mut Vec<String>) -> Option<T> {
+    match value.try_into() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warnings.push(format!("Bad {}: {}", key, e));
+            None
+        },
+    }
+}
+
+// Function reads the keys of every action of the table
+// Actions with keys of wrong types are skipped with warnings, so the other ones are still bound
+fn keys_of(value: toml::Value, warnings: &mut Vec<String>) -> BTreeMap<String, Keys> {
+    let table: toml::value::Table = value_of("keys", value, warnings).unwrap_or_default();
+    table.into_iter()
+        .filter_map(|(action, keys)| value_of(&format!("keys of {}", action), keys, warnings).map(|keys| (action, keys)))
+        .collect()
+}
+
+// Function reads the value of the setting and checks that it's within the limits
+fn checked<T>(key: &str, value: toml::Value, limits: RangeInclusive<T>, warnings: &mut Vec<String>) -> Option<T>
+where T: DeserializeOwned + PartialOrd + Display {
+    let value = value_of(key, value, warnings)?;
+    match limits.contains(&value) {
+        true => Some(value),
+        false => {
+            warnings.push(format!("Bad {}: {} is not in {}-{}", key, value, limits.start(), limits.end()));
+            None
+        },
+    }
+}
+
+// Function reads the config from the file
+// Returns the config with the warnings
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<(Config, Vec<String>)> {
+    let text = fs::read_to_string(path)?;
+    Ok(parse(&text))
+}
+
+// Function writes the config to the file
+// Missing directories of the path are created
+pub fn save<P: AsRef<Path>>(config: &Config, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, config.to_toml())
+}
+
+// Places the config file is looked for in: next to the binary and in the config directory of the user
+// (like `~/.config/gameoflife/gameoflife.toml` on Linux)
+pub fn paths() -> Vec<PathBuf> {
+    let beside_binary = env::current_exe().ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(FILE_NAME)));
+    beside_binary.into_iter().chain(default_path()).collect()
+}
+
+// Path the config file is written to on the first run
+pub fn default_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "gameoflife").map(|dirs| dirs.config_dir().join(FILE_NAME))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_survives_round_trip() {
+        let keys = BTreeMap::from([
+            (String::from("ToggleGrid"), Keys::One(String::from("Ctrl+G"))),
+            (String::from("Step"), Keys::Several(vec![String::from("N"), String::from("Shift+N")])),
+        ]);
+        let config = Config::of(&Options::default(), keys);
+        let text = config.to_toml();
+        assert!(text.contains("rule = \"B3/S23\""));
+        assert!(text.contains("Step = [\"N\", \"Shift+N\"]"));
+        assert_eq!(parse(&text), (config, Vec::new()));
+
+        // Missing settings are left as they are
+        assert_eq!(parse("wrap = true\n"), (Config{wrap: Some(true), ..Config::default()}, Vec::new()));
+        assert_eq!(parse("noise = 0.001\n").0.noise, Some(0.001));
+    }
+
+    #[test]
+    fn bad_settings_are_skipped_with_warnings() {
+        let (config, warnings) = parse("cols = 1000\nrows = 30\nspeed = \"fast\"\nrule = \"B9\"\ncolour = 1\nnoise = 2.0\n");
+        assert_eq!(config, Config{rows: Some(30), ..Config::default()});
+        assert_eq!(warnings.len(), 5);
+        assert!(warnings.contains(&String::from("Bad cols: 1000 is not in 1-320")));
+
+        let mut options = Options::default();
+        config.apply(&mut options);
+        assert_eq!((options.cols, options.rows), (20, 30));
+
+        // Keys of wrong types are skipped without the other ones
+        let (config, warnings) = parse("[keys]\nToggleGrid = 7\nToggleHelp = \"F1\"\n");
+        assert_eq!(config.keys, BTreeMap::from([(String::from("ToggleHelp"), Keys::One(String::from("F1")))]));
+        assert_eq!(warnings.len(), 1);
+
+        // A broken file gives the default config
+        assert_eq!(parse("cols = ").0, Config::default());
+    }
+}
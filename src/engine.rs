@@ -0,0 +1,316 @@
+// Ways of computing the next generation of a board
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use crate::rule::Rule;
+
+// Boards with more cells than this use the sparse engine by default
+pub const SPARSE_THRESHOLD: i32 = 100 * 100;
+
+// Boards with at least this many cells split rows of the dense engine between threads by default
+// A generation of a smaller board takes less than a millisecond, and starting the threads would take
+// a good part of the time they save
+pub const PARALLEL_THRESHOLD: i32 = 200 * 200;
+
+// How the next generation is computed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Engine {
+    // Every cell of the board is checked
+    Dense,
+    // Only alive cells and their neighbours are checked
+    Sparse,
+    // Cells are packed into 64-bit words and 64 of them are computed at once
+    Bitwise,
+}
+
+impl Engine {
+    // Engine that suits a board of the given size better
+    pub fn for_size(width: i32, height: i32) -> Engine {
+        match width * height > SPARSE_THRESHOLD {
+            true => Engine::Sparse,
+            false => Engine::Dense,
+        }
+    }
+}
+
+// Function checks if rows of a board of the given size are better computed by several threads
+pub fn runs_parallel(width: i32, height: i32) -> bool {
+    width * height >= PARALLEL_THRESHOLD
+}
+
+impl FromStr for Engine {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Engine, String> {
+        match text {
+            "dense" => Ok(Engine::Dense),
+            "sparse" => Ok(Engine::Sparse),
+            "bitwise" => Ok(Engine::Bitwise),
+            _ => Err(format!("Unknown engine {}, expected dense, sparse or bitwise", text)),
+        }
+    }
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Engine::Dense => write!(f, "dense"),
+            Engine::Sparse => write!(f, "sparse"),
+            Engine::Bitwise => write!(f, "bitwise"),
+        }
+    }
+}
+
+// Function returns alive cells of the next generation of a board of the given size
+// Neighbours are counted only around alive cells, so dead cells without alive
+// neighbours are never visited
+// Rules where cells are born without neighbours (B0) need the dense engine
+pub fn sparse_step(alive: &HashSet<(i32, i32)>, width: i32, height: i32, wrap: bool, rule: Rule) -> HashSet<(i32, i32)> {
+    let mut neighbours: HashMap<(i32, i32), u32> = HashMap::with_capacity(alive.len() * 8);
+    for (x, y) in alive {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                // The cell is not a neighbour of itself
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let mut n_x = x + dx;
+                let mut n_y = y + dy;
+                if wrap {
+                    // Neighbours outside of the board are taken from the opposite edge
+                    n_x = n_x.rem_euclid(width);
+                    n_y = n_y.rem_euclid(height);
+                } else if !(0..width).contains(&n_x) || !(0..height).contains(&n_y) {
+                    continue;
+                }
+                *neighbours.entry((n_x, n_y)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut next: HashSet<(i32, i32)> = neighbours.iter()
+        .filter(|(cell, count)| match alive.contains(cell) {
+            true => rule.survives(**count),
+            false => rule.born(**count),
+        })
+        .map(|(cell, _)| *cell)
+        .collect();
+    // Alive cells without alive neighbours are not counted above
+    if rule.survives(0) {
+        next.extend(alive.iter().filter(|cell| !neighbours.contains_key(cell)));
+    }
+    next
+}
+
+// Alive cells of a board packed into rows of 64-bit words, a bit per cell
+// The cell in column `x` is the bit `x % 64` of the word `x / 64` of its row,
+// bits past the last column are always zero
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BitRows {
+    width: i32,
+    height: i32,
+    // Number of words in a single row
+    words: usize,
+    bits: Vec<u64>,
+}
+
+impl BitRows {
+    // Constructor for rows of dead cells
+    pub fn new(width: i32, height: i32) -> BitRows {
+        let words = (width.max(0) as usize).div_ceil(64);
+        BitRows{width, height, words, bits: vec![0; words * height.max(0) as usize]}
+    }
+
+    // Is the cell alive
+    pub fn get(&self, x: i32, y: i32) -> bool {
+        let (index, bit) = self.position(x, y);
+        self.bits[index] >> bit & 1 == 1
+    }
+
+    // Function makes the cell alive or dead
+    pub fn set(&mut self, x: i32, y: i32, alive: bool) {
+        let (index, bit) = self.position(x, y);
+        match alive {
+            true => self.bits[index] |= 1 << bit,
+            false => self.bits[index] &= !(1 << bit),
+        }
+    }
+
+    // Number of alive cells
+    pub fn population(&self) -> usize {
+        self.bits.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // Function returns the cells that are alive in one of the rows and dead in the other one
+    // Both rows must have the same size
+    pub fn differences(&self, other: &BitRows) -> Vec<(i32, i32)> {
+        let mut cells = Vec::new();
+        for (index, (word, other_word)) in self.bits.iter().zip(other.bits.iter()).enumerate() {
+            let mut changed = word ^ other_word;
+            let y = (index / self.words) as i32;
+            let first_x = (index % self.words * 64) as i32;
+            while changed != 0 {
+                cells.push((first_x + changed.trailing_zeros() as i32, y));
+                // The lowest set bit is cleared
+                changed &= changed - 1;
+            }
+        }
+        cells
+    }
+
+    // Function computes the next generation of the cells by the rule
+    // Neighbours of 64 cells are counted at once: rows above and below and the row itself shifted
+    // by a bit to the left and to the right are added as binary numbers, a bit of every word per cell
+    pub fn step(&self, wrap: bool, rule: Rule) -> BitRows {
+        let mut next = BitRows::new(self.width, self.height);
+        // The rule is checked once for every number of neighbours instead of once for every cell
+        let born: Vec<u32> = (0..=8).filter(|count| rule.born(*count)).collect();
+        let survive: Vec<u32> = (0..=8).filter(|count| rule.survives(*count)).collect();
+        for y in 0..self.height {
+            // Rows above and below the cells and their own row, where the cells themselves aren't neighbours
+            // (but a wrapped board of a single row has the cells above and below themselves)
+            let rows: Vec<(&[u64], bool)> = [(y - 1, false), (y, true), (y + 1, false)].into_iter()
+                .filter_map(|(row, own)| self.neighbour_row(row, wrap).map(|row| (self.row(row), own)))
+                .collect();
+            let start = y as usize * self.words;
+            for (word, alive) in self.row(y).iter().enumerate() {
+                // Bits of the number of neighbours of every cell of the word, lowest first
+                let mut count = [0u64; 4];
+                for (row, own) in rows.iter() {
+                    let (west, east) = self.shifted(row, word, wrap);
+                    add_bits(&mut count, west);
+                    add_bits(&mut count, east);
+                    if !own {
+                        add_bits(&mut count, row[word]);
+                    }
+                }
+                let born = born.iter().fold(0, |mask, n| mask | count_is(&count, *n));
+                let survive = survive.iter().fold(0, |mask, n| mask | count_is(&count, *n));
+                next.bits[start + word] = ((alive & survive) | (!alive & born)) & self.mask(word);
+            }
+        }
+        next
+    }
+
+    // Row of neighbours at the given index: the row of the opposite edge on a wrapped board,
+    // or none if the index is outside of the board
+    fn neighbour_row(&self, y: i32, wrap: bool) -> Option<i32> {
+        match (wrap, (0..self.height).contains(&y)) {
+            (_, true) => Some(y),
+            (true, false) => Some(y.rem_euclid(self.height)),
+            (false, false) => None,
+        }
+    }
+
+    // Words of the row
+    fn row(&self, y: i32) -> &[u64] {
+        let start = y as usize * self.words;
+        &self.bits[start..start + self.words]
+    }
+
+    // Function shifts the word of the row by a bit, taking the bits that come in from the next words
+    // Returns words of the cells to the left and to the right of the cells of the word (at the same bits)
+    fn shifted(&self, row: &[u64], word: usize, wrap: bool) -> (u64, u64) {
+        let last = self.words - 1;
+        // Bit of the last column in the last word
+        let last_bit = (self.width - 1) % 64;
+        let west_carry = match (word, wrap) {
+            (0, true) => row[last] >> last_bit & 1,
+            (0, false) => 0,
+            _ => row[word - 1] >> 63,
+        };
+        let east_carry = match (word == last, wrap) {
+            (true, true) => (row[0] & 1) << last_bit,
+            (true, false) => 0,
+            (false, _) => row[word + 1] << 63,
+        };
+        (row[word] << 1 | west_carry, row[word] >> 1 | east_carry)
+    }
+
+    // Bits of the word that are cells of the board
+    fn mask(&self, word: usize) -> u64 {
+        match (word + 1 == self.words, self.width % 64) {
+            (true, bits) if bits != 0 => (1 << bits) - 1,
+            _ => u64::MAX,
+        }
+    }
+
+    // Index of the word of the cell and the bit of the cell in it
+    fn position(&self, x: i32, y: i32) -> (usize, u32) {
+        (y as usize * self.words + x as usize / 64, x as u32 % 64)
+    }
+}
+
+// Function adds a bit of the word to the number of every cell, with the bits of numbers kept in words
+// Numbers of neighbours never reach 16, so 4 bits are enough
+fn add_bits(count: &mut [u64; 4], word: u64) {
+    let mut carry = word;
+    for bits in count.iter_mut() {
+        let next_carry = *bits & carry;
+        *bits ^= carry;
+        carry = next_carry;
+    }
+}
+
+// Function finds the cells whose number is `n`
+fn count_is(count: &[u64; 4], n: u32) -> u64 {
+    count.iter().enumerate().fold(u64::MAX, |mask, (bit, bits)| match n >> bit & 1 {
+        1 => mask & bits,
+        _ => mask & !bits,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_neighbours_only_around_alive_cells() {
+        let blinker: HashSet<(i32, i32)> = [(5, 4), (5, 5), (5, 6)].into_iter().collect();
+        let next = sparse_step(&blinker, 10, 10, false, Rule::CONWAY);
+        assert_eq!(next, [(4, 5), (5, 5), (6, 5)].into_iter().collect());
+
+        // Lone cells survive with S0 and neighbours outside of the board are ignored
+        let corner: HashSet<(i32, i32)> = [(0, 0)].into_iter().collect();
+        assert_eq!(sparse_step(&corner, 10, 10, false, "B1/S0".parse().unwrap()), [(0, 0), (1, 0), (0, 1), (1, 1)].into_iter().collect());
+        assert_eq!(sparse_step(&corner, 10, 10, true, "B1/S0".parse().unwrap()).len(), 9);
+    }
+
+    #[test]
+    fn counts_neighbours_of_packed_cells_across_words() {
+        // A blinker on the border of two words turns over
+        let mut rows = BitRows::new(100, 5);
+        for y in 1..=3 {
+            rows.set(64, y, true);
+        }
+        let next = rows.step(false, Rule::CONWAY);
+        assert_eq!(next.population(), 3);
+        assert!(next.get(63, 2) && next.get(64, 2) && next.get(65, 2));
+        assert_eq!(rows.differences(&next), vec![(64, 1), (63, 2), (65, 2), (64, 3)]);
+
+        // Cells of the last column are neighbours of the first one only with wrap
+        let mut rows = BitRows::new(70, 3);
+        for y in 0..3 {
+            rows.set(69, y, true);
+        }
+        assert!(!rows.step(false, Rule::CONWAY).get(0, 1));
+        assert!(rows.step(true, Rule::CONWAY).get(0, 1));
+    }
+
+    #[test]
+    fn splits_only_large_boards() {
+        assert!(!runs_parallel(100, 100));
+        assert!(runs_parallel(200, 200));
+        assert!(runs_parallel(1000, 50));
+    }
+
+    #[test]
+    fn chooses_engine_by_size() {
+        assert_eq!(Engine::for_size(20, 20), Engine::Dense);
+        assert_eq!(Engine::for_size(320, 320), Engine::Sparse);
+        assert_eq!("sparse".parse(), Ok(Engine::Sparse));
+        assert_eq!("bitwise".parse(), Ok(Engine::Bitwise));
+        assert!("fast".parse::<Engine>().is_err());
+    }
+}
@@ -0,0 +1,79 @@
+// Errors of the game, most of them are told to the player instead of stopping it
+// Errors of tetra are carried along too and the game turns its errors into errors of tetra only where tetra runs it
+use std::io;
+use std::path::PathBuf;
+use tetra::TetraError;
+use thiserror::Error;
+use crate::pattern_io::LoadError;
+
+#[derive(Debug, Error)]
+pub enum GolError {
+    // A pattern file can't be read
+    #[error("Can't load {}: {source}", path.display())]
+    Load {
+        path: PathBuf,
+        source: LoadError,
+    },
+    // A pattern given as text (like the contents of the clipboard) can't be read
+    #[error("Bad pattern: {0}")]
+    Pattern(#[from] LoadError),
+    // A file can't be written
+    #[error("Can't save {}: {source}", path.display())]
+    Save {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The config file can't be read
+    #[error("Can't read the config from {}: {source}", path.display())]
+    Config {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // The system clipboard can't be used
+    #[error("Clipboard is unavailable: {0}")]
+    Clipboard(#[from] arboard::Error),
+    // The window or its graphics failed, which the game can't go on after
+    #[error(transparent)]
+    Tetra(#[from] TetraError),
+}
+
+// Errors of the game are given to tetra as errors of the platform, while errors of tetra are given back as they were
+impl From<GolError> for TetraError {
+    fn from(error: GolError) -> TetraError {
+        match error {
+            GolError::Tetra(error) => error,
+            error => TetraError::PlatformError(error.to_string()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pattern_io::rle::RleError;
+
+    #[test]
+    fn errors_tell_what_failed() {
+        let error = GolError::Load{path: PathBuf::from("saves/gun.rle"), source: LoadError::Rle(RleError::MissingTerminator)};
+        assert_eq!(error.to_string(), "Can't load saves/gun.rle: No '!' at the end of RLE");
+        assert_eq!(GolError::from(LoadError::UnknownFormat).to_string(), "Bad pattern: Unknown pattern format");
+
+        let error = GolError::Save{path: PathBuf::from("screenshots/gol_1.png"), source: io::Error::other("Disk is full")};
+        assert_eq!(error.to_string(), "Can't save screenshots/gol_1.png: Disk is full");
+    }
+
+    #[test]
+    fn errors_turn_into_errors_of_tetra() {
+        let error = GolError::Save{path: PathBuf::from("saves/slot1.json"), source: io::Error::other("Disk is full")};
+        match TetraError::from(error) {
+            TetraError::PlatformError(message) => assert_eq!(message, "Can't save saves/slot1.json: Disk is full"),
+            error => panic!("Unexpected error {:?}", error),
+        }
+
+        // Errors of tetra go through the game unchanged
+        let error = GolError::from(TetraError::NoAudioDevice);
+        assert_eq!(error.to_string(), TetraError::NoAudioDevice.to_string());
+        assert!(matches!(TetraError::from(error), TetraError::NoAudioDevice));
+    }
+}
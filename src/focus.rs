@@ -0,0 +1,64 @@
+// Pausing the game while its window is out of focus
+// Focus can be lost and gained many times in a row (like when windows are switched quickly),
+// so the game is only resumed if it was this pause that stopped it
+
+// What to do with the game when the focus of the window changes
+#[derive(Debug, PartialEq)]
+pub struct FocusPause {
+    // Resume the game when the focus comes back
+    resume: bool,
+    // The game was running when the focus was lost and is paused since then
+    paused: bool,
+}
+
+impl FocusPause {
+    // Constructor for a pause that resumes the game on focus gain if `resume` is set
+    pub fn new(resume: bool) -> FocusPause {
+        FocusPause{resume, paused: false}
+    }
+
+    // Function is called when the window loses focus
+    // Returns `true` if the running game has to be paused
+    pub fn lost(&mut self, running: bool) -> bool {
+        if running {
+            self.paused = true;
+        }
+        running
+    }
+
+    // Function is called when the window gains focus
+    // Returns `true` if the game paused by the focus loss has to be resumed
+    pub fn gained(&mut self) -> bool {
+        let paused = self.paused;
+        self.paused = false;
+        paused && self.resume
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_paused_by_player_is_not_resumed() {
+        let mut focus = FocusPause::new(true);
+        assert!(focus.lost(true));
+        // The game is paused now, so losing focus again changes nothing
+        assert!(!focus.lost(false));
+        assert!(focus.gained());
+        assert!(!focus.gained());
+
+        // The game paused before the focus loss stays paused
+        assert!(!focus.lost(false));
+        assert!(!focus.gained());
+    }
+
+    #[test]
+    fn game_stays_paused_by_default() {
+        let mut focus = FocusPause::new(false);
+        assert!(focus.lost(true));
+        assert!(!focus.gained());
+        assert!(!focus.gained());
+    }
+}
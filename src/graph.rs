@@ -0,0 +1,84 @@
+// Population of the latest generations drawn as a line
+use std::collections::VecDeque;
+
+// Populations of the latest generations, the newest is the last one
+pub struct PopulationGraph {
+    values: VecDeque<usize>,
+    // Number of generations shown at most
+    capacity: usize,
+}
+
+impl PopulationGraph {
+    // Constructor for an empty graph of the given number of generations
+    pub fn new(capacity: usize) -> PopulationGraph {
+        PopulationGraph{values: VecDeque::with_capacity(capacity), capacity}
+    }
+
+    // Function adds the population of the next generation
+    // The oldest generation is forgotten when the graph is full
+    pub fn push(&mut self, population: usize) {
+        if self.values.len() >= self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(population);
+    }
+
+    // Function forgets the newest generation (when it's stepped back from)
+    pub fn pop(&mut self) {
+        self.values.pop_back();
+    }
+
+    // Function forgets all generations
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    // Number of generations in the graph
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    // Does the graph have no generations
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    // Function returns points of the line in an area of the given size with the origin in its upper left corner
+    // Generations go from left to right, and the largest population reaches the top of the area
+    // The line spans the whole width once the graph is full
+    pub fn points(&self, width: f32, height: f32) -> Vec<(f32, f32)> {
+        let max = self.values.iter().max().map_or(1, |max| (*max).max(1)) as f32;
+        let step = width / self.capacity.saturating_sub(1).max(1) as f32;
+        self.values.iter().enumerate()
+            .map(|(i, population)| (i as f32 * step, height - *population as f32 / max * height))
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scales_to_largest_population() {
+        let mut graph = PopulationGraph::new(5);
+        for population in [0, 10, 5] {
+            graph.push(population);
+        }
+        assert_eq!(graph.points(8.0, 20.0), vec![(0.0, 20.0), (2.0, 0.0), (4.0, 10.0)]);
+
+        // The oldest generation is dropped and the line spans the whole width
+        for population in [2, 4, 1] {
+            graph.push(population);
+        }
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.points(8.0, 20.0), vec![(0.0, 0.0), (2.0, 10.0), (4.0, 16.0), (6.0, 12.0), (8.0, 18.0)]);
+
+        // A dead field is drawn along the bottom
+        graph.clear();
+        graph.push(0);
+        graph.push(0);
+        assert_eq!(graph.points(8.0, 20.0), vec![(0.0, 20.0), (2.0, 20.0)]);
+    }
+}
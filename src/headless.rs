@@ -0,0 +1,201 @@
+// Simulation without a window for scripts and benchmarks
+use log::{debug, info, warn};
+use std::fmt;
+use std::io;
+use crate::board::Board;
+use crate::cli::Options;
+use crate::history;
+use crate::pattern_io::{self, LoadError};
+use crate::picture::{self, PictureError};
+use crate::stability::{Detector, Outcome, PeriodFinder};
+use crate::stats::StatsLog;
+
+// How the board ended up after the simulation
+#[derive(Debug, PartialEq)]
+pub struct Report {
+    // Number of generations made
+    pub generations: u32,
+    // Number of alive cells after the last generation
+    pub population: usize,
+    // Generation after which the board stopped changing (if it did)
+    pub stabilized: Option<u32>,
+    // Period of the board after the last generation (if it repeats itself)
+    pub period: Option<usize>,
+}
+
+// A single line of stats like "Gen: 100, alive: 5, stabilized: no"
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gen: {}, alive: {}", self.generations, self.population)?;
+        match self.stabilized {
+            Some(generation) => write!(f, ", stabilized: at gen {}", generation)?,
+            None => write!(f, ", stabilized: no")?,
+        }
+        match self.period {
+            Some(period) => write!(f, ", period: {}", period),
+            None => Ok(()),
+        }
+    }
+}
+
+// Errors that can occur in the headless mode
+#[derive(Debug)]
+pub enum HeadlessError {
+    // The mode needs a pattern or a picture to start with
+    NoPattern,
+    Load(String, LoadError),
+    Picture(String, PictureError),
+    // The stats file can't be written
+    Stats(String, io::Error),
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HeadlessError::NoPattern => write!(f, "Headless mode needs a --pattern or an --image"),
+            HeadlessError::Load(path, e) => write!(f, "Can't load {}: {}", path, e),
+            HeadlessError::Picture(path, e) => write!(f, "Can't load {}: {}", path, e),
+            HeadlessError::Stats(path, e) => write!(f, "Can't write stats to {}: {}", path, e),
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {}
+
+// Function applies the rules to the board `generations` times
+// Generations after the board stops changing are skipped, as they change nothing
+pub fn simulate(board: &mut Board, generations: u32) -> Report {
+    match simulate_with(board, generations, |_, _| Ok(())) {
+        Ok(report) => report,
+        Err(_) => unreachable!("Nothing is written without stats"),
+    }
+}
+
+// Function applies the rules to the board like `simulate` and calls `after_step` after every generation
+fn simulate_with<F>(board: &mut Board, generations: u32, mut after_step: F) -> io::Result<Report>
+where F: FnMut(u32, &Board) -> io::Result<()> {
+    let mut detector = Detector::new(false);
+    let mut periods = PeriodFinder::new();
+    let mut period = None;
+    let mut stabilized = None;
+    // Cells before every generation are copied to the same buffer
+    let mut before = Vec::new();
+    for generation in 1..=generations {
+        history::copy_into(&mut before, board.cells());
+        let (births, deaths) = board.step();
+        debug!("Gen {}: {} cells, +{} / -{}", generation, board.population(), births, deaths);
+        after_step(generation, board)?;
+        // Noise keeps changing boards, so they don't settle down or repeat themselves for good
+        if board.noise() > 0.0 {
+            continue;
+        }
+        period = periods.check(&before, board.cells());
+        if let Some(Outcome::Extinct | Outcome::Stabilized) = detector.check(&before, board) {
+            stabilized = Some(generation);
+            break;
+        }
+    }
+    Ok(Report{generations, population: board.population(), stabilized, period})
+}
+
+// Function loads the pattern (or the picture) from options, makes the given number of generations
+// and returns the board as the contents of an RLE file (or as ASCII art) with the report
+pub fn run(options: &Options) -> Result<(String, Report), HeadlessError> {
+    let mut board = Board::new(options.cols, options.rows);
+    board.set_wrap(options.wrap);
+    board.set_rule(options.rule);
+    board.set_automaton(options.automaton);
+    board.set_capture(options.capture);
+    board.set_neighbourhood(options.neighbourhood);
+    board.set_engine(options.engine);
+    // Runs with noise are repeated by giving them the same seed
+    let seed = options.seed.unwrap_or_else(rand::random);
+    board.set_noise(options.noise, seed);
+    match board.noise() > 0.0 {
+        true => info!("Noise p={} with --seed {}", options.noise, seed),
+        false if options.noise > 0.0 => warn!("Noise doesn't apply to {} and is ignored", board.automaton()),
+        false => (),
+    }
+    match (&options.pattern, &options.image) {
+        (Some(path), _) => {
+            let pattern = pattern_io::load(path).map_err(|e| HeadlessError::Load(path.clone(), e))?;
+            info!("Loaded {}", path);
+            pattern_io::stamp_centered(&mut board, &pattern);
+        },
+        (None, Some(path)) => {
+            let cells = picture::load(path, options.cols, options.rows, options.threshold)
+                .map_err(|e| HeadlessError::Picture(path.clone(), e))?;
+            info!("Loaded {}", path);
+            board.stamp(0, 0, &cells);
+        },
+        (None, None) => return Err(HeadlessError::NoPattern),
+    }
+
+    let report = match &options.stats {
+        Some(path) => {
+            let error = |e| HeadlessError::Stats(path.clone(), e);
+            let mut stats = StatsLog::append(path).map_err(error)?;
+            let report = simulate_with(&mut board, options.generations, |generation, board| stats.record(generation, board))
+                .map_err(error)?;
+            stats.flush().map_err(error)?;
+            report
+        },
+        None => simulate(&mut board, options.generations),
+    };
+    let text = match options.dump_ascii {
+        true => pattern_io::ascii_dump(&board, options.full_field),
+        false => pattern_io::board_rle(&board),
+    };
+    Ok((text, report))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn reports_still_lifes_and_oscillators() {
+        let mut board = Board::new(10, 10);
+        board.stamp(4, 4, &[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let report = simulate(&mut board, 100);
+        assert_eq!(report, Report{generations: 100, population: 4, stabilized: Some(1), period: Some(1)});
+
+        let mut board = Board::new(10, 10);
+        board.stamp(4, 4, patterns::BLINKER.cells);
+        let report = simulate(&mut board, 100);
+        assert_eq!(report, Report{generations: 100, population: 3, stabilized: None, period: Some(2)});
+        assert_eq!(report.to_string(), "Gen: 100, alive: 3, stabilized: no, period: 2");
+    }
+
+    #[test]
+    fn glider_stops_in_the_corner_of_bounded_board() {
+        let mut board = Board::new(10, 10);
+        board.stamp(0, 0, patterns::GLIDER.cells);
+        let report = simulate(&mut board, 100);
+        // The glider turns into a block in the lower right corner
+        assert_eq!(report.population, 4);
+        assert!(report.stabilized.is_some());
+        assert_eq!(board.alive_cells(), vec![(8, 8), (9, 8), (8, 9), (9, 9)]);
+    }
+
+    #[test]
+    fn noisy_runs_repeat_with_the_same_seed() {
+        let path = std::env::temp_dir().join(format!("game_of_life_noise_{}.rle", std::process::id()));
+        std::fs::write(&path, "x = 2, y = 2, rule = B3/S23\n2o$2o!\n").unwrap();
+        let options = |seed| Options{headless: true, pattern: Some(path.display().to_string()), noise: 0.01, seed: Some(seed), ..Options::default()};
+        let (text, report) = run(&options(42)).unwrap();
+        // The block would stabilize right away without noise
+        assert_eq!(report.stabilized, None);
+        assert_eq!(run(&options(42)).unwrap(), (text.clone(), report));
+        assert_ne!(run(&options(43)).unwrap().0, text);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn needs_a_pattern() {
+        let options = Options{headless: true, ..Options::default()};
+        assert!(matches!(run(&options), Err(HeadlessError::NoPattern)));
+    }
+}
@@ -0,0 +1,109 @@
+// Previous generations of the board that the game can step back to
+use std::collections::VecDeque;
+
+// Rolling list of the latest states of cells
+// Every state is a copy of the cells of the board (see `Board::cells`)
+pub struct History {
+    // The oldest states are dropped first when there are too many of them
+    states: VecDeque<Vec<u8>>,
+    // Maximal number of states that are kept
+    capacity: usize,
+}
+
+impl History {
+    // Constructor for an empty history
+    pub fn new(capacity: usize) -> History {
+        History{states: VecDeque::new(), capacity}
+    }
+
+    // Function remembers the state of cells before a generation
+    // Once the history is full the copy of the oldest state is reused, so remembering a state allocates nothing
+    pub fn push(&mut self, cells: &[u8]) {
+        if self.capacity == 0 {
+            return;
+        }
+        let state = match self.states.len() == self.capacity {
+            true => self.states.pop_front().map(|mut oldest| {
+                copy_into(&mut oldest, cells);
+                oldest
+            }),
+            false => None,
+        };
+        self.states.push_back(state.unwrap_or_else(|| cells.to_vec()));
+    }
+
+    // Function takes the latest state out of the history
+    // It is forgotten, so stepping forward from it makes a new history
+    pub fn pop(&mut self) -> Option<Vec<u8>> {
+        self.states.pop_back()
+    }
+
+    // Number of generations the game can step back
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    // Function forgets all states
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+}
+
+// Function copies the cells into the buffer, which only allocates if the buffer is shorter than the cells
+pub fn copy_into(buffer: &mut Vec<u8>, cells: &[u8]) {
+    match buffer.len() == cells.len() {
+        true => buffer.copy_from_slice(cells),
+        false => {
+            buffer.clear();
+            buffer.extend_from_slice(cells);
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn steps_back_to_previous_generations() {
+        let mut board = Board::new(5, 5);
+        board.stamp(1, 2, &[(0, 0), (1, 0), (2, 0)]);
+        let start = board.cells().to_vec();
+        let mut history = History::new(100);
+        for _ in 0..3 {
+            history.push(board.cells());
+            board.step();
+        }
+
+        // A blinker has period 2, so its phases alternate on the way back
+        assert_eq!(history.len(), 3);
+        board.restore(&history.pop().unwrap());
+        assert_eq!(board.cells(), &start[..]);
+        board.restore(&history.pop().unwrap());
+        assert_ne!(board.cells(), &start[..]);
+        board.restore(&history.pop().unwrap());
+        assert_eq!(board.cells(), &start[..]);
+        assert!(history.pop().is_none());
+    }
+
+    #[test]
+    fn keeps_only_latest_states() {
+        let mut history = History::new(2);
+        for state in [1, 0, 1] {
+            history.push(&[state]);
+        }
+        assert_eq!(history.pop(), Some(vec![1]));
+        assert_eq!(history.pop(), Some(vec![0]));
+        assert_eq!(history.pop(), None);
+
+        let mut disabled = History::new(0);
+        disabled.push(&[1]);
+        assert!(disabled.is_empty());
+    }
+}
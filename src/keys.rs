@@ -0,0 +1,466 @@
+// Keys of the game: the actions keys do, their built-in keys and the keys of the config file bound instead
+// The names of keys in the help overlay are the ones of the config file
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use tetra::input::{Key, KeyModifier};
+use crate::config::Keys;
+
+// What a key of the keyboard does when pressed
+// Actions are named in the config file as they are here
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum KeyAction {
+    ToggleHelp,
+    SelectPattern,
+    SelectRule,
+    CycleNeighbourhood,
+    Undo,
+    Redo,
+    StartPause,
+    ToggleTurbo,
+    Reset,
+    Step,
+    StepBack,
+    GoTo,
+    RunUntilStable,
+    SpeedUp,
+    SlowDown,
+    Coarser,
+    Finer,
+    Clear,
+    Wipe,
+    RandomizeNewSeed,
+    Randomize,
+    FlipHorizontally,
+    FlipVertically,
+    TogglePeriodTwo,
+    ToggleAutoPause,
+    ToggleEditPause,
+    ToggleAgeColors,
+    ToggleBirthColors,
+    ToggleLookahead,
+    ToggleNeighbours,
+    ToggleTrails,
+    ToggleWrap,
+    ToggleCapture,
+    ToggleGrid,
+    ToggleBoundingBox,
+    NextTheme,
+    PanHorizontal,
+    PanVertical,
+    ResetView,
+    FitView,
+    MoveCursor,
+    PaintWithCursor,
+    ToggleCursorCell,
+    Copy,
+    Cut,
+    Paste,
+    CopyRle,
+    PasteRle,
+    SaveSlot,
+    LoadSlot,
+    Browse,
+    BrowseRecent,
+    Load,
+    Save,
+    Screenshot,
+    DumpAsciiField,
+    DumpAscii,
+    ToggleRecording,
+    ToggleStats,
+    ToggleLineTool,
+    ToggleRectangleTool,
+    CycleSymmetry,
+    CycleBrushSize,
+    Cancel,
+    Quit,
+}
+
+
+impl KeyAction {
+    // Function checks if the action is done while the help overlay is shown
+    pub fn works_over_help(self) -> bool {
+        matches!(self, KeyAction::ToggleHelp | KeyAction::Cancel | KeyAction::Quit)
+    }
+}
+
+
+// Keys bound to a single action
+#[derive(Clone, Debug)]
+pub struct KeyBinding {
+    // The action gets the position of the pressed key among these keys
+    pub keys: &'static [Key],
+    // Modifiers that have to be held down with a key
+    pub modifiers: &'static [KeyModifier],
+    pub action: KeyAction,
+    // Description of the action in the help overlay
+    pub description: &'static str,
+}
+
+
+// All key bindings of the game in the order they are listed in the help overlay
+// A key held with modifiers only does the first action it is bound to,
+// so bindings with more modifiers are handled before the ones with fewer of them
+pub const KEY_BINDINGS: &[KeyBinding] = &[
+    KeyBinding{keys: &[Key::H], modifiers: &[], action: KeyAction::ToggleHelp, description: "Show or hide this help"},
+    // F1 loads the first slot, so the help is shown with Shift+F1 too
+    KeyBinding{keys: &[Key::F1], modifiers: &[KeyModifier::Shift], action: KeyAction::ToggleHelp, description: "Show or hide this help"},
+    KeyBinding{keys: &[Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5], modifiers: &[], action: KeyAction::SelectPattern, description: "Select a pattern to stamp with a click"},
+    KeyBinding{keys: &[Key::Num1, Key::Num2, Key::Num3, Key::Num4], modifiers: &[KeyModifier::Alt], action: KeyAction::SelectRule, description: "Life, HighLife, Seeds, Day & Night"},
+    KeyBinding{keys: &[Key::K], modifiers: &[], action: KeyAction::CycleNeighbourhood, description: "Change the neighbourhood of cells"},
+    KeyBinding{keys: &[Key::Z], modifiers: &[KeyModifier::Ctrl], action: KeyAction::Undo, description: "Undo the last edit"},
+    KeyBinding{keys: &[Key::Y], modifiers: &[KeyModifier::Ctrl], action: KeyAction::Redo, description: "Redo the last undone edit"},
+    KeyBinding{keys: &[Key::Space], modifiers: &[KeyModifier::Shift], action: KeyAction::ToggleTurbo, description: "Make many generations at a time"},
+    KeyBinding{keys: &[Key::Space], modifiers: &[], action: KeyAction::StartPause, description: "Start or pause the game"},
+    KeyBinding{keys: &[Key::Backspace], modifiers: &[], action: KeyAction::Reset, description: "Reset to the cells of the last start"},
+    KeyBinding{keys: &[Key::N, Key::Right], modifiers: &[], action: KeyAction::Step, description: "Make a single step when paused"},
+    KeyBinding{keys: &[Key::Left], modifiers: &[], action: KeyAction::StepBack, description: "Step back a generation when paused"},
+    KeyBinding{keys: &[Key::J], modifiers: &[], action: KeyAction::GoTo, description: "Go to a generation by its number"},
+    KeyBinding{keys: &[Key::U], modifiers: &[], action: KeyAction::RunUntilStable, description: "Run until the field stabilizes"},
+    KeyBinding{keys: &[Key::Equals, Key::NumPadPlus], modifiers: &[], action: KeyAction::SpeedUp, description: "Speed up the game"},
+    KeyBinding{keys: &[Key::Minus, Key::NumPadMinus], modifiers: &[], action: KeyAction::SlowDown, description: "Slow down the game"},
+    KeyBinding{keys: &[Key::LeftBracket], modifiers: &[], action: KeyAction::Coarser, description: "Make the grid coarser"},
+    KeyBinding{keys: &[Key::RightBracket], modifiers: &[], action: KeyAction::Finer, description: "Make the grid finer"},
+    KeyBinding{keys: &[Key::C], modifiers: &[KeyModifier::Shift], action: KeyAction::Wipe, description: "Clear the field and remove walls"},
+    KeyBinding{keys: &[Key::C], modifiers: &[], action: KeyAction::Clear, description: "Clear the field (walls are kept)"},
+    KeyBinding{keys: &[Key::R], modifiers: &[KeyModifier::Shift], action: KeyAction::RandomizeNewSeed, description: "Fill randomly with a new seed"},
+    KeyBinding{keys: &[Key::R], modifiers: &[], action: KeyAction::Randomize, description: "Fill randomly with the same seed or rotate the stamp"},
+    KeyBinding{keys: &[Key::F], modifiers: &[], action: KeyAction::FlipHorizontally, description: "Flip the stamp horizontally"},
+    KeyBinding{keys: &[Key::V], modifiers: &[], action: KeyAction::FlipVertically, description: "Flip the stamp vertically"},
+    KeyBinding{keys: &[Key::L], modifiers: &[], action: KeyAction::ToggleLineTool, description: "Draw lines between two clicks"},
+    KeyBinding{keys: &[Key::B], modifiers: &[KeyModifier::Shift], action: KeyAction::ToggleRectangleTool, description: "Draw rectangles by dragging"},
+    KeyBinding{keys: &[Key::B], modifiers: &[], action: KeyAction::CycleBrushSize, description: "Change the size of the brush"},
+    KeyBinding{keys: &[Key::M], modifiers: &[], action: KeyAction::CycleSymmetry, description: "Change the symmetry of painting"},
+    KeyBinding{keys: &[Key::A], modifiers: &[KeyModifier::Shift], action: KeyAction::TogglePeriodTwo, description: "Auto-pause on period 2 oscillators"},
+    KeyBinding{keys: &[Key::A], modifiers: &[], action: KeyAction::ToggleAutoPause, description: "Toggle auto-pause"},
+    KeyBinding{keys: &[Key::E], modifiers: &[], action: KeyAction::ToggleEditPause, description: "Toggle pausing on clicks"},
+    KeyBinding{keys: &[Key::F6], modifiers: &[], action: KeyAction::ToggleAgeColors, description: "Toggle age colors"},
+    KeyBinding{keys: &[Key::F6], modifiers: &[KeyModifier::Shift], action: KeyAction::ToggleBirthColors, description: "Toggle colors of newborn cells"},
+    KeyBinding{keys: &[Key::P], modifiers: &[], action: KeyAction::ToggleLookahead, description: "Preview the next generation when paused"},
+    KeyBinding{keys: &[Key::D], modifiers: &[], action: KeyAction::ToggleNeighbours, description: "Show numbers of alive neighbours"},
+    KeyBinding{keys: &[Key::F7], modifiers: &[], action: KeyAction::ToggleTrails, description: "Toggle trails of dead cells"},
+    KeyBinding{keys: &[Key::W], modifiers: &[], action: KeyAction::ToggleWrap, description: "Toggle wrapping of the edges"},
+    KeyBinding{keys: &[Key::I], modifiers: &[], action: KeyAction::ToggleCapture, description: "Toggle captures of Immigration cells"},
+    KeyBinding{keys: &[Key::G], modifiers: &[], action: KeyAction::ToggleGrid, description: "Toggle grid lines"},
+    KeyBinding{keys: &[Key::X], modifiers: &[], action: KeyAction::ToggleBoundingBox, description: "Toggle the bounding box of alive cells"},
+    KeyBinding{keys: &[Key::T], modifiers: &[], action: KeyAction::NextTheme, description: "Switch the color theme"},
+    KeyBinding{keys: &[Key::Left, Key::Right], modifiers: &[KeyModifier::Shift], action: KeyAction::PanHorizontal, description: "Move the view left or right"},
+    KeyBinding{keys: &[Key::Up, Key::Down], modifiers: &[KeyModifier::Shift], action: KeyAction::PanVertical, description: "Move the view up or down"},
+    KeyBinding{keys: &[Key::Z], modifiers: &[], action: KeyAction::FitView, description: "Fit the view to alive cells"},
+    KeyBinding{keys: &[Key::Left, Key::Right, Key::Up, Key::Down], modifiers: &[KeyModifier::Ctrl, KeyModifier::Shift], action: KeyAction::PaintWithCursor, description: "Move the keyboard cursor painting cells"},
+    KeyBinding{keys: &[Key::Left, Key::Right, Key::Up, Key::Down], modifiers: &[KeyModifier::Ctrl], action: KeyAction::MoveCursor, description: "Move the keyboard cursor"},
+    KeyBinding{keys: &[Key::Enter], modifiers: &[], action: KeyAction::ToggleCursorCell, description: "Toggle the cell under the keyboard cursor"},
+    KeyBinding{keys: &[Key::Home], modifiers: &[], action: KeyAction::ResetView, description: "Show the whole field"},
+    KeyBinding{keys: &[Key::C], modifiers: &[KeyModifier::Ctrl, KeyModifier::Shift], action: KeyAction::CopyRle, description: "Copy the field as RLE text"},
+    KeyBinding{keys: &[Key::V], modifiers: &[KeyModifier::Ctrl, KeyModifier::Shift], action: KeyAction::PasteRle, description: "Paste RLE text with a click"},
+    KeyBinding{keys: &[Key::C], modifiers: &[KeyModifier::Ctrl], action: KeyAction::Copy, description: "Copy the selected cells"},
+    KeyBinding{keys: &[Key::X], modifiers: &[KeyModifier::Ctrl], action: KeyAction::Cut, description: "Copy and clear the selected cells"},
+    KeyBinding{keys: &[Key::V], modifiers: &[KeyModifier::Ctrl], action: KeyAction::Paste, description: "Paste the copied cells with a click"},
+    KeyBinding{keys: &[Key::F1, Key::F2, Key::F3, Key::F4, Key::F5], modifiers: &[KeyModifier::Ctrl], action: KeyAction::SaveSlot, description: "Save the field to a slot"},
+    KeyBinding{keys: &[Key::F1, Key::F2, Key::F3, Key::F4, Key::F5], modifiers: &[], action: KeyAction::LoadSlot, description: "Load the field from a slot"},
+    KeyBinding{keys: &[Key::O], modifiers: &[KeyModifier::Ctrl], action: KeyAction::BrowseRecent, description: "Pick one of the recently loaded patterns"},
+    KeyBinding{keys: &[Key::O], modifiers: &[KeyModifier::Shift], action: KeyAction::Load, description: "Load the pattern file"},
+    KeyBinding{keys: &[Key::O], modifiers: &[], action: KeyAction::Browse, description: "Pick a pattern from the patterns directory"},
+    KeyBinding{keys: &[Key::S], modifiers: &[], action: KeyAction::Save, description: "Save the field to a file"},
+    KeyBinding{keys: &[Key::F12], modifiers: &[], action: KeyAction::Screenshot, description: "Save a picture of the field"},
+    KeyBinding{keys: &[Key::E], modifiers: &[KeyModifier::Ctrl, KeyModifier::Shift], action: KeyAction::DumpAsciiField, description: "Save the whole field as ASCII art"},
+    KeyBinding{keys: &[Key::E], modifiers: &[KeyModifier::Ctrl], action: KeyAction::DumpAscii, description: "Save alive cells as ASCII art"},
+    KeyBinding{keys: &[Key::F9], modifiers: &[], action: KeyAction::ToggleRecording, description: "Start or stop recording a GIF"},
+    KeyBinding{keys: &[Key::F8], modifiers: &[], action: KeyAction::ToggleStats, description: "Start or stop writing stats to CSV"},
+    KeyBinding{keys: &[Key::Escape], modifiers: &[], action: KeyAction::Cancel, description: "Cancel the selection, stamp or shape, or quit"},
+    KeyBinding{keys: &[Key::Q], modifiers: &[], action: KeyAction::Quit, description: "Quit the game"},
+];
+
+
+// Function finds the actions of all keys pressed since the last update
+// `modifier_down` tells if a modifier is held down and `key_pressed` if a key was pressed
+// Returns every action with the position of its pressed key among the keys of the binding
+pub fn pressed_actions<D: Fn(KeyModifier) -> bool, P: Fn(Key) -> bool>(bindings: &[KeyBinding], modifier_down: D, key_pressed: P) -> Vec<(KeyAction, usize)> {
+    let mut handled = Vec::new();
+    let mut actions = Vec::new();
+    let mut bindings: Vec<&KeyBinding> = bindings.iter().collect();
+    bindings.sort_by_key(|binding| Reverse(binding.modifiers.len()));
+    for binding in bindings {
+        if !binding.modifiers.iter().all(|modifier| modifier_down(*modifier)) {
+            continue;
+        }
+        for (index, key) in binding.keys.iter().enumerate() {
+            if key_pressed(*key) && !handled.contains(key) {
+                handled.push(*key);
+                actions.push((binding.action, index));
+            }
+        }
+    }
+    actions
+}
+
+// Names of keys shown in the help overlay and used in the config file
+pub const KEY_NAMES: &[(Key, &str)] = &[
+    (Key::Num1, "1"),
+    (Key::Num2, "2"),
+    (Key::Num3, "3"),
+    (Key::Num4, "4"),
+    (Key::Num5, "5"),
+    (Key::Num6, "6"),
+    (Key::Num7, "7"),
+    (Key::Num8, "8"),
+    (Key::Num9, "9"),
+    (Key::Num0, "0"),
+    (Key::A, "A"),
+    (Key::B, "B"),
+    (Key::C, "C"),
+    (Key::D, "D"),
+    (Key::E, "E"),
+    (Key::F, "F"),
+    (Key::G, "G"),
+    (Key::H, "H"),
+    (Key::I, "I"),
+    (Key::J, "J"),
+    (Key::K, "K"),
+    (Key::L, "L"),
+    (Key::M, "M"),
+    (Key::N, "N"),
+    (Key::O, "O"),
+    (Key::P, "P"),
+    (Key::Q, "Q"),
+    (Key::R, "R"),
+    (Key::S, "S"),
+    (Key::T, "T"),
+    (Key::U, "U"),
+    (Key::V, "V"),
+    (Key::W, "W"),
+    (Key::X, "X"),
+    (Key::Y, "Y"),
+    (Key::Z, "Z"),
+    (Key::F1, "F1"),
+    (Key::F2, "F2"),
+    (Key::F3, "F3"),
+    (Key::F4, "F4"),
+    (Key::F5, "F5"),
+    (Key::F6, "F6"),
+    (Key::F7, "F7"),
+    (Key::F8, "F8"),
+    (Key::F9, "F9"),
+    (Key::F12, "F12"),
+    (Key::Space, "Space"),
+    (Key::Backspace, "Backspace"),
+    (Key::Escape, "Esc"),
+    (Key::Left, "←"),
+    (Key::Right, "→"),
+    (Key::Enter, "Enter"),
+    (Key::Up, "↑"),
+    (Key::Down, "↓"),
+    (Key::Home, "Home"),
+    (Key::Equals, "+"),
+    (Key::Minus, "-"),
+    (Key::NumPadPlus, "Num+"),
+    (Key::NumPadMinus, "Num-"),
+    (Key::LeftBracket, "["),
+    (Key::RightBracket, "]"),
+];
+
+// Sets of modifiers keys can be bound with in the config file
+const MODIFIER_SETS: &[&[KeyModifier]] = &[
+    &[],
+    &[KeyModifier::Ctrl],
+    &[KeyModifier::Shift],
+    &[KeyModifier::Alt],
+    &[KeyModifier::Ctrl, KeyModifier::Shift],
+    &[KeyModifier::Ctrl, KeyModifier::Alt],
+    &[KeyModifier::Alt, KeyModifier::Shift],
+    &[KeyModifier::Ctrl, KeyModifier::Alt, KeyModifier::Shift],
+];
+
+// Function returns the name of a key shown in the help overlay
+pub fn key_name(key: Key) -> &'static str {
+    KEY_NAMES.iter().find(|(named, _)| *named == key).map_or("?", |(_, name)| name)
+}
+
+// Function reads keys like "Ctrl+Shift+E" (in any case) as the key and its modifiers
+// The key is returned as a slice of the table of names to be bound like the built-in keys
+pub fn parse_keys(text: &str) -> Option<(&'static [Key], &'static [KeyModifier])> {
+    let text = text.trim();
+    // The plus key is named "+" itself, so it can't be split off by the last plus
+    let (modifiers, key) = match text.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None if text == "+" => ("", "+"),
+        None => text.rsplit_once('+').unwrap_or(("", text)),
+    };
+    let key = KEY_NAMES.iter().find(|(_, name)| name.eq_ignore_ascii_case(key.trim()))?;
+    let mut held = Vec::new();
+    for modifier in modifiers.split('+').map(|modifier| modifier.trim()).filter(|modifier| !modifier.is_empty()) {
+        let modifier = [KeyModifier::Ctrl, KeyModifier::Alt, KeyModifier::Shift].into_iter()
+            .find(|held| held.to_string().eq_ignore_ascii_case(modifier))?;
+        held.push(modifier);
+    }
+    let set = MODIFIER_SETS.iter()
+        .find(|set| set.len() == held.len() && held.iter().all(|modifier| set.contains(modifier)))?;
+    Some((std::slice::from_ref(&key.0), set))
+}
+
+// Function writes the keys of a binding of a single key like "Ctrl+Shift+E"
+fn keys_text(binding: &KeyBinding) -> String {
+    let keys = binding.keys.iter().map(|key| key_name(*key)).collect::<Vec<_>>().join("/");
+    binding.modifiers.iter().rev().fold(keys, |keys, modifier| format!("{}+{}", modifier, keys))
+}
+
+// Function lists the keys of all actions bound to a single key, as they are written to the config file
+// Actions with several bindings of a single key (like the help) get a list of their keys
+pub fn default_keys() -> BTreeMap<String, Keys> {
+    let mut keys = BTreeMap::new();
+    for binding in KEY_BINDINGS.iter().filter(|binding| binding.keys.len() == 1) {
+        let action = format!("{:?}", binding.action);
+        let list = match keys.remove(&action) {
+            Some(Keys::One(first)) => Keys::Several(vec![first, keys_text(binding)]),
+            Some(Keys::Several(mut list)) => {
+                list.push(keys_text(binding));
+                Keys::Several(list)
+            },
+            None => Keys::One(keys_text(binding)),
+        };
+        keys.insert(action, list);
+    }
+    keys
+}
+
+// Function checks if the binding has the key with the same modifiers
+fn is_bound(binding: &KeyBinding, key: Key, modifiers: &[KeyModifier]) -> bool {
+    binding.keys.contains(&key)
+        && binding.modifiers.len() == modifiers.len()
+        && modifiers.iter().all(|modifier| binding.modifiers.contains(modifier))
+}
+
+// Function binds actions to the keys of the config file instead of their built-in keys
+// An action can have several keys, but a key can't do two actions, so keys taken by other actions are skipped
+// Actions of several built-in keys (like the arrows) keep them, as do actions without any good keys
+// Returns the bindings in the order of the built-in ones with warnings about skipped keys
+pub fn key_bindings(keys: &BTreeMap<String, Keys>) -> (Vec<KeyBinding>, Vec<String>) {
+    let mut warnings = Vec::new();
+    // Positions of the built-in bindings of rebound actions and their new bindings
+    let mut rebound = Vec::new();
+    let mut new_bindings = Vec::new();
+    for (action, list) in keys {
+        let position = KEY_BINDINGS.iter().position(|binding| format!("{:?}", binding.action) == *action);
+        match position {
+            None => warnings.push(format!("Unknown action {}", action)),
+            Some(position) if KEY_BINDINGS[position].keys.len() > 1 => warnings.push(format!("{} has several keys and can't be bound", action)),
+            Some(position) => {
+                let mut bindings = Vec::new();
+                for text in list.list() {
+                    match parse_keys(text) {
+                        Some((key, modifiers)) => bindings.push(KeyBinding{keys: key, modifiers, ..KEY_BINDINGS[position].clone()}),
+                        None => warnings.push(format!("Unknown keys {} of {}", text, action)),
+                    }
+                }
+                // All built-in bindings of the action are replaced
+                if !bindings.is_empty() {
+                    let action = KEY_BINDINGS[position].action;
+                    rebound.extend(KEY_BINDINGS.iter().enumerate().filter(|(_, binding)| binding.action == action).map(|(position, _)| position));
+                    new_bindings.extend(bindings);
+                }
+            },
+        }
+    }
+
+    // Built-in keys of rebound actions are free, so two actions can swap their keys
+    let mut result: Vec<KeyBinding> = KEY_BINDINGS.iter().enumerate()
+        .filter(|(position, _)| !rebound.contains(position))
+        .map(|(_, binding)| binding.clone())
+        .collect();
+    for binding in new_bindings {
+        match result.iter().find(|taken| is_bound(taken, binding.keys[0], binding.modifiers)) {
+            Some(taken) => warnings.push(format!("{} of {:?} is taken by {:?}", keys_text(&binding), binding.action, taken.action)),
+            None => result.push(binding),
+        }
+    }
+    // Bindings are listed in the help in the order of the built-in ones
+    let order = |binding: &KeyBinding| KEY_BINDINGS.iter().position(|built_in| built_in.action == binding.action);
+    result.sort_by_key(order);
+    (result, warnings)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Function binds the keys of the config file given as actions with lists of their keys
+    fn bound(keys: &[(&str, &[&str])]) -> (Vec<KeyBinding>, Vec<String>) {
+        let keys = keys.iter()
+            .map(|(action, list)| (action.to_string(), Keys::Several(list.iter().map(|key| key.to_string()).collect())))
+            .collect();
+        key_bindings(&keys)
+    }
+
+    // Actions done when the key is pressed with the modifiers held down
+    fn pressed(bindings: &[KeyBinding], key: Key, modifiers: &[KeyModifier]) -> Vec<(KeyAction, usize)> {
+        pressed_actions(bindings, |modifier| modifiers.contains(&modifier), |pressed| pressed == key)
+    }
+
+    // Actions, keys and modifiers of the bindings
+    fn summary(bindings: &[KeyBinding]) -> Vec<(KeyAction, Vec<Key>, Vec<KeyModifier>)> {
+        bindings.iter().map(|binding| (binding.action, binding.keys.to_vec(), binding.modifiers.to_vec())).collect()
+    }
+
+    #[test]
+    fn bound_keys_have_names() {
+        // Keys without a name are shown as "?" in the help and can't be written to the config file
+        for binding in KEY_BINDINGS {
+            for key in binding.keys {
+                assert_ne!(key_name(*key), "?", "{:?} of {:?} has no name", key, binding.action);
+            }
+        }
+    }
+
+    #[test]
+    fn default_keys_survive_round_trip() {
+        let keys = default_keys();
+        for binding in KEY_BINDINGS.iter().filter(|binding| binding.keys.len() == 1) {
+            assert!(keys.contains_key(&format!("{:?}", binding.action)), "{:?} isn't written", binding.action);
+        }
+        for (action, list) in &keys {
+            for text in list.list() {
+                let (key, modifiers) = parse_keys(text).unwrap_or_else(|| panic!("Can't read {} of {}", text, action));
+                let built_in = KEY_BINDINGS.iter().any(|binding| format!("{:?}", binding.action) == *action && is_bound(binding, key[0], modifiers));
+                assert!(built_in, "{} isn't a key of {}", text, action);
+            }
+        }
+        // Binding the written keys gives the built-in bindings back
+        let (bindings, warnings) = key_bindings(&keys);
+        assert_eq!(warnings, Vec::<String>::new());
+        assert_eq!(summary(&bindings), summary(KEY_BINDINGS));
+    }
+
+    #[test]
+    fn bad_keys_are_skipped() {
+        // Unknown keys are skipped, while the other keys of the action are bound
+        let (bindings, warnings) = bound(&[("ToggleGrid", &["Ctrl+Foo", "ctrl+g"]), ("Fly", &["F"])]);
+        assert_eq!(warnings, vec!["Unknown action Fly", "Unknown keys Ctrl+Foo of ToggleGrid"]);
+        assert_eq!(pressed(&bindings, Key::G, &[KeyModifier::Ctrl]), vec![(KeyAction::ToggleGrid, 0)]);
+        assert_eq!(pressed(&bindings, Key::G, &[]), Vec::new());
+
+        // A key can't do two actions, so a key taken by another action is rejected
+        let (bindings, warnings) = bound(&[("ToggleGrid", &["Q"])]);
+        assert_eq!(warnings, vec!["Q of ToggleGrid is taken by Quit"]);
+        assert_eq!(pressed(&bindings, Key::Q, &[]), vec![(KeyAction::Quit, 0)]);
+    }
+
+    #[test]
+    fn actions_can_have_several_keys() {
+        let (bindings, warnings) = bound(&[("ToggleGrid", &["G", "Shift+G"])]);
+        assert!(warnings.is_empty());
+        assert_eq!(pressed(&bindings, Key::G, &[]), vec![(KeyAction::ToggleGrid, 0)]);
+        assert_eq!(pressed(&bindings, Key::G, &[KeyModifier::Shift]), vec![(KeyAction::ToggleGrid, 0)]);
+        // Both built-in keys of the help work too
+        assert_eq!(pressed(KEY_BINDINGS, Key::H, &[]), vec![(KeyAction::ToggleHelp, 0)]);
+        assert_eq!(pressed(KEY_BINDINGS, Key::F1, &[KeyModifier::Shift]), vec![(KeyAction::ToggleHelp, 0)]);
+    }
+
+    #[test]
+    fn bindings_with_more_modifiers_win() {
+        let ctrl_shift = [KeyModifier::Ctrl, KeyModifier::Shift];
+        assert_eq!(pressed(KEY_BINDINGS, Key::Up, &ctrl_shift), vec![(KeyAction::PaintWithCursor, 2)]);
+        assert_eq!(pressed(KEY_BINDINGS, Key::Up, &[KeyModifier::Ctrl]), vec![(KeyAction::MoveCursor, 2)]);
+        assert_eq!(pressed(KEY_BINDINGS, Key::Up, &[KeyModifier::Shift]), vec![(KeyAction::PanVertical, 0)]);
+    }
+}
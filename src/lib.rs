@@ -0,0 +1,34 @@
+// Game logic that doesn't depend on the window
+pub mod ant;
+pub mod automaton;
+pub mod board;
+pub mod cli;
+pub mod config;
+pub mod engine;
+pub mod error;
+pub mod focus;
+pub mod graph;
+pub mod headless;
+pub mod history;
+pub mod keys;
+pub mod library;
+pub mod neighbourhood;
+pub mod pattern_io;
+pub mod patterns;
+pub mod picture;
+pub mod recent;
+pub mod recording;
+pub mod render;
+pub mod rule;
+pub mod selection;
+pub mod session;
+pub mod shapes;
+pub mod slots;
+pub mod soup;
+pub mod stability;
+pub mod stats;
+pub mod symmetry;
+pub mod theme;
+pub mod undo;
+pub mod versus;
+pub mod view;
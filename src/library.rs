@@ -0,0 +1,158 @@
+// Pattern files of a directory listed to be picked from by typing a part of their names
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::pattern_io;
+
+// Extensions of the files that are listed
+pub const EXTENSIONS: [&str; 2] = ["rle", "cells"];
+
+// A pattern file of the directory
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    // Name of the file without the directory
+    pub name: String,
+    pub path: PathBuf,
+    // Width and height of the pattern (if they can be read)
+    pub size: Option<(i32, i32)>,
+    // The file doesn't exist anymore
+    pub missing: bool,
+}
+
+// Function lists the pattern files of the directory sorted by their names
+// Only the sizes of the patterns are read, not their cells
+pub fn scan(dir: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for file in fs::read_dir(dir)? {
+        let path = file?.path();
+        let listed = pattern_io::extension(&path).is_some_and(|extension| EXTENSIONS.contains(&extension.as_str()));
+        if !listed || !path.is_file() {
+            continue;
+        }
+        let name = path.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        let size = pattern_io::peek_size(&path);
+        entries.push(Entry{name, path, size, missing: false});
+    }
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    Ok(entries)
+}
+
+// Function lists the files of the paths in their order, named by their whole paths
+// Files that don't exist anymore are listed as missing
+pub fn entries_of(paths: &[PathBuf]) -> Vec<Entry> {
+    paths.iter().map(|path| {
+        let missing = !path.is_file();
+        let size = match missing {
+            true => None,
+            false => pattern_io::peek_size(path),
+        };
+        Entry{name: path.display().to_string(), path: path.clone(), size, missing}
+    }).collect()
+}
+
+// Listed files with the typed filter and the selected one among those that match it
+pub struct Library {
+    entries: Vec<Entry>,
+    filter: String,
+    // Index of the selected file among the matching ones
+    selected: usize,
+}
+
+impl Library {
+    // Constructor of a library of the files with an empty filter and the first file selected
+    pub fn new(entries: Vec<Entry>) -> Library {
+        Library{entries, filter: String::new(), selected: 0}
+    }
+
+    // Text the names of the files are filtered by
+    pub fn filter(&self) -> &str {
+        &self.filter
+    }
+
+    // Function adds the typed text to the filter and selects the first matching file
+    pub fn type_text(&mut self, text: &str) {
+        self.filter.extend(text.chars().filter(|c| !c.is_control()));
+        self.selected = 0;
+    }
+
+    // Function erases the last character of the filter and selects the first matching file
+    pub fn erase(&mut self) {
+        self.filter.pop();
+        self.selected = 0;
+    }
+
+    // Files with the filter in their names (in any case)
+    pub fn matching(&self) -> Vec<&Entry> {
+        let filter = self.filter.to_lowercase();
+        self.entries.iter().filter(|entry| entry.name.to_lowercase().contains(&filter)).collect()
+    }
+
+    // Index of the selected file among the matching ones
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    // The selected file (if any file matches the filter)
+    pub fn selected(&self) -> Option<&Entry> {
+        self.matching().get(self.selected).copied()
+    }
+
+    // Function moves the selection by the number of files, wrapping around the ends of the list
+    pub fn move_selection(&mut self, offset: i32) {
+        let count = self.matching().len() as i32;
+        if count > 0 {
+            self.selected = (self.selected as i32 + offset).rem_euclid(count) as usize;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // Function builds an entry of a file of unknown size
+    fn entry(name: &str) -> Entry {
+        Entry{name: String::from(name), path: PathBuf::from(name), size: None, missing: false}
+    }
+
+    #[test]
+    fn typing_filters_files() {
+        let mut library = Library::new(vec![entry("glider.rle"), entry("Gosper gun.rle"), entry("pulsar.cells")]);
+        library.move_selection(-1);
+        assert_eq!(library.selected().map(|entry| entry.name.as_str()), Some("pulsar.cells"));
+
+        library.type_text("G");
+        assert_eq!(library.matching().len(), 2);
+        assert_eq!(library.selected_index(), 0);
+        library.type_text("un");
+        assert_eq!(library.selected().map(|entry| entry.name.as_str()), Some("Gosper gun.rle"));
+        library.type_text("x");
+        assert_eq!(library.selected(), None);
+        library.erase();
+        assert_eq!(library.filter(), "Gun");
+    }
+
+    #[test]
+    fn scan_reads_sizes_of_new_files() {
+        let dir = env::temp_dir().join(format!("game_of_life_library_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("glider.rle"), "#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n").unwrap();
+        fs::write(dir.join("notes.txt"), "Not a pattern").unwrap();
+        let names: Vec<String> = scan(&dir).unwrap().into_iter().map(|entry| entry.name).collect();
+        assert_eq!(names, vec!["glider.rle"]);
+
+        // Files added later show up on the next scan
+        fs::write(dir.join("Blinker.cells"), "!Name: Blinker\nOOO\n").unwrap();
+        let entries = scan(&dir).unwrap();
+        assert_eq!(entries.iter().map(|entry| entry.size).collect::<Vec<_>>(), vec![Some((3, 1)), Some((3, 3))]);
+        assert_eq!(entries[0].name, "Blinker.cells");
+
+        // Files of the recent list that are gone are kept as missing
+        fs::remove_file(dir.join("glider.rle")).unwrap();
+        let recent = entries_of(&[dir.join("glider.rle"), dir.join("Blinker.cells")]);
+        assert_eq!(recent.iter().map(|entry| (entry.size, entry.missing)).collect::<Vec<_>>(), vec![(None, true), (Some((3, 1)), false)]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,105 +1,548 @@
 use tetra::graphics::{self, Color, Rectangle, DrawParams};
-use tetra::graphics::mesh::{Mesh, ShapeStyle};
+use tetra::graphics::mesh::{GeometryBuilder, Mesh, ShapeStyle};
 use tetra::graphics::text::{Font, Text};
-use tetra::{Context, ContextBuilder, State, Result};
-use tetra::window::{set_mouse_visible, quit};
-use tetra::math::Vec2;
-use tetra::input::{self, MouseButton, Key};
+use tetra::{Context, ContextBuilder, Event, State, Result, TetraError};
+use tetra::window::{self, set_mouse_visible, quit};
+use tetra::math::{Mat4, Vec2, Vec3};
+use tetra::input::{self, MouseButton, Key, KeyModifier};
 use tetra::time::Timestep;
 // Similar to HashMap but with ordered indexing
-use indexmap::IndexMap;
+use arboard::Clipboard;
+use game_of_life::ant;
+use game_of_life::automaton::{self, Automaton};
+use game_of_life::board::Board;
+use game_of_life::cli::{self, Options};
+use game_of_life::config::{self, Config, Keys};
+use game_of_life::error::GolError;
+use game_of_life::focus::FocusPause;
+use game_of_life::graph::PopulationGraph;
+use game_of_life::headless;
+use game_of_life::soup;
+use game_of_life::history::{self, History};
+use game_of_life::keys::{self, KeyAction, KeyBinding};
+use game_of_life::library::{self, Library};
+use game_of_life::neighbourhood::Neighbourhood;
+use game_of_life::shapes;
+use game_of_life::slots;
+use game_of_life::stability::{Detector, Outcome, PeriodFinder};
+use game_of_life::view;
+use game_of_life::undo::{self, Edit, UndoStack};
+use game_of_life::versus;
+use game_of_life::recording::{self, Recording};
+use game_of_life::render;
+use game_of_life::rule;
+use game_of_life::selection::{self, Selection};
+use game_of_life::session::{self, Session};
+use game_of_life::stats::StatsLog;
+use game_of_life::symmetry::Symmetry;
+use game_of_life::patterns;
+use game_of_life::picture;
+use game_of_life::recent::{self, RecentFiles};
+use game_of_life::pattern_io::{self, LoadError};
+use game_of_life::theme::{self, Theme, ThemeColor};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use log::{debug, error, info, warn, Level};
+use std::collections::BTreeMap;
+use std::cmp::Ordering;
+use std::env;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 
-// Size of a field
+// Maximal size of a field
+// The field is as large as the grid of square cells that fits in it
 const FIELD_WIDTH: f32 = 640.0;
 const FIELD_HEIGHT: f32 = 640.0;
 
-// 20 cells in a signle row
-const ROW_PARTS: i32 = 20;
+// Width of the part of the window to the right of the field
+const PANEL_WIDTH: f32 = 200.0;
 
-// Length of a side of a cell
-const CELL_SIZE: f32 = FIELD_WIDTH / ROW_PARTS as f32;
+// Minimal height of the window for the whole menu to fit
+const MIN_WINDOW_HEIGHT: f32 = 480.0;
+
+// Minimal width of the field the window can be shrunk to
+const MIN_FIELD_WIDTH: f32 = 160.0;
 
 // Width of the line of the grid
 const LINE_WIDTH: f32 = 2.0;
 
-// Width of menu part
-const MENU_WIDTH: f32 = 100.0;
+// Width of every `MAJOR_LINE_STEP`-th line of the grid
+const MAJOR_LINE_WIDTH: f32 = 3.0;
+const MAJOR_LINE_STEP: i32 = 5;
 
-// Indent of a status text from the field
+// Indent of the menu from the upper right corner of the field
 // Indent to the right and down
-const STATUS_TEXT_INDENTS: (f32, f32) = (MENU_WIDTH / 4.0, 80.0 as f32);
+const MENU_INDENTS: (f32, f32) = (25.0, 80.0);
+
+// Maximal width of the status before it is wrapped
+const STATUS_TEXT_WIDTH: f32 = 160.0;
+
+// Font sizes of the lines below the status, of the buttons and of the help
+// They are scaled with the font size of the status (see `Options::font_size`)
+const MENU_FONT_SIZE: f32 = 14.0;
+const BUTTON_FONT_SIZE: f32 = 16.0;
+const HELP_FONT_SIZE: f32 = 16.0;
+
+// Distance between the tops of two lines of the menu in font sizes
+const MENU_LINE_SPACING: f32 = 1.4;
+
+// Width of a character of the font in font sizes
+const CHAR_WIDTH: f32 = 0.6;
+
+// Font size digits of neighbour counts are made with
+// They are scaled to `DIGIT_HEIGHT` of the size of a cell when drawn
+const DIGIT_FONT_SIZE: f32 = 24.0;
+const DIGIT_HEIGHT: f32 = 0.6;
+
+// Largest number of neighbours a cell can have (in the radius 2 neighbourhood)
+const MAX_NEIGHBOURS: u32 = 24;
+
+// Size of a button in the menu
+const BUTTON_SIZE: (f32, f32) = (150.0, 30.0);
+
+// Vertical gap between buttons in the menu
+const BUTTON_GAP: f32 = 10.0;
+
+// Indent of a button label from the upper left corner of the button
+const BUTTON_LABEL_INDENTS: (f32, f32) = (10.0, 4.0);
+
+// Indent of the help text from the upper left corner of the field
+const HELP_TEXT_INDENT: f32 = 20.0;
+
+// Number of pattern files listed by the browser at once
+const BROWSER_ROWS: usize = 20;
+
+// Change of the scale of the field by a single step of the mouse wheel
+const ZOOM_STEP: f32 = 1.25;
+
+// Font used for all texts of the game unless another font file is given
+// It's built into the game, so the game can be started from any directory
+const FONT_DATA: &[u8] = include_bytes!("../resources/DejaVuSansMono.ttf");
+
+// Pattern file loaded if no other file is given
+const DEFAULT_PATTERN_PATH: &str = "./patterns/glider.rle";
+
+// Directory the pattern browser lists files of
+const PATTERNS_DIR: &str = "./patterns";
+
+// Theme file loaded on start if it exists
+const THEME_PATH: &str = "./theme.toml";
+
+// Directory and file the field is saved to
+const SAVES_DIR: &str = "./saves";
+const SAVE_PATH: &str = "./saves/pattern.rle";
+
+// Directory screenshots of the field are saved to
+const SCREENSHOTS_DIR: &str = "./screenshots";
+
+// Directory recordings of the game are saved to
+const RECORDINGS_DIR: &str = "./recordings";
+
+// Directory ASCII art of the field is saved to
+const DUMPS_DIR: &str = "./dumps";
+
+// File population stats are written to if no other file is given
+const DEFAULT_STATS_PATH: &str = "./stats.csv";
+
+// File the soup search writes its summary to if no file is given
+const DEFAULT_SOUPS_PATH: &str = "./soups.csv";
+
+// How many times a second the input is handled and the window is updated
+const UPDATE_RATE: f64 = 60.0;
+
+// Number of generations of a batch made on every update
+// The window is drawn between the chunks, so it doesn't freeze
+const BATCH_GENERATIONS: u32 = 200;
+
+// Keys of digits typed as the generation to go to (on the top row and on the numpad)
+const DIGIT_KEYS: [[Key; 2]; 10] = [
+    [Key::Num0, Key::NumPad0], [Key::Num1, Key::NumPad1], [Key::Num2, Key::NumPad2], [Key::Num3, Key::NumPad3],
+    [Key::Num4, Key::NumPad4], [Key::Num5, Key::NumPad5], [Key::Num6, Key::NumPad6], [Key::Num7, Key::NumPad7],
+    [Key::Num8, Key::NumPad8], [Key::Num9, Key::NumPad9],
+];
+
+// Maximal number of digits of the generation to go to
+const MAX_GOTO_DIGITS: usize = 9;
+
+// Available speeds of the game (generations per second)
+const SPEEDS: [f64; 8] = [1.0, 2.0, 5.0, 10.0, 15.0, 20.0, 30.0, 60.0];
+
+// Available sizes of the square brush (in cells)
+const BRUSH_SIZES: [i32; 4] = [1, 2, 3, 5];
+
+// Available numbers of cells in a row
+const RESOLUTIONS: [i32; 4] = [10, 20, 40, 80];
+
+// Maximal number of edits that can be undone
+const UNDO_CAPACITY: usize = 200;
+
+// Color of the oldest cells
+// Cells that have just come to life have the color of alive cells of the theme
+// and cells between them in age get a color between them
+const OLD_CELL_COLOR: Color = Color::rgb(0.0, 0.1, 0.6);
+
+// Age of cells (in generations) that have the color of the oldest cells
+const OLD_CELL_AGE: u32 = 20;
+
+// Share of the background color mixed into cells that survived the last generation
+// Newborn cells keep the full color, so they look brighter
+const SURVIVOR_FADE: f32 = 0.35;
+
+// Opacity of the background drawn over cells that die in the previewed generation
+const DYING_ALPHA: f32 = 0.6;
+
+// Colors of Wireworld cells by their states
+const CONDUCTOR_COLOR: Color = Color::rgb(0.9, 0.75, 0.1);
+const HEAD_COLOR: Color = Color::rgb(0.15, 0.4, 1.0);
+const TAIL_COLOR: Color = Color::rgb(0.9, 0.2, 0.15);
+
+// Color of walls
+const WALL_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+// Color of immortal cells
+const IMMORTAL_COLOR: Color = Color::rgb(1.0, 0.85, 0.0);
+
+// Colors of the two kinds of Immigration cells
+const RED_COLOR: Color = Color::rgb(0.85, 0.15, 0.15);
+const BLUE_COLOR: Color = Color::rgb(0.15, 0.3, 0.9);
+
+// Color of ants of Langton's Ant
+const ANT_COLOR: Color = Color::rgb(0.9, 0.3, 0.1);
+// Corners of the triangle ants are drawn as (heading up) relative to the middle of a cell of size 1
+const ANT_TRIANGLE: [(f32, f32); 3] = [(0.0, -0.4), (0.35, 0.35), (-0.35, 0.35)];
+
+// Color of recent files that don't exist anymore
+const MISSING_FILE_COLOR: Color = Color::rgb(0.5, 0.5, 0.5);
+
+// Opacity of a trail of a cell that has just died
+// Trails fade out completely in the given number of generations
+const TRAIL_ALPHA: f32 = 0.4;
+
+// Size of the graph of population in the menu, its gap from the lines of stats and the width of its line
+// The graph shows the latest `GRAPH_GENERATIONS` generations
+const GRAPH_SIZE: (f32, f32) = (90.0, 40.0);
+const GRAPH_GAP: f32 = 10.0;
+const GRAPH_LINE_WIDTH: f32 = 1.5;
+const GRAPH_GENERATIONS: usize = 200;
+
+// Colors of the numbers of cells born and died in the last generation
+const BIRTHS_COLOR: Color = Color::rgb(0.3, 0.85, 0.3);
+const DEATHS_COLOR: Color = Color::rgb(0.9, 0.3, 0.3);
+
+// Failures the game goes on after (like a bad pattern file) are shown in red below the graph for a few seconds
+const ERROR_COLOR: Color = Color::rgb(0.95, 0.25, 0.25);
+const ERROR_SECONDS: f64 = 4.0;
+
+// The status flashes in the color of the winner for a few seconds after a victory of a color
+const FLASH_SECONDS: f64 = 3.0;
+// Times a second the color of the status changes while it flashes
+const FLASH_RATE: f64 = 6.0;
+
+// Color of the outline of the bounding box of alive cells
+const BOUNDING_BOX_COLOR: Color = Color::rgb(1.0, 0.6, 0.2);
+
+// Moves of the keyboard cursor by the arrow keys: left, right, up and down
+const CURSOR_STEPS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+// Color of the cell under the keyboard cursor
+const CURSOR_COLOR: Color = Color::rgba(0.2, 0.8, 1.0, 0.5);
+
+// Opacity the mouse highlight or the keyboard cursor is drawn with while the other one is used
+const INACTIVE_CURSOR_FADE: f32 = 0.4;
+
+// Color of the rectangle drawn over selected cells
+const SELECTION_COLOR: Color = Color::rgba(0.3, 0.6, 1.0, 0.3);
 
 // A sctructure of a single cell on the field
+// Cells are stored column by column, so the ID of a cell is its index
 struct Cell{
-    // ID of the cell
-    id: i32,
+    // Column and row of the cell
+    coords: (i32, i32),
     // Position of cell's upper left corner
     pos: Vec2<f32>,
-    mesh: Mesh,
-    // Status of the cell (alive/dead)
-    alive: bool,
+}
+
+
+// Everything cells are drawn with except the board itself
+// The mesh of all cells is built again when it changes
+#[derive(PartialEq)]
+struct CellLook{
+    cell_size: f32,
+    alive: Color,
+    background: Color,
+    age_colors: bool,
+    birth_colors: bool,
+    trails: bool,
+    trail_length: u32,
 }
 
 impl Cell{
     // Constructor for a cell
-    fn new(id: i32, pos: Vec2<f32>, alive: bool, ctx: &mut Context) -> Cell{
-        // Mesh should be a bit smaller for the grid lines to fit
-        let gap = LINE_WIDTH * 0.5;
-        let mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, Rectangle::new(0.0 + gap , 0.0 + gap, CELL_SIZE - 2.0 * gap, CELL_SIZE - 2.0 * gap)); 
-        match mesh{
-            Ok(mesh) =>  Cell{id, pos, mesh, alive},
-            // TODO a more fancy way to handle it?
-            Err(_) => panic!("Couldn`t create a cell!")
-        }
-        
+    fn new(coords: (i32, i32), pos: Vec2<f32>) -> Cell{
+        Cell{coords, pos}
     }
 
 }
 
 // A single line
 struct Line{
-    width: f32,
-    points: [Vec2<f32>; 2],
     mesh: Mesh,
+    // Is the line on the edge of the field
+    edge: bool,
 }
 
 impl Line{
     // Constructor for a line
-    fn new(width: f32, points: [Vec2<f32>; 2], ctx: &mut Context) -> Line{
-        let mesh = Mesh::polyline(ctx, width, &points);
-        match mesh{
-            Ok(mesh) =>  Line{width, points, mesh},
-            Err(e) => panic!("{}", e)
+    fn new(width: f32, points: [Vec2<f32>; 2], edge: bool, ctx: &mut Context) -> Result<Line>{
+        let mesh = Mesh::polyline(ctx, width, &points)?;
+        Ok(Line{mesh, edge})
+    }
+}
+
+
+// Outline of the smallest rectangle containing all alive cells
+struct BoundingBox{
+    // Upper left and lower right cells of the rectangle
+    corners: ((i32, i32), (i32, i32)),
+    mesh: Mesh,
+}
+
+
+// Source of fonts of all texts
+struct Fonts{
+    // Font file used instead of the built-in font (if any)
+    path: Option<String>,
+    // Font size of the status, other texts are scaled with it
+    status_size: f32,
+}
+
+
+impl Fonts{
+    // Function creates a font of the given size
+    fn font(&self, ctx: &mut Context, size: f32) -> Result<Font>{
+        match &self.path {
+            Some(path) => Font::vector(ctx, path, size),
+            None => Font::from_vector_file_data(ctx, FONT_DATA, size),
         }
     }
+
+
+    // Function scales the font size of a text the same way as the font size of the status
+    fn scaled(&self, size: f32) -> f32{
+        size * self.status_size / cli::DEFAULT_FONT_SIZE
+    }
 }
 
 
-// Status text of the game
-// It's eaither "Running" or "Paused"
-struct StatusText{
+// Texts of the menu
+// The status of the game (like "Running" or "Paused") is followed by its stats line by line
+struct MenuText{
     // Position of the text on the window
     pos: Vec2<f32>,
+    // Current status of the game
+    status: String,
+    // The status is wrapped if it is too long
     text: Text,
+    // Single lines of stats shown below the status
+    lines: Vec<Text>,
+    // Numbers of cells born and died in the last generation shown as "+b / -d" below the stats
+    births: Text,
+    separator: Text,
+    deaths: Text,
+    // Width of a character of the lines of stats
+    char_width: f32,
+    // Font of the lines of stats
+    font: Font,
+    // Maximal number of characters in a line of stats
+    line_chars: usize,
+    // Distance between the tops of two lines of stats
+    line_spacing: f32,
+    // The last failure and the seconds it's still shown for
+    error: Text,
+    error_time: f64,
+    // Color the status flashes in and the seconds it still flashes for
+    flash_color: Color,
+    flash_time: f64,
 }
 
 
-impl StatusText{
-    // Constructor of a status text
-    fn new(ctx: &mut Context, pos: Vec2<f32>) -> StatusText{
-        let font = Font::vector(ctx, "./resources/DejaVuSansMono.ttf", 21.0);
-        let f = match font {
-            Ok(font) => font,
-            Err(_) => panic!("Can't read a font file!"),
-        };
-        let text = Text::new(
-            "New Attempt", 
-            f,
+impl MenuText{
+    // Constructor of a menu text
+    fn new(ctx: &mut Context, fonts: &Fonts, pos: Vec2<f32>) -> Result<MenuText>{
+        let status_font = fonts.font(ctx, fonts.status_size)?;
+        let size = fonts.scaled(MENU_FONT_SIZE);
+        let font = fonts.font(ctx, size)?;
+        let status = String::from("New Attempt");
+        let text = Text::wrapped(
+            status.as_str(), 
+            status_font,
+            STATUS_TEXT_WIDTH,
             );
+        let char_width = size * CHAR_WIDTH;
+        let line_chars = ((STATUS_TEXT_WIDTH / char_width) as usize).max(1);
+        let line_spacing = size * MENU_LINE_SPACING;
+        let births = Text::new("+0", font.clone());
+        let separator = Text::new("/", font.clone());
+        let deaths = Text::new("-0", font.clone());
+        let error = Text::wrapped("", font.clone(), STATUS_TEXT_WIDTH);
+
+        Ok(MenuText{pos, status, text, lines: Vec::new(), births, separator, deaths, char_width, font, line_chars, line_spacing, error, error_time: 0.0, flash_color: Color::WHITE, flash_time: 0.0})
+    }
+
+
+    // Function shows the numbers of cells born and died in the last generation
+    fn set_changes(&mut self, births: usize, deaths: usize){
+        self.births.set_content(format!("+{}", births));
+        self.deaths.set_content(format!("-{}", deaths));
+    }
+
+
+    // Function replaces the lines of stats
+    // Lines too long for the menu are cut off
+    fn set_lines(&mut self, lines: &[String]){
+        self.lines.truncate(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            let line = fit_line(line, self.line_chars);
+            match self.lines.get_mut(i) {
+                Some(text) => text.set_content(line),
+                None => self.lines.push(Text::new(line, self.font.clone())),
+            }
+        }
+    }
+}
+
+
+// What a button of the menu does when clicked
+#[derive(Clone, Copy)]
+enum ButtonAction{
+    StartPause,
+    Step,
+    Clear,
+    Randomize,
+}
+
+
+// A clickable button of the menu
+struct Button{
+    // Area of the button on the window
+    rect: Rectangle,
+    mesh: Mesh,
+    label: Text,
+    action: ButtonAction,
+}
+
+
+impl Button{
+    // Constructor of a button with its upper left corner at the given position
+    fn new(ctx: &mut Context, font: Font, pos: Vec2<f32>, label: &str, action: ButtonAction) -> Result<Button>{
+        let rect = Rectangle::new(pos.x, pos.y, BUTTON_SIZE.0, BUTTON_SIZE.1);
+        let mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, Rectangle::new(0.0, 0.0, rect.width, rect.height))?;
+        let label = Text::new(label, font);
+        Ok(Button{rect, mesh, label, action})
+    }
+
+
+    // Function checks if the point is over the button
+    fn contains(&self, point: Vec2<f32>) -> bool{
+        self.rect.contains_point(point)
+    }
+}
+
+
+// Translucent overlay with all key bindings drawn over the field
+struct HelpOverlay{
+    // Is the overlay shown
+    visible: bool,
+    // Dark rectangle covering the field
+    mesh: Mesh,
+    text: Text,
+}
+
+
+impl HelpOverlay{
+    // Constructor of a hidden overlay of the given size listing the bindings
+    fn new(ctx: &mut Context, fonts: &Fonts, size: Vec2<f32>, bindings: &[KeyBinding]) -> Result<HelpOverlay>{
+        let mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, Rectangle::new(0.0, 0.0, size.x, size.y))?;
+        let font = fonts.font(ctx, fonts.scaled(HELP_FONT_SIZE))?;
+        let text = Text::new(help_content(bindings), font);
+        Ok(HelpOverlay{visible: false, mesh, text})
+    }
+}
+
+
+// List of pattern files drawn over the field to pick a pattern to stamp
+struct PatternBrowser{
+    // Listed files while the browser is open
+    library: Option<Library>,
+    // Are the recent files listed instead of the patterns directory
+    recent: bool,
+    text: Text,
+    // Files that don't exist anymore, drawn in gray
+    missing_text: Text,
+}
+
 
-        StatusText{pos, text}
+impl PatternBrowser{
+    // Constructor of a closed browser
+    fn new(ctx: &mut Context, fonts: &Fonts) -> Result<PatternBrowser>{
+        let font = fonts.font(ctx, fonts.scaled(HELP_FONT_SIZE))?;
+        Ok(PatternBrowser{library: None, recent: false, text: Text::new("", font.clone()), missing_text: Text::new("", font)})
     }
+
+    // Is the browser open
+    fn is_open(&self) -> bool{
+        self.library.is_some()
+    }
+}
+
+
+// A continuous stroke of cells painted with a mouse
+struct Stroke{
+    // Mouse button that is held during the stroke
+    button: MouseButton,
+    // State given to all cells of the stroke
+    state: u8,
+    // The last painted cell
+    last: (i32, i32),
+    // Changes of cells made by the stroke
+    edit: Edit,
+}
+
+
+// Cells stamped with the next click (a pattern or pasted cells)
+struct Stamp{
+    // Name shown in the menu
+    name: String,
+    // Coordinates of alive cells relative to the cell under the cursor
+    cells: Vec<(i32, i32)>,
+}
+
+
+// Generations made in chunks on updates without drawing the ones between them
+#[derive(Clone, Copy)]
+enum Batch{
+    // Going from the first generation to the second one
+    GoTo{from: u32, to: u32},
+    // Running from the generation until the field stabilizes or dies out, or `cap` generations are made
+    UntilStable{from: u32, cap: u32},
+}
+
+
+// Ways to draw cells with the LMB
+#[derive(Clone, Copy, PartialEq)]
+enum Tool{
+    // Cells under the cursor are painted while the button is held
+    Brush,
+    // A straight line of cells is revived between two clicked cells
+    Line,
+    // A rectangle of cells is revived between two corners dragged with the mouse
+    // It's filled if Shift is held when the button is released
+    Rectangle,
 }
 
 
@@ -107,278 +550,2625 @@ impl StatusText{
 struct GameState {
     // Is the game running
     running: bool,
+    // Number of times the rules were applied
+    generation: u32,
+    // Speed of the game (generations per second)
+    speed: f64,
+    // Time passed since the last generation (in seconds)
+    accumulator: f64,
+    // Make `turbo_steps` generations instead of one at a time
+    turbo: bool,
+    turbo_steps: u32,
+    // Digits of the generation to go to while they are typed
+    goto_input: Option<String>,
+    // Generations that are being made in chunks
+    batch: Option<Batch>,
+    // Maximal number of generations made while waiting for the field to stabilize
+    stable_cap: u32,
+    // Statuses of all cells on the field
+    board: Board,
+    // Length of a side of a cell
+    cell_size: f32,
+    // Seed used to fill the field with random cells
+    seed: u64,
+    // Show the seed in the menu (the field was filled with it and not replaced since)
+    show_seed: bool,
+    // Probability of a cell to be alive when the field is filled randomly
+    density: f64,
     // Vector of lines to form a grid
     grid: Vec<Line>,
-    // A map of coordinates of cells
-    // {cell_ID -> coordinates}
-    cell_coords: IndexMap<i32, Vec2<f32>>,
+    // Draw lines between cells
+    show_grid: bool,
+    // Themes that can be selected
+    themes: Vec<Theme>,
+    // Index of the theme everything is drawn with
+    theme: usize,
     // Vector of all cells on the field 
     cells: Vec<Cell>,
+    // A single mesh drawn at the positions of all alive cells
+    cell_mesh: Mesh,
+    // Quads of all alive cells and trails drawn at once (if there are any), built again when the board changes
+    field_mesh: Option<Mesh>,
+    // Look of cells the mesh was built with
+    field_look: Option<CellLook>,
+    // A single mesh for the outline of a cell
+    outline_mesh: Mesh,
+    // A triangle of size 1 drawn at ants of Langton's Ant, turned to their headings
+    ant_mesh: Mesh,
     // Coordinates of a mouse
     mouse_coords: Vec2<f32>,
-    // Game status text
-    status_text: StatusText, 
+    // Is Ctrl held, so the menu shows the number of neighbours of the cell under the mouse
+    ctrl_held: bool,
+    // Area of the window the field is drawn in
+    field_area: Rectangle,
+    // Scale and offset the field is drawn with
+    transform: view::Transform,
+    // Is the field being moved with the mouse
+    panning: bool,
+    // Stroke being painted with a mouse (if any)
+    stroke: Option<Stroke>,
+    // Manual edits of cells since the last generation
+    history: UndoStack,
+    // Previous generations to step back to
+    generations: History,
+    // Cells before the last generation, copied to the same buffer every generation
+    before: Vec<u8>,
+    // Cells of the field at the moment the game was started the last time
+    snapshot: Option<Vec<u8>>,
+    // Color cells by the number of generations they have been alive
+    age_colors: bool,
+    // Draw cells that survived the last generation darker than newborn ones
+    birth_colors: bool,
+    // Show the next generation over the field while the game is paused
+    lookahead: bool,
+    // Cells that change in the next generation (while it's previewed)
+    next_changes: Vec<(i32, i32, u8)>,
+    // Show the number of alive neighbours in every cell that has any
+    show_neighbours: bool,
+    // Cells with alive neighbours and their numbers (while they are shown)
+    neighbour_counts: Vec<((i32, i32), u32)>,
+    // Texts of numbers of neighbours from 1 to `MAX_NEIGHBOURS` drawn in all cells
+    digits: Vec<Text>,
+    // Draw fading trails of dead cells
+    trails: bool,
+    // Number of generations a dead cell leaves a trail for
+    trail_length: u32,
+    // Pause the game when the field stops changing
+    auto_pause: bool,
+    // Pauses the game while the window is out of focus
+    focus: FocusPause,
+    // Pause the running game when a cell is clicked, so the edit is not changed right away
+    pause_on_edit: bool,
+    // Finds out when the field stops changing
+    detector: Detector,
+    // Finds out when the field repeats itself
+    periods: PeriodFinder,
+    // Period of the field found after the last generation (if any)
+    period: Option<usize>,
+    // Cells to stamp with the next click (if any)
+    stamp: Option<Stamp>,
+    // Tool drawing cells with the LMB
+    tool: Tool,
+    // Cell clicked first with the line tool (if any)
+    line_start: Option<(i32, i32)>,
+    // Corner the rectangle tool is dragged from (if it is)
+    rectangle_start: Option<(i32, i32)>,
+    // Axes painted cells are mirrored across
+    symmetry: Symmetry,
+    // Index of the size of the brush in `BRUSH_SIZES`
+    brush: usize,
+    // Rectangle of selected cells (if any)
+    selection: Option<Selection>,
+    // Is the selection being dragged with the mouse
+    selecting: bool,
+    // A single mesh stretched over the selected cells
+    selection_mesh: Mesh,
+    // Cell of the keyboard cursor (after it's moved for the first time)
+    cursor: Option<(i32, i32)>,
+    // Was the keyboard cursor used after the mouse was moved
+    keyboard_used: bool,
+    // Outline the smallest rectangle containing all alive cells
+    show_bounding_box: bool,
+    // Bounding box of alive cells while it's shown, its outline is built again when its corners change
+    bounding_box: Option<BoundingBox>,
+    // Copied cells relative to the upper left corner of their selection
+    clipboard: Vec<(i32, i32)>,
+    // Path to the file with a pattern to load
+    pattern_path: Option<String>,
+    // Pattern files loaded the latest
+    recent: RecentFiles,
+    // Save slots that have a file
+    slots: [bool; slots::SLOT_COUNT],
+    // Generations recorded since recording was started (if it was)
+    recording: Option<Recording>,
+    // File population stats are written to
+    stats_path: String,
+    // Log of population stats of every generation (if it is written)
+    stats: Option<StatsLog<BufWriter<File>>>,
+    // Status and stats of the game in the menu
+    menu_text: MenuText,
+    // Population of the latest generations
+    graph: PopulationGraph,
+    // Line of the graph (if it has two generations at least), it's built again when the graph changes
+    graph_mesh: Option<Mesh>,
+    graph_changed: bool,
+    // Line at the bottom of the graph (zero population)
+    baseline: Mesh,
+    // Buttons of the menu
+    buttons: Vec<Button>,
+    // Keys of actions with the ones of the config file
+    bindings: Vec<KeyBinding>,
+    // List of controls shown over the field
+    help: HelpOverlay,
+    // List of pattern files shown over the field
+    browser: PatternBrowser,
 }
 
 impl GameState{
     // A constructor for a new game state
-    fn new(ctx: &mut Context) -> Result<GameState>{
-        let mut cells = Vec::new();
-        let mut cell_coords = IndexMap::new();
-        let mut grid = Vec::new();
-        let mouse_coords = Vec2::new(FIELD_WIDTH / 2.0, FIELD_HEIGHT / 2.0);
+    // The game continues the session if there is one
+    // Actions are bound to the given keys (of the config file) instead of their built-in ones
+    fn new(ctx: &mut Context, options: Options, session: Option<Session>, keys: &BTreeMap<String, Keys>) -> std::result::Result<GameState, GolError>{
+        let (window_width, window_height) = window::get_size(ctx);
+        let window = Vec2::new(window_width as f32, window_height as f32);
+        let (field_width, field_height) = view::field_size((window.x, window.y), PANEL_WIDTH);
+        let field_area = Rectangle::new(0.0, 0.0, field_width, field_height);
+        let mouse_coords = Vec2::new(field_area.width / 2.0, field_area.height / 2.0);
+        let ctrl_held = false;
+        let transform = view::Transform::default();
+        let panning = false;
+        let stroke = None;
+        let history = UndoStack::new(UNDO_CAPACITY);
+        let generations = History::new(options.history);
+        let before = Vec::new();
+        let snapshot = None;
+        let age_colors = false;
+        let birth_colors = true;
+        let lookahead = false;
+        let next_changes = Vec::new();
+        let show_neighbours = false;
+        let neighbour_counts = Vec::new();
+        let trails = true;
+        let trail_length = options.trail;
+        // Still lifes are detected by default, but oscillators are left running
+        let auto_pause = true;
+        let focus = FocusPause::new(options.resume_on_focus);
+        let pause_on_edit = true;
+        let detector = Detector::new(false);
+        let periods = PeriodFinder::new();
+        let period = None;
+        let stamp = None;
+        let tool = Tool::Brush;
+        let line_start = None;
+        let rectangle_start = None;
+        let symmetry = Symmetry::Off;
+        let brush = 0;
+        let selection = None;
+        let selecting = false;
+        let selection_mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, Rectangle::new(0.0, 0.0, 1.0, 1.0))?;
+        let cursor = None;
+        let keyboard_used = false;
+        let show_bounding_box = false;
+        let bounding_box = None;
+        let clipboard = Vec::new();
         // By default the game is not running
         let running = false;
+        let generation = session.as_ref().map_or(0, |session| session.generation);
+        let speed = session.as_ref().map_or(options.speed, |session| session.speed);
+        let accumulator = 0.0;
+        let turbo = false;
+        let turbo_steps = options.turbo;
+        let goto_input = None;
+        let batch = None;
+        let stable_cap = options.stable_cap;
+        // By default all cells are dead
+        let mut board = match &session {
+            Some(session) => session.board(),
+            None => {
+                let mut board = Board::new(options.cols, options.rows);
+                board.set_wrap(options.wrap);
+                board.set_rule(options.rule);
+                board.set_automaton(options.automaton);
+                board.set_capture(options.capture);
+                board.set_neighbourhood(options.neighbourhood);
+                board
+            },
+        };
+        board.set_engine(options.engine);
+        let (cell_size, cell_mesh, cells, grid) = GameState::build_field(ctx, &board, field_area)?;
+        let outline_mesh = GameState::build_outline(ctx, cell_size)?;
+        let ant_mesh = Mesh::polygon(ctx, ShapeStyle::Fill, &ANT_TRIANGLE.map(|(x, y)| Vec2::new(x, y)))?;
+        let field_mesh = None;
+        let field_look = None;
+        let show_grid = options.grid;
+        let themes = Theme::presets();
+        let theme = 0;
+        let pattern_path = options.pattern;
+        let recent = read_recent();
+        let slots = slots::occupied(Path::new(SAVES_DIR));
+        let recording = None;
+        let stats_path = options.stats.clone().unwrap_or_else(|| String::from(DEFAULT_STATS_PATH));
+        let stats = None;
+        let seed = options.seed.unwrap_or_else(rand::random);
+        board.set_noise(options.noise, seed);
+        if options.noise > 0.0 && board.noise() == 0.0 {
+            warn!("Noise doesn't apply to {} and is ignored", board.automaton());
+        }
+        let density = options.density;
+        let show_seed = false;
         // By default text indicates that game is stopped
-        let status_text = StatusText::new(ctx, Vec2::new(FIELD_WIDTH + STATUS_TEXT_INDENTS.0, STATUS_TEXT_INDENTS.1));
-
-        // Initialize all cell coordinates
-        let mut x: f32 = 0.0;
-        let mut y: f32 = 0.0;
-        let mut id: i32 = 0;
-        // Cell shouldn't be drawn after the last vertical line
-        while x <= FIELD_WIDTH - 1.0 {
-            while y <= FIELD_HEIGHT - 1.0 {
-                cell_coords.insert(id, Vec2::new(x, y));
-                y += CELL_SIZE;
-                id += 1;
-            }
-            y = 0.0;
-            x += CELL_SIZE; }
-            
-        // Initialize all cells with those coordinates
-        for (_num, (id, coords)) in cell_coords.iter().enumerate() {
-            // All cells are initialized as dead ones
-            let cell = Cell::new(*id as i32, *coords, false, ctx);
-            cells.push(cell);
-        }   
+        let fonts = Fonts{path: options.font, status_size: options.font_size};
+        let menu_text = MenuText::new(ctx, &fonts, Vec2::new(window.x - PANEL_WIDTH + MENU_INDENTS.0, MENU_INDENTS.1))?;
+        let buttons = GameState::build_buttons(ctx, &fonts, window)?;
+        let mut graph = PopulationGraph::new(GRAPH_GENERATIONS);
+        graph.push(board.population());
+        let graph_mesh = None;
+        let graph_changed = true;
+        let baseline = Mesh::polyline(ctx, LINE_WIDTH, &[Vec2::new(0.0, GRAPH_SIZE.1), Vec2::new(GRAPH_SIZE.0, GRAPH_SIZE.1)])?;
+        let digit_font = fonts.font(ctx, DIGIT_FONT_SIZE)?;
+        let digits = (1..=MAX_NEIGHBOURS).map(|count: u32| Text::new(count.to_string(), digit_font.clone())).collect();
+        let (bindings, warnings) = keys::key_bindings(keys);
+        for warning in warnings {
+            warn!("{}", warning);
+        }
+        let help = HelpOverlay::new(ctx, &fonts, Vec2::new(field_area.width, field_area.height), &bindings)?;
+        let browser = PatternBrowser::new(ctx, &fonts)?;
+
+        // Make mouse cursor visible on the field
+        set_mouse_visible(ctx, true)?;
+        // The window can't be shrunk below the size the whole menu fits in
+        window::set_minimum_size(ctx, (MIN_FIELD_WIDTH + PANEL_WIDTH) as i32, MIN_WINDOW_HEIGHT as i32)?;
+
+        let mut state = GameState{running, generation, speed, accumulator, turbo, turbo_steps, goto_input, batch, stable_cap, board, cell_size, seed, show_seed, density, grid, show_grid, themes, theme, cells, cell_mesh, field_mesh, field_look, outline_mesh, ant_mesh, mouse_coords, ctrl_held, field_area, transform, panning, stroke, history, generations, before, snapshot, age_colors, birth_colors, lookahead, next_changes, show_neighbours, neighbour_counts, digits, trails, trail_length, auto_pause, focus, pause_on_edit, detector, periods, period, stamp, tool, line_start, rectangle_start, symmetry, brush, selection, selecting, selection_mesh, cursor, keyboard_used, show_bounding_box, bounding_box, clipboard, pattern_path, recent, slots, recording, stats_path, stats, menu_text, graph, graph_mesh, graph_changed, baseline, buttons, bindings, help, browser};
+        if session.is_some() {
+            state.set_status("Session restored");
+        }
+        // A custom theme is selected right away if there is one
+        if Path::new(THEME_PATH).exists() {
+            state.load_theme();
+        }
+        // The theme of arguments (or the config file) is selected over it
+        if let Some(name) = &options.theme {
+            match state.themes.iter().position(|theme| theme.name == *name) {
+                Some(index) => state.theme = index,
+                None => state.log_status(Level::Warn, &format!("Unknown theme {}", name)),
+            }
+        }
+        // The soup of the seed from arguments is filled right away
+        if options.seed.is_some() {
+            state.randomize();
+        }
+        // The pattern from arguments is loaded right away
+        if state.pattern_path.is_some() {
+            state.load_pattern().unwrap_or_else(|e| state.show_error(e));
+        }
+        // The picture from arguments seeds the field instead if there is no pattern
+        if let (None, Some(path)) = (&state.pattern_path, &options.image) {
+            state.load_picture(path, options.threshold);
+        }
+        // Stats are written from the start if a file is given
+        if options.stats.is_some() {
+            state.toggle_stats().unwrap_or_else(|e| state.show_error(e));
+        }
+        Ok(state)
+    }
+
+
+    // Function creates buttons of the menu at the bottom of the window of the given size
+    fn build_buttons(ctx: &mut Context, fonts: &Fonts, window: Vec2<f32>) -> Result<Vec<Button>>{
+        let font = fonts.font(ctx, fonts.scaled(BUTTON_FONT_SIZE))?;
+        let buttons = [
+            ("Start", ButtonAction::StartPause),
+            ("Step", ButtonAction::Step),
+            ("Clear", ButtonAction::Clear),
+            ("Random", ButtonAction::Randomize),
+        ];
+        buttons.iter().enumerate().map(|(i, (label, action))| {
+            let pos = GameState::button_position(window, i, buttons.len());
+            Button::new(ctx, font.clone(), pos, label, *action)
+        }).collect()
+    }
+
+
+    // Function finds the upper left corner of the `index`-th of `count` buttons at the bottom of the menu
+    fn button_position(window: Vec2<f32>, index: usize, count: usize) -> Vec2<f32>{
+        let top = window.y - count as f32 * (BUTTON_SIZE.1 + BUTTON_GAP);
+        Vec2::new(window.x - PANEL_WIDTH + MENU_INDENTS.0, top + index as f32 * (BUTTON_SIZE.1 + BUTTON_GAP))
+    }
+
+
+    // Function fits the field and the menu to the window of the new size
+    // The field takes what is left of the window to the left of the menu, and cells are built again
+    // with the size that fits it, while the board itself (and so every cell's state) is left as it is
+    fn fit_window(&mut self, ctx: &mut Context, width: i32, height: i32) -> Result{
+        let window = Vec2::new(width as f32, height as f32);
+        let (field_width, field_height) = view::field_size((window.x, window.y), PANEL_WIDTH);
+        let field_area = Rectangle::new(0.0, 0.0, field_width, field_height);
+        let (cell_size, cell_mesh, cells, grid) = GameState::build_field(ctx, &self.board, field_area)?;
+        self.outline_mesh = GameState::build_outline(ctx, cell_size)?;
+        self.help.mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, field_area)?;
+        self.field_area = field_area;
+        self.cell_size = cell_size;
+        self.cell_mesh = cell_mesh;
+        self.cells = cells;
+        self.grid = grid;
+        // The whole field is shown again, as the old zoom and offset were made for cells of the old size
+        self.transform = view::Transform::default();
+        self.panning = false;
+
+        // The menu column follows the right edge of the window
+        self.menu_text.pos = Vec2::new(window.x - PANEL_WIDTH + MENU_INDENTS.0, MENU_INDENTS.1);
+        let count = self.buttons.len();
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            let pos = GameState::button_position(window, i, count);
+            button.rect.x = pos.x;
+            button.rect.y = pos.y;
+        }
+        Ok(())
+    }
+
+
+    // Function does what the button tells
+    fn press_button(&mut self, action: ButtonAction){
+        match action {
+            ButtonAction::StartPause => self.toggle_running(),
+            ButtonAction::Step => if !self.running {
+                self.step_once();
+            },
+            ButtonAction::Clear => self.clear(false),
+            ButtonAction::Randomize => self.randomize(),
+        }
+    }
 
-        // Initialize all grid lines with a constant set of coordinates
-        x = 0.0;
-        y = 0.0;
+
+    // Function creates cells and grid lines for every cell of the board fitting the area of the field
+    // Returns the length of a side of a cell, a mesh of a cell, cells and lines
+    fn build_field(ctx: &mut Context, board: &Board, field_area: Rectangle) -> Result<(f32, Mesh, Vec<Cell>, Vec<Line>)>{
+        let mut cells = Vec::new();
+        let mut grid = Vec::new();
+        // Cells are squares, so the whole grid has to fit the field
+        let cell_size = view::cell_size(board.width(), board.height(), field_area.width, field_area.height);
+
+        // Mesh should be a bit smaller for the grid lines to fit
+        let gap = LINE_WIDTH * 0.5;
+        let cell_mesh = Mesh::rectangle(ctx, ShapeStyle::Fill, Rectangle::new(0.0 + gap , 0.0 + gap, cell_size - 2.0 * gap, cell_size - 2.0 * gap))?;
+
+        // Initialize all cells with their coordinates on the board
+        // Cells are stored column by column
+        for col in 0..board.width() {
+            for row in 0..board.height() {
+                let pos = Vec2::new(col as f32 * cell_size, row as f32 * cell_size);
+                cells.push(Cell::new((col, row), pos));
+            }
+        }
+
+        // Initialize all grid lines around the cells
+        // Every `MAJOR_LINE_STEP`-th line is heavier to help counting cells
+        let grid_width = board.width() as f32 * cell_size;
+        let grid_height = board.height() as f32 * cell_size;
+        let line_width = |n: i32| match n % MAJOR_LINE_STEP {
+            0 => MAJOR_LINE_WIDTH,
+            _ => LINE_WIDTH,
+        };
         // Vertical lines
-        while x <= FIELD_WIDTH + 1.0 {
-            let line = Line::new(LINE_WIDTH, [Vec2::new(x, y), Vec2::new(x, FIELD_HEIGHT)], ctx);
+        for col in 0..=board.width() {
+            let x = col as f32 * cell_size;
+            let edge = col == 0 || col == board.width();
+            let line = Line::new(line_width(col), [Vec2::new(x, 0.0), Vec2::new(x, grid_height)], edge, ctx)?;
             grid.push(line);
-            x += CELL_SIZE;
         }
 
-        x = 0.0;
-        y = 0.0;
         // Horizontal lines
-        while y <= FIELD_HEIGHT {
-            let line = Line::new(LINE_WIDTH, [Vec2::new(x, y), Vec2::new(FIELD_WIDTH, y)], ctx);
+        for row in 0..=board.height() {
+            let y = row as f32 * cell_size;
+            let edge = row == 0 || row == board.height();
+            let line = Line::new(line_width(row), [Vec2::new(0.0, y), Vec2::new(grid_width, y)], edge, ctx)?;
             grid.push(line);
-            y += CELL_SIZE;
-        }
-        
-        // Make mouse cursor visible on the field
-        match set_mouse_visible(ctx, true){
-            Ok(_) => (),
-            Err(_) => panic!("Can not see the mouse!"),
         }
 
-        Ok(GameState{running, grid, cell_coords, cells, mouse_coords, status_text})
+        Ok((cell_size, cell_mesh, cells, grid))
     }
-    
 
-    // Function resets the game state
-    fn reset(&mut self){
-        self.running = false;
-        self.status_text.text.set_content("New Attempt");
-        for cell in self.cells.iter_mut(){
-            cell.alive = false;
-        }
+
+    // Function creates the outline of a cell of the given size drawn inside of its grid lines
+    fn build_outline(ctx: &mut Context, cell_size: f32) -> Result<Mesh>{
+        let inset = LINE_WIDTH;
+        let side = (cell_size - 2.0 * inset).max(1.0);
+        Mesh::rectangle(ctx, ShapeStyle::Stroke(LINE_WIDTH), Rectangle::new(inset, inset, side, side))
     }
 
 
-    // Function to find a corresponding cell for the cursor
-    fn point_to_cell(&self) -> i32 {
-        let mouse_x = self.mouse_coords[0];
-        let mouse_y = self.mouse_coords[1];
-        for (_, cell) in self.cells.iter().enumerate(){
-            // First check the lower right corner of the cell
-            if (mouse_x <= cell.pos[0] + CELL_SIZE) && (mouse_y <= cell.pos[1] + CELL_SIZE){
-                // Then check the upper left corner of the cell
-                if (mouse_x >= cell.pos[0]) && (mouse_y >= cell.pos[1]){
-                    return cell.id
-                }
-            }   
-        }
+    // Function changes the number of cells in a row to the next or previous one of `RESOLUTIONS`
+    // The number of cells in a column is changed in proportion and alive cells are scaled
+    fn change_resolution(&mut self, ctx: &mut Context, finer: bool) -> Result{
+        let cols = self.board.width();
+        let next = match finer {
+            true => RESOLUTIONS.iter().find(|resolution| **resolution > cols),
+            false => RESOLUTIONS.iter().rev().find(|resolution| **resolution < cols),
+        };
+        let new_cols = match next {
+            Some(resolution) => *resolution,
+            None => return Ok(()),
+        };
+        let new_rows = (self.board.height() * new_cols / cols).max(1);
+        // The field is built first, so the game is left as it was if it fails
+        let board = self.board.resized(new_cols, new_rows);
+        let (cell_size, cell_mesh, cells, grid) = GameState::build_field(ctx, &board, self.field_area)?;
+        let outline_mesh = GameState::build_outline(ctx, cell_size)?;
 
-        // Return -1 if none matches
-        -1
-    }   
+        // Frames of a recording have the size of the old field
+        self.stop_recording().unwrap_or_else(|e| self.show_error(e));
+        self.running = false;
+        self.stroke = None;
+        self.line_start = None;
+        self.rectangle_start = None;
+        self.history.clear();
+        self.generations.clear();
+        self.forget_period();
+        self.snapshot = None;
+        self.board = board;
+        self.cell_size = cell_size;
+        self.cell_mesh = cell_mesh;
+        self.outline_mesh = outline_mesh;
+        self.cells = cells;
+        self.grid = grid;
+        self.cursor = None;
+        self.bounding_box = None;
+        self.restart_graph();
+        self.set_status("Paused");
+        Ok(())
+    }
+    
 
-}
+    // Function changes the status of the game and updates the buttons
+    fn set_status(&mut self, status: &str){
+        self.menu_text.status = String::from(status);
+        self.menu_text.flash_time = 0.0;
+        self.update_buttons();
+    }
 
 
-// Implement library trait for custom sctructure
-impl State for GameState {
-    // Function to draw all meshes
-    fn draw(&mut self, ctx: &mut Context) -> Result{
-        // Color of the field
-        graphics::clear(ctx, Color::rgb(0.2, 0.2, 0.2));
+    // Function shows the failure the game goes on after in red and logs it
+    // Failures of the window are returned as errors of tetra, which stop the game
+    fn show_error(&mut self, error: GolError){
+        warn!("{}", error);
+        self.menu_text.error.set_content(error.to_string());
+        self.menu_text.error_time = ERROR_SECONDS;
+    }
 
-        // Draw grid
-        for line in self.grid.iter(){
-            line.mesh.draw(ctx, DrawParams::new()
-             .color(Color::rgb(1.0, 0.0, 0.0))
-             );
-        }   
 
-        // Draw text
-        self.status_text.text.draw(ctx, DrawParams::new()
-            .position(self.status_text.pos)
-            .color(Color::rgb(0.643, 0.258, 0.862))
-            );
-        
+    // Function changes the status like `set_status` and logs it
+    // It's used for loads and saves of files (info) and failures the game goes on after (warnings)
+    fn log_status(&mut self, level: Level, status: &str){
+        log::log!(level, "{}", status);
+        self.set_status(status);
+    }
 
-        // Draw cells 
-        for cell in self.cells.iter(){
-            // *only alive cells
-            if cell.alive {
-                cell.mesh.draw(ctx, DrawParams::new()
-                    .position(Vec2::new(cell.pos[0], cell.pos[1]))
-                    .color(Color::rgb(0.0, 1.0, 0.0))
-                    );
 
-            }
-        }             
-        
-        Ok(())
+    // Function updates the menu text according to the game state
+    fn update_menu_text(&mut self){
+        let wrap = match self.board.wrap() {
+            true => "Wrap: on",
+            false => "Wrap: off",
+        };
+        let auto_pause = match (self.auto_pause, self.detector.period_two) {
+            (false, _) => "Auto-pause: off",
+            (true, false) => "Auto-pause: on",
+            (true, true) => "Auto-pause: on+p2",
+        };
+        self.menu_text.text.set_content(self.menu_text.status.as_str());
+        // The cell under the mouse is shown with the number of its neighbours while Ctrl is held
+        let pointed = match (self.pointed_cell_coords(), self.ctrl_held) {
+            (Some((col, row)), true) => format!("Cell: ({}, {}) n={}", col, row, self.board.alive_neighbours(col, row)),
+            (Some((col, row)), false) => format!("Cell: ({}, {})", col, row),
+            (None, _) => String::from("Cell: -"),
+        };
+        let mut lines = vec![
+            format!("Gen: {}", self.generation),
+            format!("Alive: {}", self.board.population()),
+            pointed,
+            match self.turbo {
+                true => format!("Speed: {}/s x{}", self.speed, self.turbo_steps),
+                false => format!("Speed: {}/s", self.speed),
+            },
+            match self.board.automaton() {
+                Automaton::Life => format!("Rule: {}", self.board.rule()),
+                automaton => format!("Automaton: {}", automaton),
+            },
+            String::from(wrap),
+            String::from(auto_pause),
+        ];
+        if self.board.automaton().has_colors() {
+            let (red, blue) = self.score();
+            lines.push(format!("Red: {} Blue: {}", red, blue));
+            lines.push(String::from(match self.board.capture() {
+                true => "Capture: on",
+                false => "Capture: off",
+            }));
+        }
+        if self.board.neighbourhood() != Neighbourhood::Moore {
+            lines.push(format!("Neighbours: {}", self.board.neighbourhood()));
+        }
+        if self.board.noise() > 0.0 {
+            lines.push(format!("noise p={}", self.board.noise()));
+        }
+        // Long seeds are cut off, the status shows the whole one
+        if self.show_seed {
+            lines.push(format!("Seed: {}", self.seed));
+        }
+        if !self.pause_on_edit {
+            lines.push(String::from("Edit pause: off"));
+        }
+        if self.lookahead {
+            lines.push(String::from("Preview: on"));
+        }
+        if let Some(period) = self.period {
+            lines.push(format!("Period {}", period));
+        }
+        let slots: Vec<String> = self.slots.iter().enumerate()
+            .map(|(i, occupied)| match occupied {
+                true => (i + 1).to_string(),
+                false => String::from("-"),
+            })
+            .collect();
+        lines.push(format!("Slots: {}", slots.join(" ")));
+        if let Some(recording) = &self.recording {
+            lines.push(format!("Recording: {}/{}", recording.len(), recording::MAX_FRAMES));
+        }
+        if self.stats.is_some() {
+            lines.push(String::from("Writing stats"));
+        }
+        if let Some(selection) = self.selection {
+            let (_, _, width, height) = selection.bounds();
+            lines.push(format!("Selected: {}x{}", width, height));
+        }
+        if let Some(BoundingBox{corners: ((left, top), (right, bottom)), ..}) = &self.bounding_box {
+            lines.push(format!("Box: {}x{}", right - left + 1, bottom - top + 1));
+        }
+        if let Some(stamp) = &self.stamp {
+            lines.push(format!("Stamp: {}", stamp.name));
+        }
+        if let Some((col, row)) = self.cursor {
+            lines.push(format!("Cursor: {},{}", col, row));
+        }
+        match self.tool {
+            Tool::Brush => (),
+            Tool::Line => lines.push(String::from("Tool: line")),
+            Tool::Rectangle => lines.push(String::from("Tool: rectangle")),
+        }
+        let brush_size = BRUSH_SIZES[self.brush];
+        lines.push(format!("Brush: {}x{}", brush_size, brush_size));
+        if self.symmetry != Symmetry::Off {
+            lines.push(format!("Symmetry: {}", self.symmetry));
+        }
+        self.menu_text.set_lines(&lines);
+        self.menu_text.set_changes(self.board.births(), self.board.deaths());
     }
 
-    
-    // TODO find a way to make different timesteps for mouse input and update rate
-    // Function to update the state
-    fn update(&mut self, ctx: &mut Context) -> Result{
 
-        self.mouse_coords = input::get_mouse_position(ctx).round();
+    // Function changes the speed of the game to the next or previous one of `SPEEDS`
+    fn change_speed(&mut self, faster: bool){
+        let next = match faster {
+            true => SPEEDS.iter().find(|speed| **speed > self.speed),
+            false => SPEEDS.iter().rev().find(|speed| **speed < self.speed),
+        };
+        if let Some(speed) = next {
+            self.speed = *speed;
+        }
+    }
 
-        // Revive or kill a cell with a LMB
-        if input::is_mouse_button_pressed(ctx, MouseButton::Left){
-            let pointed_cell_id =  self.point_to_cell();
-            if let Some(mut cell) = self.cells.get_mut(pointed_cell_id as usize) {
-                if cell.alive == false {
-                    cell.alive = true;
-                } else {
-                    cell.alive = false;
-                }
+
+    // Function applies the rules of the game once
+    // Edits made before it can't be undone
+    // Returns how the field ended up if it doesn't change anymore
+    fn step_once(&mut self) -> Option<Outcome>{
+        self.history.clear();
+        // Numbers of cells of both colors are compared after the generation to find the winner
+        let score = match self.board.automaton().has_colors() {
+            true => Some(self.score()),
+            false => None,
+        };
+        history::copy_into(&mut self.before, self.board.cells());
+        self.generations.push(&self.before);
+        let (births, deaths) = self.board.step();
+        self.generation += 1;
+        self.graph.push(self.board.population());
+        debug!("Gen {}: {} cells, +{} / -{}", self.generation, self.board.population(), births, deaths);
+        self.graph_changed = true;
+        // Noise keeps the field changing, so it never settles down or repeats itself for good
+        let noisy = self.board.noise() > 0.0;
+        self.period = match noisy {
+            true => None,
+            false => self.periods.check(&self.before, self.board.cells()),
+        };
+        // A full recording is saved, and the rest of the generation is checked as usual
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(&self.board);
+            if recording.is_full() {
+                self.stop_recording().unwrap_or_else(|e| self.show_error(e));
+            }
+        }
+        // Stats that can't be written are stopped, but the generation is still checked
+        if let Some(stats) = self.stats.as_mut() {
+            if let Err(source) = stats.record(self.generation, &self.board) {
+                self.stats = None;
+                let error = self.stats_error(source);
+                self.show_error(error);
             }
         }
 
-        // Start or pause the game with SPACE
-        if input::is_key_pressed(ctx, Key::Space){
-            self.running = !self.running;
-            match self.running {
-                true => self.status_text.text.set_content("Running"),
-                false => self.status_text.text.set_content("Paused"),
+        // The game is paused if the field doesn't change anymore
+        let outcome = match noisy {
+            true => None,
+            false => self.detector.check(&self.before, &self.board),
+        };
+        // A color wins when the other one dies out, which ends the game even without auto-pause
+        let victory = score.and_then(|score| versus::victory(score, self.score()));
+        let status = match (victory, outcome) {
+            (Some(victory), _) => Some(format!("{} at gen {}", victory, self.generation)),
+            (None, Some(_)) if !self.auto_pause => None,
+            (None, Some(Outcome::Extinct)) => Some(format!("Extinct at gen {}", self.generation)),
+            (None, Some(Outcome::Stabilized)) => Some(format!("Stabilized at gen {}", self.generation)),
+            (None, Some(Outcome::PeriodTwo)) => Some(format!("Period 2 at gen {}", self.generation)),
+            (None, None) => None,
+        };
+        if let Some(status) = status {
+            self.running = false;
+            self.flush_stats().unwrap_or_else(|e| self.show_error(e));
+            self.set_status(&status);
+        }
+        if let Some(victory) = victory {
+            self.menu_text.flash_color = match victory {
+                versus::Victory::Red => RED_COLOR,
+                versus::Victory::Blue => BLUE_COLOR,
+                versus::Victory::Draw => Color::WHITE,
             };
+            self.menu_text.flash_time = FLASH_SECONDS;
         }
-    
+        outcome
+    }
 
-        // Reset the game with R
-        if input::is_key_pressed(ctx, Key::R){
-            self.reset();
-        }
 
+    // Numbers of red and blue cells of automata with colors
+    fn score(&self) -> (usize, usize){
+        (self.board.population_of(automaton::RED), self.board.population_of(automaton::BLUE))
+    }
 
-        // Quit the game with Q
-        if input::is_key_pressed(ctx, Key::Q){
-            quit(ctx);
-        }
 
-        // Main part - updating cells coordinates and alive statuses
+    // Function asks for the generation to go to
+    // The game is paused while its number is typed
+    fn start_goto(&mut self){
         if self.running {
-
-            // Vector of indexes of cells that should be alive in the next iteration
-            // Contains `usize` elements!
-            let mut next_cells = Vec::new();
-
-            for id in 0..self.cells.len() {
-
-                // Convert id to i32 to do calculations
-                let id32 = id as i32;
-                
-                // Indexes of neighbours of the cell
-                let n_ids = [
-                    id32 - ROW_PARTS,
-                    id32 + ROW_PARTS,
-                    id32 - 1,
-                    id32 + 1,
-                    id32 - (ROW_PARTS - 1),
-                    id32 + (ROW_PARTS - 1),
-                    id32 - (ROW_PARTS + 1),
-                    id32 + (ROW_PARTS + 1),
-                ];
-
-                // A number of alive neighbours of the cell
-                let mut alive_neighbours = 0;
-                // Create a list all 8 neighbour cells
-                for n_id in n_ids{
-                    // If the neighbour is alive and the distance to the neighbour is less than length of cell side multiplied by 2 - increment the 
-                    // number of alive neighbours
-                    if let Some(n_cell) = self.cells.get(n_id as usize) { 
-                        if n_cell.alive && (self.cells[id].pos[1] as i32 - n_cell.pos[1] as i32).abs() <= (CELL_SIZE * 2.0) as i32{
-                            alive_neighbours += 1;
-                        }
-                    }
-                }
-                
-                // Check the total number of alive neighbours  
-                match alive_neighbours {
-                    // Cell survives if it has 2 or 3 neighbours
-                    // Cell revives if it has 3 neighbours
-                    // Cell dies in all other cases
-                    // Add indexes of cells that should be alive in the next iteration
-                    2 => {
-                        if self.cells[id].alive == true {
-                            next_cells.push(id);
-                        }
-                    },
-                    3 => {
-                        next_cells.push(id);
-                    },
-                    _ => ()
-                };
-            }
+            self.toggle_running();
+        }
+        self.batch = None;
+        self.goto_input = Some(String::new());
+        self.set_status("Go to gen: _");
+    }
 
 
-            // If none of cells should be alive on the next iteration - kill all of them
-            if next_cells.len() == 0{
-                for cell in self.cells.iter_mut(){
-                    cell.alive = false;
-                }
-            // Else - only leave alive those from next cells
+    // Function types digits of the generation to go to with pressed keys
+    // Enter goes to the generation and Escape stops asking for it
+    fn type_goto(&mut self, ctx: &mut Context){
+        let mut digits = match self.goto_input.take() {
+            Some(digits) => digits,
+            None => return,
+        };
+        if input::is_key_pressed(ctx, Key::Escape) {
+            self.set_status("Paused");
+            return;
+        }
+        if input::is_key_pressed(ctx, Key::Enter) || input::is_key_pressed(ctx, Key::NumPadEnter) {
+            match digits.parse() {
+                Ok(target) => self.go_to(target),
+                Err(_) => self.set_status("Paused"),
+            }
+            return;
+        }
+        for (digit, keys) in DIGIT_KEYS.iter().enumerate() {
+            if keys.iter().any(|key| input::is_key_pressed(ctx, *key)) && digits.len() < MAX_GOTO_DIGITS {
+                digits.push_str(&digit.to_string());
+            }
+        }
+        if input::is_key_pressed(ctx, Key::Backspace) {
+            digits.pop();
+        }
+        self.set_status(&format!("Go to gen: {}_", digits));
+        self.goto_input = Some(digits);
+    }
+
+
+    // Function goes to the generation
+    // Earlier generations are brought back from the history and later ones are made in a batch
+    fn go_to(&mut self, target: u32){
+        match target.cmp(&self.generation) {
+            Ordering::Equal => self.set_status(&format!("Already at gen {}", target)),
+            Ordering::Less => {
+                let back = (self.generation - target) as usize;
+                if back > self.generations.len() {
+                    self.set_status(&format!("Can't go back to gen {}, {} generations are kept", target, self.generations.len()));
+                    return;
+                }
+                for _ in 0..back {
+                    self.step_back();
+                }
+                self.set_status(&format!("Went back to gen {}", target));
+            },
+            Ordering::Greater => {
+                self.batch = Some(Batch::GoTo{from: self.generation, to: target});
+                self.set_status(&format!("Going to gen {}: 0%", target));
+            },
+        }
+    }
+
+
+    // Function runs the game without drawing generations until the field stabilizes
+    fn start_until_stable(&mut self){
+        if self.running {
+            self.toggle_running();
+        }
+        self.batch = Some(Batch::UntilStable{from: self.generation, cap: self.stable_cap});
+        self.set_status("Running until stable");
+    }
+
+
+    // Function makes the next chunk of generations of the batch and shows its progress
+    fn run_batch(&mut self){
+        let batch = match self.batch {
+            Some(batch) => batch,
+            None => return,
+        };
+        match batch {
+            Batch::GoTo{from, to} => {
+                for _ in 0..BATCH_GENERATIONS.min(to.saturating_sub(self.generation)) {
+                    self.step_once();
+                }
+                match self.generation >= to {
+                    true => {
+                        self.batch = None;
+                        self.set_status(&format!("Went to gen {}", self.generation));
+                    },
+                    false => {
+                        let progress = u64::from(self.generation.saturating_sub(from)) * 100 / u64::from(to - from);
+                        self.set_status(&format!("Going to gen {}: {}%", to, progress));
+                    },
+                }
+            },
+            // Extinct and stable fields (and oscillators with period 2 if auto-pause looks for them) end the batch
+            Batch::UntilStable{from, cap} => {
+                let mut outcome = None;
+                for _ in 0..BATCH_GENERATIONS {
+                    outcome = self.step_once();
+                    if outcome.is_some() || self.generation.saturating_sub(from) >= cap {
+                        break;
+                    }
+                }
+                let population = self.board.population();
+                let status = match outcome {
+                    Some(Outcome::Extinct) => format!("Extinct at gen {}", self.generation),
+                    Some(Outcome::Stabilized) => format!("Stabilized at gen {} with {} cells", self.generation, population),
+                    Some(Outcome::PeriodTwo) => format!("Period 2 at gen {} with {} cells", self.generation, population),
+                    None if self.generation.saturating_sub(from) >= cap => {
+                        format!("Not stable after {} gens: gen {} with {} cells", cap, self.generation, population)
+                    },
+                    None => {
+                        self.set_status(&format!("Running until stable: gen {}", self.generation));
+                        return;
+                    },
+                };
+                self.batch = None;
+                self.flush_stats().unwrap_or_else(|e| self.show_error(e));
+                self.set_status(&status);
+            },
+        }
+    }
+
+
+    // Function starts the graph of population over from the current generation
+    fn restart_graph(&mut self){
+        self.graph.clear();
+        self.graph.push(self.board.population());
+        self.graph_changed = true;
+    }
+
+
+    // Function builds the mesh of cells again if the board or the look of cells has changed since it was built
+    // Every cell is a quad of its own color, so thousands of cells take a single draw call
+    fn update_field_mesh(&mut self, ctx: &mut Context) -> Result{
+        let theme = &self.themes[self.theme];
+        let look = CellLook{
+            cell_size: self.cell_size,
+            alive: color(theme.cell_alive),
+            background: color(theme.background),
+            age_colors: self.age_colors,
+            birth_colors: self.birth_colors,
+            trails: self.trails,
+            trail_length: self.trail_length,
+        };
+        if !self.board.changed() && self.field_look.as_ref() == Some(&look) {
+            return Ok(());
+        }
+        // Quads are a bit smaller than cells for the grid lines to fit, as the mesh of a single cell
+        let gap = LINE_WIDTH * 0.5;
+        let side = self.cell_size - 2.0 * gap;
+        let quads = self.cell_quads(&look);
+        let mut builder = GeometryBuilder::new();
+        for (pos, quad_color) in quads.iter() {
+            builder.set_color(*quad_color).rectangle(ShapeStyle::Fill, Rectangle::new(pos.x + gap, pos.y + gap, side, side))?;
+        }
+        self.field_mesh = match quads.is_empty() {
+            true => None,
+            false => Some(builder.build_mesh(ctx)?),
+        };
+        self.field_look = Some(look);
+        self.board.mark_drawn();
+        Ok(())
+    }
+
+
+    // Function finds positions and colors of all cells that are drawn
+    // Trails of dead cells come first to be drawn below alive cells
+    fn cell_quads(&self, look: &CellLook) -> Vec<(Vec2<f32>, Color)>{
+        let mut quads = Vec::new();
+        if look.trails {
+            for cell in self.cells.iter(){
+                let (col, row) = cell.coords;
+                let since_death = self.board.since_death(col, row);
+                if since_death == 0 || since_death > look.trail_length {
+                    continue;
+                }
+                let fade = 1.0 - (since_death - 1) as f32 / look.trail_length as f32;
+                quads.push((cell.pos, look.alive.with_alpha(TRAIL_ALPHA * fade)));
+            }
+        }
+
+        for cell in self.cells.iter(){
+            // *only alive cells
+            let (col, row) = cell.coords;
+            // Cells of other automata and dying cells of Generations rules are colored by their states
+            let state = self.board.state(col, row);
+            if self.board.automaton() != Automaton::Life || state > automaton::ALIVE {
+                if let Some(cell_color) = state_color(&self.board, state, look.alive, look.background) {
+                    quads.push((cell.pos, cell_color));
+                }
+                continue;
+            }
+            if self.board.get(col, row) {
+                let cell_color = match look.age_colors {
+                    true => age_color(look.alive, self.board.age(col, row)),
+                    false => look.alive,
+                };
+                let cell_color = match look.birth_colors && !self.board.is_newborn(col, row) {
+                    true => mix_colors(cell_color, look.background, SURVIVOR_FADE),
+                    false => cell_color,
+                };
+                quads.push((cell.pos, cell_color));
+            }
+        }
+        quads
+    }
+
+
+    // Function builds the line of the graph again if the graph has changed since it was built
+    // A single generation makes no line
+    fn update_graph_mesh(&mut self, ctx: &mut Context) -> Result{
+        if !self.graph_changed {
+            return Ok(());
+        }
+        self.graph_changed = false;
+        self.graph_mesh = match self.graph.len() >= 2 {
+            true => {
+                let points: Vec<Vec2<f32>> = self.graph.points(GRAPH_SIZE.0, GRAPH_SIZE.1).into_iter()
+                    .map(|(x, y)| Vec2::new(x, y))
+                    .collect();
+                Some(Mesh::polyline(ctx, GRAPH_LINE_WIDTH, &points)?)
+            },
+            false => None,
+        };
+        Ok(())
+    }
+
+    // Function finds the bounding box of alive cells if it's shown
+    // Its outline is built again only if the box has changed
+    fn update_bounding_box(&mut self, ctx: &mut Context) -> Result{
+        let corners = match self.show_bounding_box {
+            true => self.board.bounding_box(),
+            false => None,
+        };
+        self.bounding_box = match (corners, self.bounding_box.take()) {
+            (None, _) => None,
+            (Some(corners), Some(built)) if built.corners == corners => Some(built),
+            (Some(corners), _) => {
+                let ((left, top), (right, bottom)) = corners;
+                let rect = Rectangle::new(
+                    left as f32 * self.cell_size,
+                    top as f32 * self.cell_size,
+                    (right - left + 1) as f32 * self.cell_size,
+                    (bottom - top + 1) as f32 * self.cell_size,
+                    );
+                Some(BoundingBox{corners, mesh: Mesh::rectangle(ctx, ShapeStyle::Stroke(LINE_WIDTH), rect)?})
+            },
+        };
+        Ok(())
+    }
+
+    // Function moves the keyboard cursor by the offset and paints the cell it moves to with `paint`
+    // The first move puts the cursor in the middle of the field
+    // The cursor stops at the edges of the field or goes around them if the field wraps
+    fn move_cursor(&mut self, offset: (i32, i32), paint: bool){
+        self.keyboard_used = true;
+        let cursor = match self.cursor {
+            Some(cursor) => self.board.moved_cell(cursor, offset),
+            None => (self.board.width() / 2, self.board.height() / 2),
+        };
+        self.cursor = Some(cursor);
+        if paint {
+            self.edit_with_cursor(cursor, automaton::ALIVE);
+        }
+    }
+
+
+    // Function toggles the cell under the keyboard cursor the way a click does
+    fn toggle_cursor_cell(&mut self){
+        self.keyboard_used = true;
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None => {
+                self.set_status("Move the cursor with Ctrl+arrows first");
+                return;
+            },
+        };
+        let state = self.board.automaton().clicked(self.board.state(cursor.0, cursor.1));
+        self.edit_with_cursor(cursor, state);
+    }
+
+
+    // Function paints the brush at the cell of the keyboard cursor, so it can be undone
+    // The running game is paused first, like it is by a click
+    fn edit_with_cursor(&mut self, cursor: (i32, i32), state: u8){
+        if self.running && self.pause_on_edit {
+            self.toggle_running();
+            self.set_status("Paused (editing)");
+        }
+        self.period = None;
+        let edit = self.paint_cell(cursor, state);
+        self.history.push(edit);
+    }
+
+
+    // Function zooms and pans the field for alive cells to fill most of it in the middle
+    // The whole field is shown if there are no alive cells
+    fn fit_view(&mut self){
+        self.transform = match self.board.bounding_box() {
+            Some(((left, top), (right, bottom))) => {
+                let rect = (
+                    left as f32 * self.cell_size,
+                    top as f32 * self.cell_size,
+                    (right - left + 1) as f32 * self.cell_size,
+                    (bottom - top + 1) as f32 * self.cell_size,
+                    );
+                view::Transform::fitted(rect, (self.field_area.width, self.field_area.height))
+            },
+            None => view::Transform::default(),
+        };
+    }
+
+    // Function forgets generations the period of the field was found with
+    fn forget_period(&mut self){
+        self.periods.clear();
+        self.period = None;
+    }
+
+
+    // Function brings back the previous generation
+    // Edits made before it can't be undone
+    fn step_back(&mut self){
+        match self.generations.pop() {
+            Some(cells) => {
+                self.history.clear();
+                self.board.restore(&cells);
+                self.period = None;
+                self.generation -= 1;
+                self.graph.pop();
+                self.graph_changed = true;
+            },
+            None => self.set_status("No earlier generations"),
+        }
+    }
+
+
+    // Function starts or pauses the game
+    // Cells are remembered on start to reset the field to them later
+    fn toggle_running(&mut self){
+        self.running = !self.running;
+        match self.running {
+            true => {
+                self.snapshot = Some(self.board.cells().to_vec());
+                self.set_status("Running");
+            },
+            false => {
+                self.flush_stats().unwrap_or_else(|e| self.show_error(e));
+                self.set_status("Paused");
+            },
+        };
+    }
+
+
+    // Function makes labels of buttons match the state of the game
+    fn update_buttons(&mut self){
+        let start_pause = match self.running {
+            true => "Pause",
+            false => "Start",
+        };
+        for button in self.buttons.iter_mut() {
+            if let ButtonAction::StartPause = button.action {
+                button.label.set_content(start_pause);
+            }
+        }
+    }
+
+
+    // Function brings back cells the game was started with and pauses the game
+    fn reset_to_snapshot(&mut self){
+        let cells = match self.snapshot.clone() {
+            Some(cells) => cells,
+            None => {
+                self.set_status("Nothing to reset to");
+                return;
+            },
+        };
+        self.running = false;
+        self.generation = 0;
+        self.history.clear();
+        self.generations.clear();
+        self.forget_period();
+        self.board.restore(&cells);
+        self.restart_graph();
+        self.set_status("Reset");
+    }
+
+
+    // Function kills all cells and pauses the game
+    // Walls and immortal cells are kept unless the clear is `full`
+    fn clear(&mut self, full: bool){
+        self.running = false;
+        self.generation = 0;
+        self.history.clear();
+        self.generations.clear();
+        self.forget_period();
+        // The board is emptied before the graph starts over, so the graph starts from the cells that are left
+        match full {
+            true => self.board.wipe(),
+            false => self.board.clear(),
+        }
+        self.show_seed = false;
+        self.restart_graph();
+        self.set_status("Paused");
+    }
+
+
+    // Function fills the field with random cells and pauses the game
+    // The same seed always gives the same cells, so the seed is printed to be shared
+    fn randomize(&mut self){
+        self.running = false;
+        self.generation = 0;
+        self.history.clear();
+        self.generations.clear();
+        self.forget_period();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        self.board.randomize(self.density, &mut rng);
+        // The noise starts over too, so the same seed gives the same noisy run
+        self.board.set_noise(self.board.noise_probability(), self.seed);
+        self.show_seed = true;
+        self.restart_graph();
+        info!("Filled with --seed {} --density {}", self.seed, self.density);
+        self.set_status(&format!("Filled with seed {}", self.seed));
+    }
+
+
+
+    // Function paints the first cell of a new stroke
+    fn start_stroke(&mut self, button: MouseButton, state: u8, coords: (i32, i32)){
+        self.period = None;
+        let edit = self.paint_cell(coords, state);
+        self.stroke = Some(Stroke{button, state, last: coords, edit});
+    }
+
+
+    // Function paints all cells between the last painted cell of the stroke and the given one
+    // Fast mouse movements don't leave gaps this way
+    fn continue_stroke(&mut self, coords: (i32, i32)){
+        let (last, state) = match self.stroke.as_ref() {
+            Some(stroke) if stroke.last != coords => (stroke.last, stroke.state),
+            _ => return,
+        };
+        let mut edit = Edit::new();
+        for cell in shapes::line(last, coords) {
+            edit.extend(self.paint_cell(cell, state));
+        }
+        if let Some(stroke) = self.stroke.as_mut() {
+            stroke.edit.extend(edit);
+            stroke.last = coords;
+        }
+    }
+
+
+    // Function paints the brush centered on the cell together with its copies mirrored with the current symmetry
+    // Cells of the brush outside of the field are cut off
+    fn paint_cell(&mut self, coords: (i32, i32), state: u8) -> Edit{
+        self.brush_cells(coords)
+            .into_iter()
+            .filter_map(|(col, row)| undo::paint_state(&mut self.board, col, row, state))
+            .collect()
+    }
+
+
+    // Function returns cells the brush centered on the cell paints, including mirrored ones
+    fn brush_cells(&self, coords: (i32, i32)) -> Vec<(i32, i32)>{
+        let (width, height) = (self.board.width(), self.board.height());
+        shapes::square(coords, BRUSH_SIZES[self.brush])
+            .into_iter()
+            .flat_map(|(col, row)| self.symmetry.mirrored(col, row, width, height))
+            .collect()
+    }
+
+
+    // Function changes the brush to the next or the previous size of `BRUSH_SIZES`
+    // Sizes go around from the last one to the first one if `wrap` is set
+    fn change_brush_size(&mut self, larger: bool, wrap: bool){
+        let last = BRUSH_SIZES.len() - 1;
+        self.brush = match (larger, wrap) {
+            (true, true) if self.brush == last => 0,
+            (true, _) => (self.brush + 1).min(last),
+            (false, _) => self.brush.saturating_sub(1),
+        };
+        let size = BRUSH_SIZES[self.brush];
+        self.set_status(&format!("Brush: {}x{}", size, size));
+    }
+
+
+    // Function finishes the stroke, so it can be undone as a whole
+    fn end_stroke(&mut self){
+        if let Some(stroke) = self.stroke.take() {
+            self.history.push(stroke.edit);
+        }
+    }
+
+
+    // Function revives cells of the stamp with its upper left corner at the given cell
+    // Cells outside of the field are cut off
+    fn stamp_cells(&mut self, stamp: &Stamp, coords: (i32, i32)){
+        let cells: Vec<(i32, i32)> = stamp.cells.iter().map(|(dx, dy)| (coords.0 + dx, coords.1 + dy)).collect();
+        self.revive_cells(&cells);
+    }
+
+
+    // Function revives the given cells, so they can be undone as a whole
+    // Cells outside of the field are cut off
+    fn revive_cells(&mut self, cells: &[(i32, i32)]){
+        let edit = cells.iter()
+            .filter_map(|(col, row)| undo::paint(&mut self.board, *col, *row, true))
+            .collect();
+        self.period = None;
+        self.history.push(edit);
+    }
+
+
+    // Function remembers the first cell of a line or revives the line from it to the given cell
+    fn click_line(&mut self, coords: (i32, i32)){
+        match self.line_start.take() {
+            Some(start) => self.revive_cells(&shapes::line(start, coords)),
+            None => self.line_start = Some(coords),
+        }
+    }
+
+
+    // Function copies alive cells of the selection to the clipboard
+    // The selected cells are also cleared if `cut` is set
+    fn copy_selection(&mut self, cut: bool){
+        let selection = match self.selection {
+            Some(selection) => selection,
+            None => {
+                self.set_status("Nothing selected");
+                return;
+            },
+        };
+        self.clipboard = selection::copy(&self.board, &selection);
+        match cut {
+            true => {
+                self.end_stroke();
+                self.period = None;
+                let edit = selection::clear(&mut self.board, &selection);
+                self.history.push(edit);
+                self.set_status(&format!("Cut {} cells", self.clipboard.len()));
+            },
+            false => self.set_status(&format!("Copied {} cells", self.clipboard.len())),
+        }
+    }
+
+
+    // Function makes the copied cells the stamp of the next click
+    fn paste(&mut self){
+        match self.clipboard.is_empty() {
+            true => self.set_status("Nothing to paste"),
+            false => self.stamp = Some(Stamp{name: String::from("clipboard"), cells: self.clipboard.clone()}),
+        }
+    }
+
+
+    // Function puts all cells on the field to the system clipboard as RLE text
+    fn copy_rle(&mut self) -> std::result::Result<(), GolError>{
+        let cells = self.board.alive_cells();
+        if cells.is_empty() {
+            self.set_status("Nothing to copy");
+            return Ok(());
+        }
+        let text = pattern_io::board_rle(&self.board);
+        Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text))?;
+        self.set_status(&format!("Copied {} cells as RLE{}", cells.len(), self.walls_note()));
+        Ok(())
+    }
+
+
+    // Function returns a warning added to the status when the field is saved as RLE
+    // RLE files have no walls, so they are left out
+    fn walls_note(&self) -> &'static str{
+        match self.board.cells_in(automaton::WALL).is_empty() {
+            true => "",
+            false => " (walls are left out)",
+        }
+    }
+
+
+    // Function makes the RLE pattern or ASCII art from the system clipboard the stamp of the next click
+    fn paste_rle(&mut self) -> std::result::Result<(), GolError>{
+        let text = Clipboard::new().and_then(|mut clipboard| clipboard.get_text())?;
+        let pattern = pattern_io::from_text(&text)?;
+        self.stamp = Some(Stamp{name: String::from("RLE"), cells: pattern.cells});
+        Ok(())
+    }
+
+
+    // Function cancels the selection, the stamp or the started shape
+    // The shape tool is put away if there is no shape, and the game is quit if there is nothing to cancel
+    fn cancel(&mut self, ctx: &mut Context){
+        // A batch of generations is stopped first
+        if self.batch.take().is_some() {
+            self.set_status(&format!("Stopped at gen {}", self.generation));
+            return;
+        }
+        if self.selection.is_none() && self.stamp.is_none() && self.line_start.is_none() && self.rectangle_start.is_none() {
+            match self.tool {
+                Tool::Brush => quit(ctx),
+                Tool::Line | Tool::Rectangle => self.tool = Tool::Brush,
+            }
+        }
+        self.selection = None;
+        self.selecting = false;
+        self.stamp = None;
+        self.line_start = None;
+        self.rectangle_start = None;
+    }
+
+
+    // Function switches to the tool or back to the brush if it is the current one
+    // Started shapes are forgotten
+    fn toggle_tool(&mut self, tool: Tool){
+        self.line_start = None;
+        self.rectangle_start = None;
+        self.tool = match self.tool == tool {
+            true => Tool::Brush,
+            false => tool,
+        };
+    }
+
+
+    // Function reverts the last manual edit of cells
+    fn undo(&mut self){
+        self.period = None;
+        self.end_stroke();
+        match self.history.undo(&mut self.board) {
+            true => self.set_status("Undone"),
+            false => self.set_status("Nothing to undo"),
+        }
+    }
+
+
+    // Function applies the last undone edit of cells again
+    fn redo(&mut self){
+        self.period = None;
+        self.end_stroke();
+        match self.history.redo(&mut self.board) {
+            true => self.set_status("Redone"),
+            false => self.set_status("Nothing to redo"),
+        }
+    }
+
+
+    // Function replaces all cells with the pattern from the file
+    // The pattern is placed in the center of the field and the game is paused
+    fn load_pattern(&mut self) -> std::result::Result<(), GolError>{
+        let path = self.pattern_path.clone().unwrap_or_else(|| String::from(DEFAULT_PATTERN_PATH));
+        self.load_pattern_file(Path::new(&path)).map_err(|source| GolError::Load{path: PathBuf::from(&path), source})?;
+        // Only files given on purpose are remembered, not the default one
+        if self.pattern_path.is_some() {
+            self.remember(Path::new(&path));
+        }
+        Ok(())
+    }
+
+
+    // Function replaces all cells with the pattern from the file in the center of the field
+    // The game is paused and the generation starts over, while a failed load leaves the game as it was
+    fn load_pattern_file(&mut self, path: &Path) -> std::result::Result<(), LoadError>{
+        let pattern = pattern_io::load(path)?;
+        self.running = false;
+        self.generation = 0;
+        self.history.clear();
+        self.generations.clear();
+        self.forget_period();
+        self.board.clear();
+        pattern_io::stamp_centered(&mut self.board, &pattern);
+        self.show_seed = false;
+        self.restart_graph();
+        self.set_status("Paused");
+        Ok(())
+    }
+
+
+    // Function puts the file on top of the recent files and saves the list
+    fn remember(&mut self, path: &Path){
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        self.recent.push(&path);
+        self.save_recent();
+    }
+
+
+    // Function writes the recent files to their file in the data directory
+    fn save_recent(&self){
+        if let Some(path) = recent::default_path() {
+            if let Err(e) = recent::save(&self.recent, &path) {
+                warn!("Can't save recent files to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+
+    // Function checks if the help overlay or the pattern browser covers the field
+    fn overlay_shown(&self) -> bool{
+        self.help.visible || self.browser.is_open()
+    }
+
+
+    // Function lists pattern files of the patterns directory over the field
+    // The directory is read every time, so files added to it while the game runs are listed too
+    fn open_browser(&mut self){
+        match library::scan(Path::new(PATTERNS_DIR)) {
+            Ok(entries) => {
+                // A stroke is not continued under the browser
+                self.end_stroke();
+                self.browser.library = Some(Library::new(entries));
+                self.browser.recent = false;
+                self.update_browser_text();
+            },
+            Err(e) => self.log_status(Level::Warn, &format!("Can't read {}: {}", PATTERNS_DIR, e)),
+        }
+    }
+
+
+    // Function lists the recently loaded pattern files over the field
+    fn open_recent(&mut self){
+        self.end_stroke();
+        self.browser.library = Some(Library::new(library::entries_of(self.recent.paths())));
+        self.browser.recent = true;
+        self.update_browser_text();
+    }
+
+
+    // Function filters and picks files of the browser with pressed keys
+    // Typed text filters the files, Up and Down select one of them and Escape closes the browser
+    // Enter makes the file the stamp of the next click, or loads a recent file like `--pattern` does
+    fn browse(&mut self, ctx: &mut Context){
+        let library = match self.browser.library.as_mut() {
+            Some(library) => library,
+            None => return,
+        };
+        if input::is_key_pressed(ctx, Key::Escape) {
+            self.browser.library = None;
+            return;
+        }
+        if input::is_key_pressed(ctx, Key::Enter) || input::is_key_pressed(ctx, Key::NumPadEnter) {
+            let picked = library.selected().map(|entry| (entry.path.clone(), entry.missing));
+            match (picked, self.browser.recent) {
+                // A recent file that is gone is taken off the list, which stays open
+                (Some((path, true)), _) => {
+                    self.recent.remove(&path);
+                    self.save_recent();
+                    self.open_recent();
+                    self.set_status(&format!("{} is gone", path.display()));
+                },
+                (Some((path, false)), true) => {
+                    self.browser.library = None;
+                    match self.load_pattern_file(&path) {
+                        Ok(_) => self.remember(&path),
+                        Err(source) => self.show_error(GolError::Load{path, source}),
+                    }
+                },
+                (Some((path, false)), false) => {
+                    self.browser.library = None;
+                    self.stamp_file(&path);
+                },
+                (None, _) => self.browser.library = None,
+            }
+            return;
+        }
+        if input::is_key_pressed(ctx, Key::Up) {
+            library.move_selection(-1);
+        }
+        if input::is_key_pressed(ctx, Key::Down) {
+            library.move_selection(1);
+        }
+        if input::is_key_pressed(ctx, Key::Backspace) {
+            library.erase();
+        }
+        if let Some(text) = input::get_text_input(ctx) {
+            library.type_text(text);
+        }
+        self.update_browser_text();
+    }
+
+
+    // Function makes the pattern of the file the stamp of the next click
+    // Files picked in the browser and dropped onto the window are stamped the same way
+    fn stamp_file(&mut self, path: &Path){
+        let name = path.file_stem().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        let file_name = path.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        match pattern_io::load(path) {
+            Ok(pattern) => {
+                self.stamp = Some(Stamp{name, cells: pattern.cells});
+                self.set_status(&format!("Click to stamp {}", file_name));
+                self.remember(path);
+            },
+            Err(e) => self.log_status(Level::Warn, &format!("Can't load {}: {}", file_name, e)),
+        }
+    }
+
+
+    // Function writes the filter and the files matching it to the texts of the browser
+    // Missing files go to the gray text and the other ones to the white text, each on its own line
+    // The list is scrolled to keep the selected file on it
+    fn update_browser_text(&mut self){
+        let library = match self.browser.library.as_ref() {
+            Some(library) => library,
+            None => return,
+        };
+        let (title, empty, enter) = match self.browser.recent {
+            true => ("Recent patterns", String::from("No recent patterns"), "load"),
+            false => ("Patterns", format!("No patterns in {}", PATTERNS_DIR), "stamp"),
+        };
+        let mut content = format!("{}: {}_\n\n", title, library.filter());
+        let mut missing = String::from("\n\n");
+        let matching = library.matching();
+        let first = library.selected_index().saturating_sub(BROWSER_ROWS - 1);
+        for (index, entry) in matching.iter().enumerate().skip(first).take(BROWSER_ROWS) {
+            let marker = match index == library.selected_index() {
+                true => ">",
+                false => " ",
+            };
+            let size = entry.size.map_or(String::from("?"), |(width, height)| format!("{}x{}", width, height));
+            let line = format!("{} {:<40}{}\n", marker, entry.name, size);
+            match entry.missing {
+                true => {
+                    missing.push_str(&line);
+                    content.push('\n');
+                },
+                false => {
+                    content.push_str(&line);
+                    missing.push('\n');
+                },
+            }
+        }
+        if matching.is_empty() {
+            content.push_str(&format!("  {}\n", empty));
+        }
+        content.push_str(&format!("\nUp/Down to pick, Enter to {}, Esc to close", enter));
+        self.browser.text.set_content(content);
+        self.browser.missing_text.set_content(missing);
+    }
+
+
+    // Function replaces all cells with cells covered by dark parts of the picture and pauses the game
+    // The picture is scaled to the field keeping its proportions
+    fn load_picture(&mut self, path: &str, threshold: u8){
+        self.running = false;
+        match picture::load(path, self.board.width(), self.board.height(), threshold) {
+            Ok(cells) => {
+                self.generation = 0;
+                self.history.clear();
+                self.generations.clear();
+                self.forget_period();
+                self.board.clear();
+                self.board.stamp(0, 0, &cells);
+                self.show_seed = false;
+                self.restart_graph();
+                self.log_status(Level::Info, &format!("Loaded {}", path));
+            },
+            Err(e) => self.log_status(Level::Warn, &format!("Can't load {}: {}", path, e)),
+        }
+    }
+
+
+    // Function adds the theme from the file to the themes and selects it
+    fn load_theme(&mut self){
+        match theme::load(THEME_PATH) {
+            Ok(theme) => {
+                self.themes.push(theme);
+                self.theme = self.themes.len() - 1;
+            },
+            Err(e) => self.log_status(Level::Warn, &format!("Can't load {}: {}", THEME_PATH, e)),
+        }
+    }
+
+
+    // Function writes all cells on the field to the RLE file
+    fn save_pattern(&mut self) -> std::result::Result<(), GolError>{
+        let cells = self.board.alive_cells();
+        if cells.is_empty() {
+            self.set_status("Nothing to save");
+            return Ok(());
+        }
+        fs::create_dir_all(SAVES_DIR)
+            .and_then(|_| fs::write(SAVE_PATH, pattern_io::board_rle(&self.board)))
+            .map_err(|source| GolError::Save{path: PathBuf::from(SAVE_PATH), source})?;
+        self.log_status(Level::Info, &format!("Saved to {}{}", SAVE_PATH, self.walls_note()));
+        Ok(())
+    }
+
+
+    // Function saves a PNG picture of the field with the colors of the theme
+    // The picture has `render::CELL_PIXELS` pixels per cell whatever the zoom is
+    fn save_screenshot(&mut self) -> std::result::Result<(), GolError>{
+        let frame = render::render(&self.board, &self.themes[self.theme], render::CELL_PIXELS, self.show_grid);
+        let path = Path::new(SCREENSHOTS_DIR).join(format!("gol_{}.png", timestamp()));
+        render::save_png(&frame, &path).map_err(|source| GolError::Save{path, source})?;
+        self.log_status(Level::Info, "Saved screenshot");
+        Ok(())
+    }
+
+
+    // Function writes the field as ASCII art of `.` and `#` to a text file
+    // Only the bounding box of alive cells is written unless the full field is asked for
+    fn dump_ascii(&mut self, full: bool) -> std::result::Result<(), GolError>{
+        if !full && self.board.population() == 0 {
+            self.set_status("Nothing to save");
+            return Ok(());
+        }
+        let path = Path::new(DUMPS_DIR).join(format!("gol_{}.txt", timestamp()));
+        fs::create_dir_all(DUMPS_DIR)
+            .and_then(|_| fs::write(&path, pattern_io::ascii_dump(&self.board, full)))
+            .map_err(|source| GolError::Save{path: path.clone(), source})?;
+        self.log_status(Level::Info, &format!("Saved {}", path.display()));
+        Ok(())
+    }
+
+
+    // Function saves the recorded generations to an animated GIF
+    // Every generation is shown as long as it is in the game at its current speed
+    // The status tells why the recording stopped even if it can't be saved
+    fn stop_recording(&mut self) -> std::result::Result<(), GolError>{
+        let recording = match self.recording.take() {
+            Some(recording) => recording,
+            None => return Ok(()),
+        };
+        let path = Path::new(RECORDINGS_DIR).join(format!("gol_{}.gif", timestamp()));
+        let delay = (100.0 / self.speed).round().max(2.0) as u16;
+        let full = match recording.is_full() {
+            true => format!("Recording limit of {} frames reached. ", recording::MAX_FRAMES),
+            false => String::new(),
+        };
+        if recording.is_full() {
+            self.set_status(full.trim_end());
+        }
+        recording.save_gif(&path, &self.themes[self.theme], self.show_grid, delay)
+            .map_err(|source| GolError::Save{path: path.clone(), source})?;
+        self.log_status(Level::Info, &format!("{}Saved {}", full, path.display()));
+        Ok(())
+    }
+
+
+    // Function starts or stops appending population stats of every generation to the stats file
+    fn toggle_stats(&mut self) -> std::result::Result<(), GolError>{
+        match self.stats.take() {
+            Some(mut stats) => {
+                stats.flush().map_err(|source| self.stats_error(source))?;
+                self.log_status(Level::Info, &format!("Saved stats to {}", self.stats_path));
+            },
+            None => {
+                self.stats = Some(StatsLog::append(&self.stats_path).map_err(|source| self.stats_error(source))?);
+                self.log_status(Level::Info, &format!("Writing stats to {}", self.stats_path));
+            },
+        }
+        Ok(())
+    }
+
+
+    // Error of the stats file that can't be written
+    fn stats_error(&self, source: io::Error) -> GolError{
+        GolError::Save{path: PathBuf::from(&self.stats_path), source}
+    }
+
+
+    // Function writes buffered stats to the file, so it can be read while the game is paused
+    // Stats that can't be written are stopped
+    fn flush_stats(&mut self) -> std::result::Result<(), GolError>{
+        let result = match self.stats.as_mut() {
+            Some(stats) => stats.flush(),
+            None => return Ok(()),
+        };
+        result.map_err(|source| {
+            self.stats = None;
+            self.stats_error(source)
+        })
+    }
+
+
+    // Function writes all cells on the field to the slot (numbered from 1)
+    fn save_slot(&mut self, slot: usize) -> std::result::Result<(), GolError>{
+        slots::save(&self.board, Path::new(SAVES_DIR), slot)
+            .map_err(|source| GolError::Save{path: slots::path(Path::new(SAVES_DIR), slot), source})?;
+        self.slots[slot - 1] = true;
+        self.log_status(Level::Info, &format!("Saved to slot {}", slot));
+        Ok(())
+    }
+
+
+    // Function replaces all cells with the cells of the slot (numbered from 1) and pauses the game
+    // An empty slot leaves the game as it is
+    fn load_slot(&mut self, slot: usize){
+        match slots::load(&mut self.board, Path::new(SAVES_DIR), slot) {
+            Ok(true) => {
+                self.running = false;
+                self.generation = 0;
+                self.history.clear();
+                self.generations.clear();
+                self.forget_period();
+                self.show_seed = false;
+                self.restart_graph();
+                self.log_status(Level::Info, &format!("Loaded slot {}", slot));
+            },
+            Ok(false) => self.set_status(&format!("Slot {} empty", slot)),
+            Err(source) => self.show_error(GolError::Load{path: slots::path(Path::new(SAVES_DIR), slot), source}),
+        }
+    }
+
+
+    // Function returns column and row of the cell under the cursor
+    // Returns `None` if the cursor is not over the field
+    fn pointed_cell_coords(&self) -> Option<(i32, i32)> {
+        self.cell_coords_at(self.mouse_coords)
+    }
+
+
+    // Function returns column and row of the cell under the point of the window
+    // Returns `None` if the point is not over the field
+    fn cell_coords_at(&self, point: Vec2<f32>) -> Option<(i32, i32)> {
+        // The menu covers the field, so cells under it can't be pointed at
+        if !self.field_area.contains_point(point) {
+            return None;
+        }
+        let point = self.transform.to_field((point.x, point.y));
+        view::point_to_cell(point, self.cell_size, self.board.width(), self.board.height())
+    }
+
+
+    // Function returns column and row of the cell of the field nearest to the point of the window
+    fn clamped_cell_coords_at(&self, point: Vec2<f32>) -> (i32, i32) {
+        let point = self.transform.to_field((point.x, point.y));
+        view::clamped_point_to_cell(point, self.cell_size, self.board.width(), self.board.height())
+    }
+
+
+    // Function does the action of a pressed key
+    // `index` is the position of the pressed key among the keys of the binding
+    fn do_key_action(&mut self, ctx: &mut Context, action: KeyAction, index: usize) -> Result{
+        match action {
+            KeyAction::ToggleHelp => {
+                // A stroke is not continued under the overlay
+                self.end_stroke();
+                self.help.visible = !self.help.visible;
+            },
+            KeyAction::SelectPattern => if let Some(pattern) = patterns::PRESETS.get(index) {
+                self.stamp = Some(Stamp{name: String::from(pattern.name), cells: pattern.cells.to_vec()});
+            },
+            // Cells on the field are left as they are
+            KeyAction::SelectRule => if let Some(rule) = rule::PRESETS.get(index) {
+                self.board.set_rule(*rule);
+            },
+            KeyAction::CycleNeighbourhood => {
+                self.board.set_neighbourhood(self.board.neighbourhood().next());
+                self.set_status(&format!("Neighbours: {}", self.board.neighbourhood()));
+            },
+            KeyAction::Undo => self.undo(),
+            KeyAction::Redo => self.redo(),
+            KeyAction::StartPause => self.toggle_running(),
+            KeyAction::ToggleTurbo => {
+                self.turbo = !self.turbo;
+                match self.turbo {
+                    true => self.set_status(&format!("Turbo: x{}", self.turbo_steps)),
+                    false => self.set_status("Turbo: off"),
+                }
+            },
+            KeyAction::Reset => self.reset_to_snapshot(),
+            KeyAction::Step => if !self.running {
+                self.step_once();
+            },
+            KeyAction::StepBack => if !self.running {
+                self.step_back();
+            },
+            KeyAction::GoTo => self.start_goto(),
+            KeyAction::RunUntilStable => self.start_until_stable(),
+            KeyAction::SpeedUp => self.change_speed(true),
+            KeyAction::SlowDown => self.change_speed(false),
+            KeyAction::Coarser => self.change_resolution(ctx, false)?,
+            KeyAction::Finer => self.change_resolution(ctx, true)?,
+            KeyAction::Clear => self.clear(false),
+            KeyAction::Wipe => self.clear(true),
+            KeyAction::RandomizeNewSeed => {
+                self.seed = rand::random();
+                self.randomize();
+            },
+            // The field is left as it is while there is a stamp to turn
+            KeyAction::Randomize => match self.stamp.as_mut() {
+                Some(stamp) => stamp.cells = shapes::rotated(&stamp.cells),
+                None => self.randomize(),
+            },
+            KeyAction::FlipHorizontally => if let Some(stamp) = self.stamp.as_mut() {
+                stamp.cells = shapes::flipped_horizontally(&stamp.cells);
+            },
+            KeyAction::FlipVertically => if let Some(stamp) = self.stamp.as_mut() {
+                stamp.cells = shapes::flipped_vertically(&stamp.cells);
+            },
+            KeyAction::TogglePeriodTwo => self.detector.period_two = !self.detector.period_two,
+            KeyAction::ToggleAutoPause => self.auto_pause = !self.auto_pause,
+            KeyAction::ToggleEditPause => self.pause_on_edit = !self.pause_on_edit,
+            KeyAction::ToggleAgeColors => self.age_colors = !self.age_colors,
+            KeyAction::ToggleBirthColors => self.birth_colors = !self.birth_colors,
+            KeyAction::ToggleLookahead => self.lookahead = !self.lookahead,
+            KeyAction::ToggleNeighbours => self.show_neighbours = !self.show_neighbours,
+            KeyAction::ToggleTrails => self.trails = !self.trails,
+            KeyAction::ToggleWrap => self.board.set_wrap(!self.board.wrap()),
+            KeyAction::ToggleCapture => self.board.set_capture(!self.board.capture()),
+            KeyAction::ToggleGrid => self.show_grid = !self.show_grid,
+            KeyAction::ToggleBoundingBox => self.show_bounding_box = !self.show_bounding_box,
+            KeyAction::NextTheme => {
+                self.theme = (self.theme + 1) % self.themes.len();
+                self.set_status(&format!("Theme: {}", self.themes[self.theme].name));
+            },
+            // The view is moved by a cell in the direction of the arrow
+            KeyAction::PanHorizontal => {
+                let step = self.cell_size * self.transform.scale;
+                self.transform.pan(if index == 0 { step } else { -step }, 0.0);
+            },
+            KeyAction::PanVertical => {
+                let step = self.cell_size * self.transform.scale;
+                self.transform.pan(0.0, if index == 0 { step } else { -step });
+            },
+            KeyAction::ResetView => self.transform = view::Transform::default(),
+            KeyAction::FitView => self.fit_view(),
+            KeyAction::MoveCursor => self.move_cursor(CURSOR_STEPS[index], false),
+            KeyAction::PaintWithCursor => self.move_cursor(CURSOR_STEPS[index], true),
+            KeyAction::ToggleCursorCell => self.toggle_cursor_cell(),
+            KeyAction::Copy => self.copy_selection(false),
+            KeyAction::Cut => self.copy_selection(true),
+            KeyAction::Paste => self.paste(),
+            KeyAction::CopyRle => self.copy_rle().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::PasteRle => self.paste_rle().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::SaveSlot => self.save_slot(index + 1).unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::LoadSlot => self.load_slot(index + 1),
+            KeyAction::Browse => self.open_browser(),
+            KeyAction::BrowseRecent => self.open_recent(),
+            KeyAction::Load => self.load_pattern().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::Save => self.save_pattern().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::Screenshot => self.save_screenshot().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::DumpAsciiField => self.dump_ascii(true).unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::DumpAscii => self.dump_ascii(false).unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::ToggleRecording => match self.recording.is_some() {
+                true => self.stop_recording().unwrap_or_else(|e| self.show_error(e)),
+                false => {
+                    self.recording = Some(Recording::new(&self.board));
+                    self.set_status("Recording");
+                },
+            },
+            KeyAction::ToggleStats => self.toggle_stats().unwrap_or_else(|e| self.show_error(e)),
+            KeyAction::ToggleLineTool => self.toggle_tool(Tool::Line),
+            KeyAction::ToggleRectangleTool => self.toggle_tool(Tool::Rectangle),
+            KeyAction::CycleBrushSize => self.change_brush_size(true, true),
+            KeyAction::CycleSymmetry => {
+                self.symmetry = self.symmetry.next();
+                self.set_status(&format!("Symmetry: {}", self.symmetry));
+            },
+            KeyAction::Cancel => self.cancel(ctx),
+            KeyAction::Quit => quit(ctx),
+        }
+        Ok(())
+    }
+
+}
+
+
+// Implement library trait for custom sctructure
+impl State for GameState {
+    // Function handles events of the window
+    // Pattern files dropped onto the window are stamped with the next click (the browser is closed,
+    // as it would pick a file as well), the game is paused while the window is out of focus,
+    // and the field and the menu are fitted to the window when it's resized
+    fn event(&mut self, ctx: &mut Context, event: Event) -> Result{
+        match event {
+            Event::FileDropped{path} => {
+                self.browser.library = None;
+                self.goto_input = None;
+                self.end_stroke();
+                self.stamp_file(&path);
+            },
+            // The running game is paused while another window is used, a stroke can't go on there either
+            Event::FocusLost => {
+                self.end_stroke();
+                if self.focus.lost(self.running) {
+                    self.toggle_running();
+                    self.set_status("Paused (unfocused)");
+                }
+            },
+            Event::FocusGained => {
+                let resume = self.focus.gained();
+                if resume && !self.running {
+                    self.toggle_running();
+                }
+            },
+            Event::Resized{width, height} => self.fit_window(ctx, width, height)?,
+            _ => (),
+        }
+        Ok(())
+    }
+
+
+    // Function to draw all meshes
+    fn draw(&mut self, ctx: &mut Context) -> Result{
+        // Color of the field
+        // The menu shows the state of the game on every frame
+        self.update_bounding_box(ctx)?;
+        self.update_menu_text();
+        self.update_graph_mesh(ctx)?;
+        self.update_field_mesh(ctx)?;
+
+        let theme = &self.themes[self.theme];
+        graphics::clear(ctx, color(theme.background));
+
+        // The field is scaled and cut off at the edge of the menu
+        let transform = Mat4::<f32>::translation_2d(Vec2::new(self.transform.offset.0, self.transform.offset.1))
+            * Mat4::scaling_3d(Vec3::new(self.transform.scale, self.transform.scale, 1.0));
+        graphics::set_transform_matrix(ctx, transform);
+        graphics::set_scissor(ctx, Rectangle::new(0, 0, self.field_area.width as i32, self.field_area.height as i32));
+
+        // Draw grid
+        // Only the edges of the field are drawn when the grid is hidden
+        for line in self.grid.iter().filter(|line| self.show_grid || line.edge){
+            line.mesh.draw(ctx, DrawParams::new()
+             .color(color(theme.grid))
+             );
+        }   
+
+        // Draw trails of dead cells and alive cells above them in a single call
+        if let Some(field_mesh) = &self.field_mesh {
+            field_mesh.draw(ctx, DrawParams::new());
+        }
+
+        // Draw ants of Langton's Ant above their cells, pointing where they head
+        if self.board.automaton() == Automaton::LangtonsAnt {
+            for cell in self.cells.iter(){
+                let (col, row) = cell.coords;
+                if let Some(heading) = ant::ant(self.board.state(col, row)) {
+                    self.ant_mesh.draw(ctx, DrawParams::new()
+                        .position(cell.pos + Vec2::broadcast(self.cell_size / 2.0))
+                        .rotation(heading.angle())
+                        .scale(Vec2::broadcast(self.cell_size))
+                        .color(ANT_COLOR)
+                        );
+                }
+            }
+        }
+
+        // Preview the next generation over the cells
+        // Cells that will be born are outlined and cells that will die are dimmed
+        // Cells of other automata are outlined with the color of their next state
+        for (col, row, state) in self.next_changes.iter() {
+            let pos = Vec2::new(*col as f32 * self.cell_size, *row as f32 * self.cell_size);
+            match state_color(&self.board, *state, color(theme.cell_alive), color(theme.background)) {
+                None => self.cell_mesh.draw(ctx, DrawParams::new()
+                    .position(pos)
+                    .color(color(theme.background).with_alpha(DYING_ALPHA))
+                    ),
+                Some(outline) => self.outline_mesh.draw(ctx, DrawParams::new()
+                    .position(pos)
+                    .color(outline)
+                    ),
+            }
+        }
+
+        // Draw numbers of alive neighbours in the middle of cells
+        // Texts of digits are made once and scaled with the cells
+        let digit_scale = self.cell_size * DIGIT_HEIGHT / DIGIT_FONT_SIZE;
+        let digit_width = CHAR_WIDTH * DIGIT_FONT_SIZE * digit_scale;
+        for ((col, row), count) in self.neighbour_counts.iter() {
+            // Numbers with two digits are centered as well
+            let digit_offset = Vec2::new(
+                (self.cell_size - digit_width * count.to_string().len() as f32) / 2.0,
+                (self.cell_size - DIGIT_FONT_SIZE * digit_scale) / 2.0,
+                );
+            let pos = Vec2::new(*col as f32 * self.cell_size, *row as f32 * self.cell_size);
+            self.digits[*count as usize - 1].draw(ctx, DrawParams::new()
+                .position(pos + digit_offset)
+                .scale(Vec2::new(digit_scale, digit_scale))
+                .color(color(theme.text))
+                );
+        }
+
+        // Outline the bounding box of alive cells
+        if let Some(bounding_box) = &self.bounding_box {
+            bounding_box.mesh.draw(ctx, DrawParams::new()
+                .color(BOUNDING_BOX_COLOR)
+                );
+        }
+
+        // Draw a translucent rectangle over the selected cells
+        if let Some(selection) = self.selection {
+            let (col, row, width, height) = selection.bounds();
+            self.selection_mesh.draw(ctx, DrawParams::new()
+                .position(Vec2::new(col as f32 * self.cell_size, row as f32 * self.cell_size))
+                .scale(Vec2::new(width as f32 * self.cell_size, height as f32 * self.cell_size))
+                .color(SELECTION_COLOR)
+                );
+        }
+
+        // Highlight the cell under the cursor
+        // The mouse is checked on every frame for the highlight to keep up with the cursor
+        // A stamp or a started shape is previewed with all of its cells that fit the field
+        // A dragged rectangle is previewed even when the cursor leaves the field
+        // The brush is previewed with its size and mirrored copies
+        let mouse_coords = input::get_mouse_position(ctx).round();
+        let preview = match (self.rectangle_start, self.cell_coords_at(mouse_coords)) {
+            (Some(start), _) => {
+                let filled = input::is_key_modifier_down(ctx, KeyModifier::Shift);
+                shapes::rectangle(start, self.clamped_cell_coords_at(mouse_coords), filled)
+            },
+            (None, Some((col, row))) => match (&self.stamp, self.line_start) {
+                (Some(stamp), _) => stamp.cells.iter().map(|(dx, dy)| (col + dx, row + dy)).collect(),
+                (None, Some(start)) => shapes::line(start, (col, row)),
+                (None, None) if self.tool == Tool::Brush => self.brush_cells((col, row)),
+                (None, None) => vec![(col, row)],
+            },
+            (None, None) => Vec::new(),
+        };
+        // The cursor that was used last is drawn clearly and the other one is faded
+        let (highlight, cursor_color) = match self.keyboard_used {
+            true => (faded(color(theme.cell_highlight)), CURSOR_COLOR),
+            false => (color(theme.cell_highlight), faded(CURSOR_COLOR)),
+        };
+        for (col, row) in preview {
+            if !self.board.contains(col, row) {
+                continue;
+            }
+            self.cell_mesh.draw(ctx, DrawParams::new()
+                .position(Vec2::new(col as f32 * self.cell_size, row as f32 * self.cell_size))
+                .color(highlight)
+                );
+        }
+
+        // Highlight the cell of the keyboard cursor
+        if let Some((col, row)) = self.cursor {
+            self.cell_mesh.draw(ctx, DrawParams::new()
+                .position(Vec2::new(col as f32 * self.cell_size, row as f32 * self.cell_size))
+                .color(cursor_color)
+                );
+        }
+
+        // The menu is not scaled
+        graphics::reset_transform_matrix(ctx);
+        graphics::reset_scissor(ctx);
+
+        // Draw text
+        // Lines of stats are placed right below the status, however long it is
+        let status_height = self.menu_text.text.get_bounds(ctx).map_or(0.0, |bounds| bounds.height);
+        // The status flashes in the color of the winner after a victory
+        let flash_on = (self.menu_text.flash_time * FLASH_RATE).ceil() as i64 % 2 == 1;
+        let status_color = match flash_on {
+            true => self.menu_text.flash_color,
+            false => color(theme.text),
+        };
+        self.menu_text.text.draw(ctx, DrawParams::new()
+            .position(self.menu_text.pos)
+            .color(status_color)
+            );
+        for (i, line) in self.menu_text.lines.iter_mut().enumerate(){
+            let y = status_height + (i + 1) as f32 * self.menu_text.line_spacing;
+            line.draw(ctx, DrawParams::new()
+                .position(self.menu_text.pos + Vec2::new(0.0, y))
+                .color(color(theme.text))
+                );
+        }
+
+        // Draw births and deaths below the lines of stats, separated by a space from the slash
+        // Parts are placed by their numbers of characters, as spaces have no bounds
+        let changes_y = status_height + (self.menu_text.lines.len() + 1) as f32 * self.menu_text.line_spacing;
+        let births_chars = self.menu_text.births.content().chars().count();
+        for (text, text_color, chars) in [
+            (&mut self.menu_text.births, BIRTHS_COLOR, 0),
+            (&mut self.menu_text.separator, color(theme.text), births_chars + 1),
+            (&mut self.menu_text.deaths, DEATHS_COLOR, births_chars + 3),
+        ] {
+            text.draw(ctx, DrawParams::new()
+                .position(self.menu_text.pos + Vec2::new(chars as f32 * self.menu_text.char_width, changes_y))
+                .color(text_color)
+                );
+        }
+
+        // Draw the graph of population below births and deaths
+        let graph_y = changes_y + self.menu_text.line_spacing + GRAPH_GAP;
+        let graph_pos = self.menu_text.pos + Vec2::new(0.0, graph_y);
+        self.baseline.draw(ctx, DrawParams::new()
+            .position(graph_pos)
+            .color(color(theme.grid))
+            );
+        if let Some(mesh) = &self.graph_mesh {
+            mesh.draw(ctx, DrawParams::new()
+                .position(graph_pos)
+                .color(color(theme.cell_alive))
+                );
+        }
+
+        // Draw the last failure below the graph while it's shown
+        if self.menu_text.error_time > 0.0 {
+            self.menu_text.error.draw(ctx, DrawParams::new()
+                .position(graph_pos + Vec2::new(0.0, GRAPH_SIZE.1 + GRAPH_GAP))
+                .color(ERROR_COLOR)
+                );
+        }
+
+        // Draw buttons
+        // The button under the cursor is highlighted
+        for button in self.buttons.iter_mut(){
+            let color = match button.contains(self.mouse_coords) {
+                true => Color::rgb(0.45, 0.2, 0.6),
+                false => Color::rgb(0.3, 0.1, 0.4),
+            };
+            button.mesh.draw(ctx, DrawParams::new()
+                .position(Vec2::new(button.rect.x, button.rect.y))
+                .color(color)
+                );
+            button.label.draw(ctx, DrawParams::new()
+                .position(Vec2::new(button.rect.x + BUTTON_LABEL_INDENTS.0, button.rect.y + BUTTON_LABEL_INDENTS.1))
+                .color(Color::WHITE)
+                );
+        }
+
+        // Draw the help overlay over the field
+        if self.help.visible {
+            self.help.mesh.draw(ctx, DrawParams::new()
+                .color(Color::rgba(0.0, 0.0, 0.0, 0.85))
+                );
+            self.help.text.draw(ctx, DrawParams::new()
+                .position(Vec2::new(HELP_TEXT_INDENT, HELP_TEXT_INDENT))
+                .color(Color::WHITE)
+                );
+        }
+
+        // Draw the pattern browser over the field on the same dark rectangle
+        if self.browser.is_open() {
+            self.help.mesh.draw(ctx, DrawParams::new()
+                .color(Color::rgba(0.0, 0.0, 0.0, 0.85))
+                );
+            self.browser.text.draw(ctx, DrawParams::new()
+                .position(Vec2::new(HELP_TEXT_INDENT, HELP_TEXT_INDENT))
+                .color(Color::WHITE)
+                );
+            self.browser.missing_text.draw(ctx, DrawParams::new()
+                .position(Vec2::new(HELP_TEXT_INDENT, HELP_TEXT_INDENT))
+                .color(MISSING_FILE_COLOR)
+                );
+        }
+
+        Ok(())
+    }
+
+    
+    // Function to update the state
+    fn update(&mut self, ctx: &mut Context) -> Result{
+        self.menu_text.error_time = (self.menu_text.error_time - 1.0 / UPDATE_RATE).max(0.0);
+        self.menu_text.flash_time = (self.menu_text.flash_time - 1.0 / UPDATE_RATE).max(0.0);
+
+        let last_mouse_coords = self.mouse_coords;
+        self.mouse_coords = input::get_mouse_position(ctx).round();
+        // The mouse highlight is shown clearly again once the mouse moves or clicks
+        let clicked = input::is_mouse_button_pressed(ctx, MouseButton::Left) || input::is_mouse_button_pressed(ctx, MouseButton::Right);
+        self.ctrl_held = input::is_key_modifier_down(ctx, KeyModifier::Ctrl);
+        if self.mouse_coords != last_mouse_coords || clicked {
+            self.keyboard_used = false;
+        }
+
+        // Press a button of the menu with a LMB
+        // The click is not passed to the field then
+        // The mouse does nothing while the help overlay or the pattern browser is shown
+        let mut clicked_button = false;
+        if !self.overlay_shown() && input::is_mouse_button_pressed(ctx, MouseButton::Left){
+            let pressed = self.buttons.iter().find(|button| button.contains(self.mouse_coords)).map(|button| button.action);
+            if let Some(action) = pressed {
+                self.press_button(action);
+                clicked_button = true;
+            }
+        }
+
+        // The running game is paused by a click on the field before the click edits it
+        // The game stays paused until it is started again
+        let clicked_field = (!clicked_button && input::is_mouse_button_pressed(ctx, MouseButton::Left))
+            || input::is_mouse_button_pressed(ctx, MouseButton::Right);
+        if !self.overlay_shown() && self.running && self.pause_on_edit && clicked_field && self.pointed_cell_coords().is_some() {
+            self.toggle_running();
+            self.set_status("Paused (editing)");
+        }
+
+        // Revive or kill a cell with a LMB
+        // and paint cells the same way while the button is held
+        // If a pattern is selected - stamp it instead
+        // The line and rectangle tools start their shapes instead
+        // Cells are selected instead while Shift is held, unless a rectangle is drawn or cells of Langton's Ant are painted
+        if !self.overlay_shown() && !clicked_button && input::is_mouse_button_pressed(ctx, MouseButton::Left){
+            if let Some((col, row)) = self.pointed_cell_coords() {
+                let shift = input::is_key_modifier_down(ctx, KeyModifier::Shift);
+                match (shift, self.stamp.take()) {
+                    // Clicks put ants of Langton's Ant, so cells are painted black or white with Shift
+                    (true, None) if self.tool == Tool::Brush && self.board.automaton() == Automaton::LangtonsAnt => {
+                        let state = ant::painted(self.board.state(col, row));
+                        self.start_stroke(MouseButton::Left, state, (col, row));
+                    },
+                    (true, None) if self.tool == Tool::Rectangle => self.rectangle_start = Some((col, row)),
+                    (true, stamp) => {
+                        self.stamp = stamp;
+                        self.selection = Some(Selection::new((col, row)));
+                        self.selecting = true;
+                    },
+                    (false, Some(stamp)) => self.stamp_cells(&stamp, (col, row)),
+                    (false, None) => match self.tool {
+                        Tool::Brush => {
+                            // Clicks go through the states of the automaton (revive or kill cells of Life)
+                            // and build or remove walls with Ctrl
+                            // Cells become immortal with Alt and normal again with another click with Alt
+                            let ctrl = input::is_key_modifier_down(ctx, KeyModifier::Ctrl);
+                            let alt = input::is_key_modifier_down(ctx, KeyModifier::Alt);
+                            let state = match (ctrl, alt, self.board.state(col, row)) {
+                                (true, _, automaton::WALL) => automaton::DEAD,
+                                (true, _, _) => automaton::WALL,
+                                (false, true, automaton::IMMORTAL) => automaton::ALIVE,
+                                (false, true, _) => automaton::IMMORTAL,
+                                (false, false, state) => self.board.automaton().clicked(state),
+                            };
+                            self.start_stroke(MouseButton::Left, state, (col, row));
+                        },
+                        Tool::Line => self.click_line((col, row)),
+                        Tool::Rectangle => self.rectangle_start = Some((col, row)),
+                    },
+                }
+            }
+        }
+
+        // Drag the corner of the selection until the button is released
+        if self.selecting {
+            match input::is_mouse_button_down(ctx, MouseButton::Left) {
+                true => if let (Some(coords), Some(selection)) = (self.pointed_cell_coords(), self.selection.as_mut()) {
+                    selection.end = coords;
+                },
+                false => self.selecting = false,
+            }
+        }
+
+        // Drag the opposite corner of the rectangle until the button is released
+        // Corners outside of the field are moved to its edges
+        if let Some(start) = self.rectangle_start {
+            if !input::is_mouse_button_down(ctx, MouseButton::Left) {
+                self.rectangle_start = None;
+                let filled = input::is_key_modifier_down(ctx, KeyModifier::Shift);
+                let end = self.clamped_cell_coords_at(self.mouse_coords);
+                self.revive_cells(&shapes::rectangle(start, end, filled));
+            }
+        }
+
+        // Kill cells with a RMB (or paint blue cells of Immigration)
+        if !self.overlay_shown() && input::is_mouse_button_pressed(ctx, MouseButton::Right){
+            if let Some((col, row)) = self.pointed_cell_coords() {
+                let state = self.board.automaton().right_clicked(self.board.state(col, row));
+                self.start_stroke(MouseButton::Right, state, (col, row));
+            }
+        }
+
+        // Continue the stroke until the button is released
+        if let Some(button) = self.stroke.as_ref().map(|stroke| stroke.button) {
+            if input::is_mouse_button_down(ctx, button){
+                if let Some(coords) = self.pointed_cell_coords() {
+                    self.continue_stroke(coords);
+                }
             } else {
-                // Iterate through the cells and check if cell's ID is in the next cells
-                for i in 0..self.cells.len(){
-                    for j in 0..next_cells.len(){
-                        // If it is - this cell should be alive
-                        if self.cells[i].id == next_cells[j] as i32{
-                            self.cells[i].alive = true;
-                            break;
-                        // If it is not - this cell should be dead
-                        } else {
-                            self.cells[i].alive = false;
-                        }
+                self.end_stroke();
+            }
+        }
+
+        // Move the field with the mouse while the MMB is held
+        if !self.overlay_shown() && input::is_mouse_button_pressed(ctx, MouseButton::Middle){
+            self.panning = self.field_area.contains_point(self.mouse_coords);
+        }
+        if self.panning {
+            match input::is_mouse_button_down(ctx, MouseButton::Middle) {
+                true => self.transform.pan(self.mouse_coords.x - last_mouse_coords.x, self.mouse_coords.y - last_mouse_coords.y),
+                false => self.panning = false,
+            }
+        }
 
+        // Zoom the field in and out around the cursor with the mouse wheel
+        // The size of the brush is changed instead while Ctrl is held
+        let wheel = input::get_mouse_wheel_movement(ctx).y;
+        if !self.overlay_shown() && wheel != 0 && self.field_area.contains_point(self.mouse_coords){
+            match input::is_key_modifier_down(ctx, KeyModifier::Ctrl) {
+                true => self.change_brush_size(wheel > 0, false),
+                false => self.transform.zoom(ZOOM_STEP.powi(wheel), (self.mouse_coords.x, self.mouse_coords.y)),
+            }
+        }
+
+        // Do actions of pressed keys
+        // Only the help overlay and quitting work while the overlay is shown
+        // Keys type the generation to go to instead while it's asked for, and pick a pattern while the browser is open
+        match (self.goto_input.is_some(), self.browser.is_open()) {
+            (true, _) => self.type_goto(ctx),
+            (false, true) => self.browse(ctx),
+            (false, false) => for (action, index) in keys::pressed_actions(&self.bindings, |modifier| input::is_key_modifier_down(ctx, modifier), |key| input::is_key_pressed(ctx, key)){
+                if self.help.visible && !action.works_over_help() {
+                    continue;
+                }
+                self.do_key_action(ctx, action, index)?;
+            },
+        }
+
+        // Main part - updating cells alive statuses
+        // Generations are made at their own speed no matter how often the state is updated
+        if self.running {
+            self.accumulator += 1.0 / UPDATE_RATE;
+            // A generation can pause the game, so the rest are not made
+            // In turbo mode every tick makes a batch of generations and only the last one is drawn,
+            // while all of them are kept in the history, recorded and checked for stabilization
+            let steps = match self.turbo {
+                true => self.turbo_steps,
+                false => 1,
+            };
+            while self.running && self.accumulator >= 1.0 / self.speed {
+                self.accumulator -= 1.0 / self.speed;
+                for _ in 0..steps {
+                    if !self.running {
+                        break;
                     }
+                    self.step_once();
                 }
             }
+        } else {
+            self.accumulator = 0.0;
         }
+        self.run_batch();
+
+        // Numbers of neighbours are counted again on every update to follow generations and edits
+        self.neighbour_counts.clear();
+        if self.show_neighbours {
+            for cell in self.cells.iter() {
+                let (col, row) = cell.coords;
+                let count = self.board.alive_neighbours(col, row);
+                if count > 0 {
+                    self.neighbour_counts.push(((col, row), count));
+                }
+            }
+        }
+
+        // The next generation is previewed only while the game is paused
+        // It's computed on every update, so edits show up in it right away
+        self.next_changes = match self.lookahead && !self.running {
+            true => self.board.compute_next(),
+            false => Vec::new(),
+        };
 
         Ok(())
     }   
 }
 
-fn main() -> Result {
+// The game is saved when the window is closed, so it can be continued the next time
+// Buffered stats are written to their file too
+impl Drop for GameState {
+    fn drop(&mut self){
+        if let Some(stats) = self.stats.as_mut() {
+            if let Err(e) = stats.flush() {
+                error!("Can't write stats to {}: {}", self.stats_path, e);
+            }
+        }
+        let session = Session::new(&self.board, self.speed, self.generation);
+        if let Some(path) = session::default_path() {
+            match session::save(&session, &path) {
+                Ok(_) => info!("Saved the session to {}", path.display()),
+                Err(e) => error!("Can't save the session to {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+// Function returns the color of an alive cell of the given age
+// Cells fade from the young color to the old one
+fn age_color(young: Color, age: u32) -> Color {
+    let t = (age.saturating_sub(1) as f32 / (OLD_CELL_AGE - 1) as f32).min(1.0);
+    let mix = |young: f32, old: f32| young + (old - young) * t;
+    Color::rgb(
+        mix(young.r, OLD_CELL_COLOR.r),
+        mix(young.g, OLD_CELL_COLOR.g),
+        mix(young.b, OLD_CELL_COLOR.b),
+        )
+}
+
+// Function returns the color of a cell of the board in the state
+// Dying states fade from the color of alive cells to the background one by one, dead cells aren't drawn
+fn state_color(board: &Board, state: u8, alive: Color, background: Color) -> Option<Color> {
+    let automaton = board.automaton();
+    match (automaton, state) {
+        (_, automaton::DEAD) => None,
+        (_, automaton::WALL) => Some(WALL_COLOR),
+        (_, automaton::IMMORTAL) => Some(IMMORTAL_COLOR),
+        (Automaton::Wireworld, automaton::CONDUCTOR) => Some(CONDUCTOR_COLOR),
+        (Automaton::Wireworld, automaton::HEAD) => Some(HEAD_COLOR),
+        (Automaton::Wireworld, _) => Some(TAIL_COLOR),
+        (Automaton::Immigration, automaton::RED) => Some(RED_COLOR),
+        (Automaton::Immigration, _) => Some(BLUE_COLOR),
+        // Cells of Langton's Ant are black (drawn alive) or white (not drawn) with ants or without them
+        (Automaton::LangtonsAnt, state) => match ant::color(state) {
+            automaton::ALIVE => Some(alive),
+            _ => None,
+        },
+        (_, automaton::ALIVE) => Some(alive),
+        (_, state) => Some(mix_colors(alive, background, (state - 1) as f32 / (automaton.states(board.rule()) - 1) as f32)),
+    }
+}
+
+// Function returns the color of an inactive cursor
+fn faded(cursor: Color) -> Color {
+    cursor.with_alpha(cursor.a * INACTIVE_CURSOR_FADE)
+}
+
+// Function returns the color `t` of the way from the first color to the second one
+fn mix_colors(from: Color, to: Color, t: f32) -> Color {
+    let mix = |from: f32, to: f32| from + (to - from) * t;
+    Color::rgba(mix(from.r, to.r), mix(from.g, to.g), mix(from.b, to.b), from.a)
+}
+
+// Function cuts off the end of the line that is longer than `chars` characters
+fn fit_line(line: &str, chars: usize) -> String {
+    match line.chars().count() > chars {
+        true => line.chars().take(chars - 1).chain(std::iter::once('…')).collect(),
+        false => String::from(line),
+    }
+}
+
+// Function returns the number of seconds since 1970 to name files with
+fn timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs())
+}
+
+// Function converts a color of a theme to the color to draw with
+fn color(theme_color: ThemeColor) -> Color {
+    Color::rgba(theme_color.r, theme_color.g, theme_color.b, theme_color.a)
+}
+
+// Function lists all key bindings with their descriptions, one binding in a line
+fn help_content(bindings: &[KeyBinding]) -> String {
+    let mut content = String::from("Controls\n\n");
+    for binding in bindings {
+        let names: Vec<&str> = binding.keys.iter().map(|key| keys::key_name(*key)).collect();
+        // A long row of numbered keys is shown as a range
+        let numbered = names.iter().all(|name| name.ends_with(|c: char| c.is_ascii_digit()));
+        let keys = match names.as_slice() {
+            [first, .., last] if names.len() > 2 && numbered => format!("{}-{}", first, last),
+            _ => names.join("/"),
+        };
+        let keys = binding.modifiers.iter().rev().fold(keys, |keys, modifier| format!("{}+{}", modifier, keys));
+        content.push_str(&format!("{:<14}{}\n", keys, binding.description));
+    }
+    content
+}
+
+// Function simulates the pattern without a window and writes the result
+// The RLE (or ASCII art) goes to the --out file (if any) or stdout, stats go to stdout or stderr respectively
+fn run_headless(options: &Options) {
+    let (pattern, report) = match headless::run(options) {
+        Ok(result) => result,
+        Err(e) => {
+            error!("{}", e);
+            process::exit(1);
+        },
+    };
+    match &options.out {
+        Some(path) => {
+            if let Err(e) = fs::write(path, pattern) {
+                error!("Can't save {}: {}", path, e);
+                process::exit(1);
+            }
+            info!("Saved {}", path);
+            println!("{}", report);
+        },
+        None => {
+            print!("{}", pattern);
+            eprintln!("{}", report);
+        },
+    }
+}
+
+// Function runs random soups without a window and prints every soup as soon as it's done
+// The soups sorted by longevity are written to the --out file (or `soups.csv`),
+// and the arguments that open the longest-lived one in the game are printed last
+fn run_soup_search(options: &Options, search: soup::SoupSearch) {
+    let first_seed = options.seed.unwrap_or_else(rand::random);
+    let soups = soup::search(options, search, first_seed, |soup| println!("{}", soup));
+    let path = options.out.clone().unwrap_or_else(|| String::from(DEFAULT_SOUPS_PATH));
+    let written = File::create(&path).and_then(|file| soup::write_summary(BufWriter::new(file), &soups));
+    if let Err(e) = written {
+        error!("Can't save {}: {}", path, e);
+        process::exit(1);
+    }
+    println!("Summary saved to {}", path);
+    if let Some(top) = soups.first() {
+        println!("Longest-lived: {}", top);
+        println!("Open it with: game_of_life {}", soup::game_args(options, search.density, top.seed));
+    }
+}
+
+
+// Function sets up the logger writing to stderr
+// Only warnings and errors are logged by default, `-v` adds loads and saves of files and `-vv` every generation
+// The level (or a filter of modules) can be set with RUST_LOG instead
+fn init_logger(verbose: u8){
+    let level = match verbose {
+        0 => "warn",
+        1 => "info",
+        2 => "debug",
+        _ => "trace",
+    };
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or(level)).init();
+}
+
+// Function reads the config file next to the binary or in the config directory of the user
+// Bad settings are reported and skipped, and a config of all defaults is written on the first run
+fn read_config() -> Config {
+    let paths = config::paths();
+    let path = match paths.iter().find(|path| path.exists()) {
+        Some(path) => path,
+        None => {
+            if let Some(path) = config::default_path() {
+                let defaults = Config::of(&Options::default(), keys::default_keys());
+                match config::save(&defaults, &path) {
+                    Ok(_) => info!("Wrote the config with defaults to {}", path.display()),
+                    Err(e) => warn!("Can't write the config to {}: {}", path.display(), e),
+                }
+            }
+            return Config::default();
+        },
+    };
+    match config::load(path) {
+        Ok((config, warnings)) => {
+            info!("Read the config from {}", path.display());
+            for warning in warnings {
+                warn!("{} in {}", warning, path.display());
+            }
+            config
+        },
+        Err(source) => {
+            warn!("{}", GolError::Config{path: path.clone(), source});
+            Config::default()
+        },
+    }
+}
+
+// Function reads the list of recent files
+// A broken list is reported and the game starts without recent files
+fn read_recent() -> RecentFiles {
+    let path = match recent::default_path() {
+        Some(path) => path,
+        None => return RecentFiles::default(),
+    };
+    match recent::load(&path) {
+        Ok(recent) => recent,
+        Err(e) => {
+            warn!("Can't read recent files from {}: {}", path.display(), e);
+            RecentFiles::default()
+        },
+    }
+}
+
+// Function reads the session saved when the game was closed the last time
+// A broken session is reported and a new game is started instead
+fn restore_session() -> Option<Session> {
+    let path = session::default_path()?;
+    if !path.exists() {
+        return None;
+    }
+    match session::load(&path) {
+        Ok(session) => {
+            info!("Restored the session from {}", path.display());
+            Some(session)
+        },
+        Err(e) => {
+            warn!("Can't restore the session from {}: {}", path.display(), e);
+            None
+        },
+    }
+}
+
+
+fn main() {
+    // The logger is set up before the config file is read to log its problems,
+    // so arguments are read once for the verbosity and once more over the defaults of the config file
+    let args: Vec<String> = env::args().skip(1).collect();
+    init_logger(cli::parse(args.clone()).map_or(0, |options| options.verbose));
+
+    // Arguments override the defaults of the config file
+    let config = read_config();
+    let mut defaults = Options::default();
+    config.apply(&mut defaults);
+    let options = match cli::parse_with(defaults, args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            process::exit(2);
+        },
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    if let Some(search) = options.soup_search {
+        run_soup_search(&options, search);
+        return;
+    }
+    if options.headless {
+        run_headless(&options);
+        return;
+    }
+
+    // The last session is continued unless a fresh game or a seeded soup is asked for
+    let session = match options.fresh || options.seed.is_some() {
+        true => None,
+        false => restore_session(),
+    };
+
+    // Errors are told with their reasons (like a missing font file)
+    if let Err(e) = run(options, session, &config.keys) {
+        match e.source() {
+            Some(reason) => error!("{}: {}", e, reason),
+            None => error!("{}", e),
+        }
+        process::exit(1);
+    }
+}
+
+// Function opens the window and runs the game in it until it's closed
+// Errors of the game are turned into errors of tetra only here, where tetra runs the game
+fn run(options: Options, session: Option<Session>, keys: &BTreeMap<String, Keys>) -> std::result::Result<(), GolError> {
     // Create a Context with titled window
-    ContextBuilder::new("Game of Life", (FIELD_WIDTH + 200.0) as i32, (FIELD_HEIGHT + 0.0)  as i32)
-    .timestep(Timestep::Fixed(5.0)) // How many times a second the State::update() runs
-    .quit_on_escape(true)
-    .build()?
-    // Or just GameState::mew (sugar)
-    .run(|ctx| GameState::new(ctx))
+    // The window fits the field of the given size (or the size of the session) and the menu to the right of it
+    let (cols, rows) = session.as_ref().map_or((options.cols, options.rows), |session| (session.width, session.height));
+    let cell_size = view::cell_size(cols, rows, FIELD_WIDTH, FIELD_HEIGHT);
+    let window_width = cols as f32 * cell_size + PANEL_WIDTH;
+    let window_height = (rows as f32 * cell_size).max(MIN_WINDOW_HEIGHT);
+    let mut ctx = ContextBuilder::new("Game of Life", window_width.ceil() as i32, window_height.ceil() as i32)
+    .timestep(Timestep::Fixed(UPDATE_RATE)) // How many times a second the State::update() runs
+    .resizable(true)
+    .build()?;
+    ctx.run(|ctx| GameState::new(ctx, options, session, keys).map_err(TetraError::from))?;
+    Ok(())
 }
+
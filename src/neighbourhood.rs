@@ -0,0 +1,88 @@
+// Cells around a cell that are counted as its neighbours
+// See https://conwaylife.com/wiki/Neighbourhood
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Shapes of neighbourhoods
+// Neighbourhoods are saved by their names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Neighbourhood {
+    // 8 cells around the cell
+    #[default]
+    Moore,
+    // 4 cells next to the sides of the cell
+    VonNeumann,
+    // 24 cells in the 5x5 square around the cell
+    Moore2,
+}
+
+impl Neighbourhood {
+    // Function returns the neighbourhood that follows this one when they are cycled
+    pub fn next(self) -> Neighbourhood {
+        match self {
+            Neighbourhood::Moore => Neighbourhood::VonNeumann,
+            Neighbourhood::VonNeumann => Neighbourhood::Moore2,
+            Neighbourhood::Moore2 => Neighbourhood::Moore,
+        }
+    }
+
+    // Function returns offsets of all neighbours from the cell row by row
+    pub fn offsets(self) -> Vec<(i32, i32)> {
+        let (radius, is_neighbour): (i32, fn(i32, i32) -> bool) = match self {
+            Neighbourhood::Moore => (1, |_, _| true),
+            Neighbourhood::VonNeumann => (1, |dx, dy| dx.abs() + dy.abs() == 1),
+            Neighbourhood::Moore2 => (2, |_, _| true),
+        };
+        let mut offsets = Vec::new();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                // The cell is not a neighbour of itself
+                if (dx, dy) != (0, 0) && is_neighbour(dx, dy) {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+impl FromStr for Neighbourhood {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Neighbourhood, String> {
+        match text {
+            "moore" => Ok(Neighbourhood::Moore),
+            "vonneumann" => Ok(Neighbourhood::VonNeumann),
+            "moore2" => Ok(Neighbourhood::Moore2),
+            _ => Err(format!("Unknown neighbourhood {}, expected moore, vonneumann or moore2", text)),
+        }
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Neighbourhood::Moore => write!(f, "moore"),
+            Neighbourhood::VonNeumann => write!(f, "vonneumann"),
+            Neighbourhood::Moore2 => write!(f, "moore2"),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn neighbourhoods_have_their_numbers_of_cells() {
+        assert_eq!(Neighbourhood::Moore.offsets().len(), 8);
+        assert_eq!(Neighbourhood::VonNeumann.offsets(), vec![(0, -1), (-1, 0), (1, 0), (0, 1)]);
+        assert_eq!(Neighbourhood::Moore2.offsets().len(), 24);
+        assert!(Neighbourhood::Moore2.offsets().contains(&(-2, 2)));
+        assert_eq!(Neighbourhood::Moore.next().next().next(), Neighbourhood::Moore);
+        assert_eq!("vonneumann".parse(), Ok(Neighbourhood::VonNeumann));
+    }
+}
@@ -0,0 +1,111 @@
+// Reading and writing boards as ASCII art: rows of `.` for dead cells and `#` for alive ones
+// It needs nothing to read, so boards can be pasted to chats and issues as they are
+use std::fmt;
+use super::PatternFile;
+
+// Errors that can occur while reading a board
+#[derive(Debug, PartialEq)]
+pub enum AsciiError {
+    // A character that is neither a dead nor an alive cell
+    UnexpectedChar(char),
+}
+
+impl fmt::Display for AsciiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AsciiError::UnexpectedChar(c) => write!(f, "Unexpected '{}' in ASCII art", c),
+        }
+    }
+}
+
+impl std::error::Error for AsciiError {}
+
+// Function checks if the text looks like ASCII art: only `.`, `#` and whitespace with a cell at least
+pub fn detect(text: &str) -> bool {
+    text.chars().any(|c| c == '.' || c == '#') && text.chars().all(|c| c == '.' || c == '#' || c.is_whitespace())
+}
+
+// Function reads a board from ASCII art
+// Rows shorter than the others are padded with dead cells, and empty lines at the ends are skipped
+pub fn parse(text: &str) -> Result<PatternFile, AsciiError> {
+    let lines: Vec<&str> = text.lines().map(|line| line.trim_end()).collect();
+    let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |last| last + 1);
+
+    let mut cells = Vec::new();
+    let mut width = 0;
+    for (y, line) in lines[first..last].iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            match c {
+                '.' => (),
+                '#' => cells.push((x as i32, y as i32)),
+                c => return Err(AsciiError::UnexpectedChar(c)),
+            }
+        }
+        width = width.max(line.chars().count() as i32);
+    }
+    Ok(PatternFile{width, height: (last - first) as i32, rule: None, cells, states: Vec::new()})
+}
+
+// Function writes the area (x, y, width, height) of the board with the given alive cells as ASCII art
+// Cells outside of the area are left out
+pub fn encode(cells: &[(i32, i32)], area: (i32, i32, i32, i32)) -> String {
+    let (left, top, width, height) = area;
+    let mut rows = vec![vec!['.'; width as usize]; height as usize];
+    for (x, y) in cells {
+        if (left..left + width).contains(x) && (top..top + height).contains(y) {
+            rows[(y - top) as usize][(x - left) as usize] = '#';
+        }
+    }
+
+    let mut text = String::new();
+    for row in rows {
+        text.extend(row);
+        text.push('\n');
+    }
+    text
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::pattern_io::{self, bounding_box};
+    use crate::patterns;
+
+    #[test]
+    fn export_and_import_give_the_same_cells() {
+        let cells = patterns::GLIDER.cells;
+        let text = encode(cells, bounding_box(cells));
+        assert_eq!(text, ".#.\n..#\n###\n");
+        let pattern = parse(&text).unwrap();
+        assert_eq!(pattern.cells, cells.to_vec());
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+    }
+
+    #[test]
+    fn full_field_keeps_position_of_cells() {
+        let mut board = Board::new(8, 6);
+        board.stamp(5, 2, patterns::BLINKER.cells);
+        let text = pattern_io::ascii_dump(&board, true);
+        assert_eq!(text.lines().count(), 6);
+        assert!(text.lines().all(|line| line.len() == 8));
+
+        let pattern = pattern_io::from_text(&text).unwrap();
+        assert_eq!((pattern.width, pattern.height), (8, 6));
+        assert_eq!(pattern.cells, board.alive_cells());
+        // Only the bounding box is written by default
+        assert_eq!(pattern_io::ascii_dump(&board, false), "###\n");
+    }
+
+    #[test]
+    fn detects_ascii_art_by_content() {
+        assert!(detect("\n.#.\r\n..#  \n###\n\n"));
+        assert!(!detect("bo$2bo$3o!"));
+        assert!(!detect(".O.\n..O\nOOO\n"));
+        assert!(!detect("  \n"));
+        assert_eq!(parse("\n.#.\n\n#\n\n").unwrap().height, 3);
+        assert_eq!(parse(".#\n.x\n").unwrap_err(), AsciiError::UnexpectedChar('x'));
+    }
+}
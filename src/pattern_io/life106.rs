@@ -0,0 +1,95 @@
+// Reading and writing patterns in the Life 1.06 format: a header and a line of coordinates per alive cell
+// See https://conwaylife.com/wiki/Life_1.06
+use std::fmt;
+use super::{PatternFile, bounding_box};
+
+// First line of every Life 1.06 file
+pub const HEADER: &str = "#Life 1.06";
+
+// Errors that can occur while reading a pattern
+#[derive(Debug, PartialEq)]
+pub enum Life106Error {
+    // A line (numbered from 1) that isn't a pair of coordinates
+    InvalidLine(usize),
+}
+
+impl fmt::Display for Life106Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Life106Error::InvalidLine(line) => write!(f, "Line {} of Life 1.06 is not a pair of coordinates", line),
+        }
+    }
+}
+
+impl std::error::Error for Life106Error {}
+
+// Function checks if the text starts with the Life 1.06 header
+pub fn detect(text: &str) -> bool {
+    text.trim_start().starts_with(HEADER)
+}
+
+// Function reads a pattern from the contents of a Life 1.06 file
+// Coordinates can be negative, so the cells are moved to put the upper left corner of the pattern at the origin
+pub fn parse(text: &str) -> Result<PatternFile, Life106Error> {
+    let mut cells = Vec::new();
+    // `lines()` also strips `\r` of Windows line endings
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        // The header and comments start with `#`
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let coordinates: Vec<i32> = line.split_whitespace()
+            .map(|coordinate| coordinate.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| Life106Error::InvalidLine(number + 1))?;
+        match coordinates[..] {
+            [x, y] => cells.push((x, y)),
+            _ => return Err(Life106Error::InvalidLine(number + 1)),
+        }
+    }
+
+    let (min_x, min_y, width, height) = bounding_box(&cells);
+    let cells = cells.into_iter().map(|(x, y)| (x - min_x, y - min_y)).collect();
+    Ok(PatternFile{width, height, rule: None, cells, states: Vec::new()})
+}
+
+// Function writes alive cells as the contents of a Life 1.06 file
+// The cells are written around the origin, the way Golly writes them
+pub fn encode(cells: &[(i32, i32)]) -> String {
+    let (min_x, min_y, width, height) = bounding_box(cells);
+    let (center_x, center_y) = (min_x + width / 2, min_y + height / 2);
+
+    let mut text = format!("{}\n", HEADER);
+    for (x, y) in cells {
+        text.push_str(&format!("{} {}\n", x - center_x, y - center_y));
+    }
+    text
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn glider_is_read_back() {
+        let cells = patterns::GLIDER.cells;
+        let text = encode(cells);
+        assert_eq!(text, "#Life 1.06\n0 -1\n1 0\n-1 1\n0 1\n1 1\n");
+        assert!(detect(&text));
+        let pattern = parse(&text).unwrap();
+        assert_eq!(pattern.cells, cells.to_vec());
+        assert_eq!((pattern.width, pattern.height), (3, 3));
+    }
+
+    #[test]
+    fn reads_windows_line_endings() {
+        let pattern = parse("#Life 1.06\r\n-5 -5\r\n-4 -5\r\n\r\n-3 -5\r\n").unwrap();
+        assert_eq!(pattern.cells, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!((pattern.width, pattern.height), (3, 1));
+        assert_eq!(parse("#Life 1.06\n0 0\n1\n").unwrap_err(), Life106Error::InvalidLine(3));
+        assert_eq!(parse("#Life 1.06\n0 x\n").unwrap_err(), Life106Error::InvalidLine(2));
+    }
+}
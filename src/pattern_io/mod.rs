@@ -0,0 +1,163 @@
+// Reading and writing pattern files
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use crate::board::Board;
+
+pub mod ascii;
+pub mod life106;
+pub mod plaintext;
+pub mod rle;
+
+// Maximal size of a pattern given as text (like one pasted from a clipboard)
+pub const MAX_TEXT_SIZE: usize = 1024 * 1024;
+
+// A pattern read from a file
+#[derive(Debug)]
+pub struct PatternFile {
+    // Size of the pattern
+    pub width: i32,
+    pub height: i32,
+    // Rule of the pattern (if the format has it)
+    pub rule: Option<String>,
+    // Coordinates of alive cells relative to the upper left corner of the pattern
+    pub cells: Vec<(i32, i32)>,
+    // States of the cells in the same order (like colors of Immigration), empty if the format has no states
+    pub states: Vec<u8>,
+}
+
+// Errors that can occur while loading a pattern file
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Rle(rle::RleError),
+    Plaintext(plaintext::PlaintextError),
+    Ascii(ascii::AsciiError),
+    Life106(life106::Life106Error),
+    // The file extension is not one of the supported formats
+    UnknownFormat,
+    // The text is longer than `MAX_TEXT_SIZE` bytes
+    TooLarge(usize),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Rle(e) => write!(f, "{}", e),
+            LoadError::Plaintext(e) => write!(f, "{}", e),
+            LoadError::Ascii(e) => write!(f, "{}", e),
+            LoadError::Life106(e) => write!(f, "{}", e),
+            LoadError::UnknownFormat => write!(f, "Unknown pattern format"),
+            LoadError::TooLarge(size) => write!(f, "Pattern is too large ({} bytes, at most {})", size, MAX_TEXT_SIZE),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+// Function reads a pattern from the file
+// The format is chosen by the file extension (`.rle`, `.cells`, `.lif` or `.life`)
+// Files with other extensions are read if they start with the Life 1.06 header or their contents are ASCII art
+pub fn load<P: AsRef<Path>>(path: P) -> Result<PatternFile, LoadError> {
+    let path = path.as_ref();
+    let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+    match extension(path).as_deref() {
+        Some("rle") => rle::parse(&text).map_err(LoadError::Rle),
+        Some("cells") => plaintext::parse(&text).map_err(LoadError::Plaintext),
+        Some("lif" | "life") => life106::parse(&text).map_err(LoadError::Life106),
+        _ if life106::detect(&text) => life106::parse(&text).map_err(LoadError::Life106),
+        _ if ascii::detect(&text) => ascii::parse(&text).map_err(LoadError::Ascii),
+        _ => Err(LoadError::UnknownFormat),
+    }
+}
+
+// Function finds the size of the pattern in an RLE or a plaintext file without reading its cells
+// Only the header of RLE files is read, while rows of plaintext files are counted
+// Returns `None` if the file can't be read or the size isn't known
+pub fn peek_size<P: AsRef<Path>>(path: P) -> Option<(i32, i32)> {
+    let path = path.as_ref();
+    let lines = BufReader::new(fs::File::open(path).ok()?).lines().map_while(Result::ok);
+    match extension(path).as_deref() {
+        Some("rle") => rle::header_size(lines),
+        Some("cells") => Some(plaintext::size(lines)),
+        _ => None,
+    }
+}
+
+// Function returns the extension of the file in lowercase
+pub fn extension(path: &Path) -> Option<String> {
+    path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_lowercase())
+}
+
+// Function reads a pattern from RLE text, Life 1.06 text or ASCII art
+// Texts longer than `MAX_TEXT_SIZE` are not parsed at all
+pub fn from_text(text: &str) -> Result<PatternFile, LoadError> {
+    if text.len() > MAX_TEXT_SIZE {
+        return Err(LoadError::TooLarge(text.len()));
+    }
+    match (life106::detect(text), ascii::detect(text)) {
+        (true, _) => life106::parse(text).map_err(LoadError::Life106),
+        (false, true) => ascii::parse(text).map_err(LoadError::Ascii),
+        (false, false) => rle::parse(text).map_err(LoadError::Rle),
+    }
+}
+
+// Function writes alive cells of the board as ASCII art
+// Only the bounding box of the cells is written unless the full field is asked for
+pub fn ascii_dump(board: &Board, full: bool) -> String {
+    let cells = board.alive_cells();
+    let area = match full {
+        true => (0, 0, board.width(), board.height()),
+        false => bounding_box(&cells),
+    };
+    ascii::encode(&cells, area)
+}
+
+// Function writes alive cells of the board as the contents of an RLE file
+// Cells of automata with colors are written with their states, so the colors are kept
+pub fn board_rle(board: &Board) -> String {
+    let rule = board.rule().to_string();
+    match board.automaton().has_colors() {
+        true => rle::encode_states(&board.alive_states(), &rule),
+        false => rle::encode(&board.alive_cells(), &rule),
+    }
+}
+
+// Function puts the cells of the pattern in the middle of the board
+pub fn stamp_centered(board: &mut Board, pattern: &PatternFile) {
+    let x = (board.width() - pattern.width) / 2;
+    let y = (board.height() - pattern.height) / 2;
+    board.stamp_states(x, y, &pattern.cells, &pattern.states);
+}
+
+// Function returns the upper left corner and the size of the smallest
+// rectangle containing all given cells
+// The size is zero if there are no cells
+pub(crate) fn bounding_box(cells: &[(i32, i32)]) -> (i32, i32, i32, i32) {
+    if cells.is_empty() {
+        return (0, 0, 0, 0);
+    }
+    let min_x = cells.iter().map(|(x, _)| *x).min().unwrap_or(0);
+    let min_y = cells.iter().map(|(_, y)| *y).min().unwrap_or(0);
+    let max_x = cells.iter().map(|(x, _)| *x).max().unwrap_or(0);
+    let max_y = cells.iter().map(|(_, y)| *y).max().unwrap_or(0);
+    (min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_pattern_from_text() {
+        let pattern = from_text("#N Glider\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n").unwrap();
+        assert_eq!(pattern.cells, vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+
+        assert!(matches!(from_text("Hello!"), Err(LoadError::Rle(_))));
+        let huge = "b".repeat(MAX_TEXT_SIZE + 1);
+        assert!(matches!(from_text(&huge), Err(LoadError::TooLarge(_))));
+    }
+}
@@ -0,0 +1,130 @@
+// Reading and writing patterns in a plaintext format
+// See https://conwaylife.com/wiki/Plaintext
+use std::fmt;
+use super::{PatternFile, bounding_box};
+
+// Errors that can occur while reading a pattern
+#[derive(Debug, PartialEq)]
+pub enum PlaintextError {
+    // A character that is neither a dead nor an alive cell
+    UnexpectedChar(char),
+}
+
+impl fmt::Display for PlaintextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlaintextError::UnexpectedChar(c) => write!(f, "Unexpected '{}' in plaintext", c),
+        }
+    }
+}
+
+impl std::error::Error for PlaintextError {}
+
+// Function reads a pattern from the contents of a `.cells` file
+// Rows shorter than the others are padded with dead cells
+pub fn parse(text: &str) -> Result<PatternFile, PlaintextError> {
+    let mut cells = Vec::new();
+    let mut width = 0;
+    let mut height = 0;
+    // Comment lines start with `!`
+    // `lines()` also strips `\r` of Windows line endings
+    for line in text.lines().map(|line| line.trim_end()).filter(|line| !line.starts_with('!')) {
+        let mut x = 0;
+        for c in line.chars() {
+            match c {
+                '.' => (),
+                'O' | '*' => cells.push((x, height)),
+                c => return Err(PlaintextError::UnexpectedChar(c)),
+            }
+            x += 1;
+        }
+        width = width.max(x);
+        height += 1;
+    }
+
+    Ok(PatternFile{width, height, rule: None, cells, states: Vec::new()})
+}
+
+// Function counts the rows and the columns of the pattern in the lines without reading its cells
+pub fn size<I: Iterator<Item = String>>(lines: I) -> (i32, i32) {
+    let rows: Vec<i32> = lines.filter(|line| !line.starts_with('!'))
+        .map(|line| line.trim_end().chars().count() as i32)
+        .collect();
+    (rows.iter().copied().max().unwrap_or(0), rows.len() as i32)
+}
+
+// Function writes alive cells as the contents of a `.cells` file
+// Only the bounding box of the cells is written
+pub fn encode(cells: &[(i32, i32)], name: &str) -> String {
+    let (min_x, min_y, width, height) = bounding_box(cells);
+
+    let mut rows = vec![vec!['.'; width as usize]; height as usize];
+    for (x, y) in cells {
+        rows[(y - min_y) as usize][(x - min_x) as usize] = 'O';
+    }
+
+    let mut text = format!("!Name: {}\n", name);
+    for row in rows {
+        text.extend(row);
+        text.push('\n');
+    }
+    text
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+    use proptest::prelude::{any, prop_assert, prop_assert_eq, proptest, ProptestConfig};
+
+    // Cells sorted the same way for comparison
+    fn sorted(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut cells = cells.to_vec();
+        cells.sort();
+        cells
+    }
+
+    const BEEHIVE: [(i32, i32); 6] = [(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)];
+
+    #[test]
+    fn export_and_import_give_the_same_cells() {
+        for cells in [&BEEHIVE[..], patterns::PULSAR.cells] {
+            let text = encode(cells, "Test");
+            let pattern = parse(&text).unwrap();
+            assert_eq!(sorted(&pattern.cells), sorted(cells));
+        }
+    }
+
+    #[test]
+    fn tolerates_sloppy_files() {
+        // Windows line endings, trailing whitespace and ragged rows
+        let text = "!Name: Beehive\r\n!\r\n.OO \r\nO..O\t\r\n.OO\r\n";
+        let pattern = parse(text).unwrap();
+        assert_eq!((pattern.width, pattern.height), (4, 3));
+        assert_eq!(sorted(&pattern.cells), sorted(&BEEHIVE));
+    }
+
+    #[test]
+    fn rejects_unknown_characters() {
+        assert_eq!(parse(".O.\n.X.\n").unwrap_err(), PlaintextError::UnexpectedChar('X'));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn arbitrary_text_never_panics(text in any::<String>()) {
+            let _ = parse(&text);
+        }
+
+        // Cells of patterns that are read are within their size, which is the same one the library peeks at
+        #[test]
+        fn cells_stay_within_the_size(text in "([.O*!X \t\r]{0,20}\n){0,20}") {
+            if let Ok(pattern) = parse(&text) {
+                prop_assert!(pattern.cells.iter().all(|(x, y)| (0..pattern.width).contains(x) && (0..pattern.height).contains(y)));
+                prop_assert_eq!(size(text.lines().map(String::from)), (pattern.width, pattern.height));
+            }
+        }
+    }
+}
@@ -0,0 +1,350 @@
+// Reading and writing patterns in a Run Length Encoded format
+// See https://conwaylife.com/wiki/Run_Length_Encoded
+use std::fmt;
+use super::{PatternFile, bounding_box};
+
+// Errors that can occur while reading a pattern
+#[derive(Debug, PartialEq)]
+pub enum RleError {
+    // There is no `x = .., y = ..` line
+    MissingHeader,
+    // The header line can't be read
+    InvalidHeader(String),
+    // The header has a negative width or height
+    NegativeSize(i32, i32),
+    // A character that is not a part of the format
+    UnexpectedChar(char),
+    // A run count is too big
+    InvalidRunCount,
+    // There is no `!` at the end of the pattern
+    MissingTerminator,
+    // An alive cell is outside of the size in the header
+    CellOutside(i32, i32),
+    // The pattern has more than `MAX_ALIVE_CELLS` alive cells
+    TooManyCells,
+}
+
+// Maximal number of alive cells of a pattern, so a file can't take all the memory
+pub const MAX_ALIVE_CELLS: usize = 1 << 22;
+
+impl fmt::Display for RleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RleError::MissingHeader => write!(f, "No RLE header"),
+            RleError::InvalidHeader(line) => write!(f, "Bad RLE header: {}", line),
+            RleError::NegativeSize(width, height) => write!(f, "Negative RLE size {}x{}", width, height),
+            RleError::UnexpectedChar(c) => write!(f, "Unexpected '{}' in RLE", c),
+            RleError::InvalidRunCount => write!(f, "Bad run count in RLE"),
+            RleError::MissingTerminator => write!(f, "No '!' at the end of RLE"),
+            RleError::CellOutside(x, y) => write!(f, "Cell {}, {} is outside of the RLE size", x, y),
+            RleError::TooManyCells => write!(f, "RLE has more than {} alive cells", MAX_ALIVE_CELLS),
+        }
+    }
+}
+
+impl std::error::Error for RleError {}
+
+// Function reads a pattern from the contents of an RLE file
+// Alive cells have to be within the size in the header, which also keeps huge run counts
+// like `999999999o` from filling the memory
+pub fn parse(text: &str) -> Result<PatternFile, RleError> {
+    // Comment lines start with `#`
+    let mut lines = text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let header = lines.next().ok_or(RleError::MissingHeader)?;
+    let (width, height, rule) = parse_header(header)?;
+
+    let mut cells = Vec::new();
+    let mut states = Vec::new();
+    let mut x: i32 = 0;
+    let mut y: i32 = 0;
+    // Number of repetitions of the next cell (or line end)
+    let mut count: Option<i32> = None;
+    for line in lines {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap_or(0) as i32;
+                    let value = count.unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|value| value.checked_add(digit))
+                        .ok_or(RleError::InvalidRunCount)?;
+                    count = Some(value);
+                },
+                // Dead cells
+                'b' | '.' => {
+                    x = x.saturating_add(count.take().unwrap_or(1));
+                },
+                // Alive cells, `A`, `B`, ... are cells in states 1, 2, ... of multi-state rules
+                'o' | 'A'..='Z' => {
+                    let run = count.take().unwrap_or(1);
+                    if run == 0 {
+                        continue;
+                    }
+                    if y >= height || run > width - x.min(width) {
+                        return Err(RleError::CellOutside(x, y));
+                    }
+                    if cells.len() + run as usize > MAX_ALIVE_CELLS {
+                        return Err(RleError::TooManyCells);
+                    }
+                    cells.extend((x..x + run).map(|x| (x, y)));
+                    states.extend(std::iter::repeat_n(state_of(c), run as usize));
+                    x += run;
+                },
+                // End of a line
+                '$' => {
+                    y = y.saturating_add(count.take().unwrap_or(1));
+                    x = 0;
+                },
+                // End of the pattern
+                '!' => {
+                    return Ok(PatternFile{width, height, rule, cells, states});
+                },
+                c if c.is_whitespace() => (),
+                c => return Err(RleError::UnexpectedChar(c)),
+            }
+        }
+    }
+
+    Err(RleError::MissingTerminator)
+}
+
+// Function finds the size of the pattern in the header among the lines without reading its cells
+// Returns `None` if there is no valid header
+pub fn header_size<I: Iterator<Item = String>>(lines: I) -> Option<(i32, i32)> {
+    let header = lines.map(|line| String::from(line.trim()))
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    parse_header(&header).ok().map(|(width, height, _)| (width, height))
+}
+
+// Maximal length of a line of an encoded pattern
+const MAX_LINE_LENGTH: usize = 70;
+
+// Function writes alive cells as the contents of an RLE file
+// Only the bounding box of the cells is written
+pub fn encode(cells: &[(i32, i32)], rule: &str) -> String {
+    let symbols: Vec<(i32, i32, char)> = cells.iter().map(|(x, y)| (*x, *y, 'o')).collect();
+    encode_symbols(&symbols, 'b', rule)
+}
+
+// Function writes cells with their states as the contents of an RLE file of a multi-state rule
+// States 1, 2, ... are written as `A`, `B`, ... and dead cells as `.`
+pub fn encode_states(cells: &[(i32, i32, u8)], rule: &str) -> String {
+    let symbols: Vec<(i32, i32, char)> = cells.iter().map(|(x, y, state)| (*x, *y, symbol_of(*state))).collect();
+    encode_symbols(&symbols, '.', rule)
+}
+
+// State of a cell written as the symbol
+fn state_of(symbol: char) -> u8 {
+    match symbol {
+        'A'..='Z' => symbol as u8 - b'A' + 1,
+        _ => 1,
+    }
+}
+
+// Symbol of a cell in the state (states over 26 are written as the last letter)
+fn symbol_of(state: u8) -> char {
+    (b'A' + state.clamp(1, 26) - 1) as char
+}
+
+// Function writes cells given by their symbols as the contents of an RLE file with the symbol of dead cells
+fn encode_symbols(cells: &[(i32, i32, char)], dead: char, rule: &str) -> String {
+    let coords: Vec<(i32, i32)> = cells.iter().map(|(x, y, _)| (*x, *y)).collect();
+    let (min_x, min_y, width, height) = bounding_box(&coords);
+
+    // Rows of the pattern with cells sorted from left to right
+    let mut rows = vec![Vec::new(); height as usize];
+    for (x, y, symbol) in cells {
+        rows[(y - min_y) as usize].push((x - min_x, *symbol));
+    }
+
+    // Runs of the same symbols like `3o` or `2$`
+    let mut runs: Vec<(i32, char)> = Vec::new();
+    for row in rows.iter_mut() {
+        row.sort_unstable_by_key(|(x, _)| *x);
+        row.dedup_by_key(|(x, _)| *x);
+        let mut x = 0;
+        for (cell_x, symbol) in row.iter() {
+            if *cell_x > x {
+                push_run(&mut runs, cell_x - x, dead);
+            }
+            push_run(&mut runs, 1, *symbol);
+            x = cell_x + 1;
+        }
+        push_run(&mut runs, 1, '$');
+    }
+    // The last line doesn't need an end
+    runs.pop();
+    runs.push((1, '!'));
+
+    let mut text = format!("x = {}, y = {}, rule = {}\n", width, height, rule);
+    let mut line = String::new();
+    for (count, symbol) in runs {
+        let run = match count {
+            1 => symbol.to_string(),
+            _ => format!("{}{}", count, symbol),
+        };
+        if line.len() + run.len() > MAX_LINE_LENGTH {
+            text.push_str(&line);
+            text.push('\n');
+            line.clear();
+        }
+        line.push_str(&run);
+    }
+    text.push_str(&line);
+    text.push('\n');
+    text
+}
+
+// Function adds symbols to the last run if it is made of the same ones
+fn push_run(runs: &mut Vec<(i32, char)>, count: i32, symbol: char) {
+    match runs.last_mut() {
+        Some((last_count, last_symbol)) if *last_symbol == symbol => *last_count += count,
+        _ => runs.push((count, symbol)),
+    }
+}
+
+// Function reads size and rule of the pattern from a line like `x = 3, y = 3, rule = B3/S23`
+fn parse_header(line: &str) -> Result<(i32, i32, Option<String>), RleError> {
+    let invalid = || RleError::InvalidHeader(String::from(line));
+
+    let mut width = None;
+    let mut height = None;
+    let mut rule = None;
+    for part in line.split(',') {
+        let (key, value) = part.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim();
+        match key.trim() {
+            "x" => width = Some(value.parse::<i32>().map_err(|_| invalid())?),
+            "y" => height = Some(value.parse::<i32>().map_err(|_| invalid())?),
+            "rule" => rule = Some(String::from(value)),
+            _ => return Err(invalid()),
+        }
+    }
+
+    match (width, height) {
+        (Some(width), Some(height)) if width < 0 || height < 0 => Err(RleError::NegativeSize(width, height)),
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _ => Err(invalid()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+    use proptest::prelude::{any, prop_assert, proptest, ProptestConfig};
+
+    // Cells sorted the same way for comparison
+    fn sorted(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+        let mut cells = cells.to_vec();
+        cells.sort();
+        cells
+    }
+
+    #[test]
+    fn parses_glider() {
+        let text = "#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n";
+        let rle = parse(text).unwrap();
+        assert_eq!((rle.width, rle.height), (3, 3));
+        assert_eq!(rle.rule.as_deref(), Some("B3/S23"));
+        assert_eq!(sorted(&rle.cells), sorted(patterns::GLIDER.cells));
+    }
+
+    #[test]
+    fn parses_gosper_gun() {
+        let text = "\
+#N Gosper glider gun
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+";
+        let rle = parse(text).unwrap();
+        assert_eq!((rle.width, rle.height), (36, 9));
+        assert_eq!(sorted(&rle.cells), sorted(patterns::GOSPER_GUN.cells));
+    }
+
+    #[test]
+    fn export_and_import_give_the_same_cells() {
+        for pattern in patterns::PRESETS.iter() {
+            // Patterns are moved away from the corner to check the bounding box
+            let cells: Vec<(i32, i32)> = pattern.cells.iter().map(|(x, y)| (x + 5, y + 7)).collect();
+            let text = encode(&cells, "B3/S23");
+            assert!(text.lines().all(|line| line.len() <= 70));
+
+            let rle = parse(&text).unwrap();
+            assert_eq!(rle.rule.as_deref(), Some("B3/S23"));
+            assert_eq!(sorted(&rle.cells), sorted(pattern.cells));
+        }
+    }
+
+    #[test]
+    fn exports_empty_rows_and_empty_patterns() {
+        assert_eq!(encode(&[(0, 0), (2, 3)], "B3/S23"), "x = 3, y = 4, rule = B3/S23\no3$2bo!\n");
+        assert_eq!(encode(&[], "B3/S23"), "x = 0, y = 0, rule = B3/S23\n!\n");
+    }
+
+    #[test]
+    fn keeps_states_of_cells() {
+        let cells = [(2, 5, 1), (3, 5, 2), (5, 6, 2)];
+        let text = encode_states(&cells, "B3/S23");
+        assert_eq!(text, "x = 4, y = 2, rule = B3/S23\nAB$3.B!\n");
+        let rle = parse(&text).unwrap();
+        assert_eq!(rle.cells, vec![(0, 0), (1, 0), (3, 1)]);
+        assert_eq!(rle.states, vec![1, 2, 2]);
+        // Plain alive cells are in state 1
+        assert_eq!(parse("x = 2, y = 1\noA!").unwrap().states, vec![1, 1]);
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        assert_eq!(parse("").unwrap_err(), RleError::MissingHeader);
+        assert!(matches!(parse("x = 3\nooo!").unwrap_err(), RleError::InvalidHeader(_)));
+        assert_eq!(parse("x = 3, y = 1\nozo!").unwrap_err(), RleError::UnexpectedChar('z'));
+        assert_eq!(parse("x = 3, y = 1\nooo").unwrap_err(), RleError::MissingTerminator);
+        assert_eq!(parse("x = 3, y = 1\n99999999999o!").unwrap_err(), RleError::InvalidRunCount);
+    }
+
+    #[test]
+    fn rejects_hostile_patterns() {
+        // Truncated headers and negative sizes
+        assert!(matches!(parse("x = 3, y").unwrap_err(), RleError::InvalidHeader(_)));
+        assert_eq!(parse("x = -3, y = 1\no!").unwrap_err(), RleError::NegativeSize(-3, 1));
+        // Huge runs of alive cells don't fit into the size in the header
+        assert_eq!(parse("x = 3, y = 1\n999999999o!").unwrap_err(), RleError::CellOutside(0, 0));
+        assert_eq!(parse("x = 3, y = 2\n2147483647b2147483647bo!").unwrap_err(), RleError::CellOutside(i32::MAX, 0));
+        assert_eq!(parse("x = 3, y = 2\n2147483647$2147483647$o!").unwrap_err(), RleError::CellOutside(0, i32::MAX));
+        // A header as large as the run still can't fill the memory
+        let text = "x = 2000000000, y = 1\n999999999o!";
+        assert_eq!(parse(text).unwrap_err(), RleError::TooManyCells);
+        // Comments between the lines of cells are skipped
+        let rle = parse("x = 3, y = 3\nbo$\n#C A comment\n2bo$3o!").unwrap();
+        assert_eq!(sorted(&rle.cells), sorted(patterns::GLIDER.cells));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(256))]
+
+        #[test]
+        fn arbitrary_text_never_panics(text in any::<String>()) {
+            let _ = parse(&text);
+        }
+
+        // Patterns made of the symbols of the format are cut anywhere, and the cells of the ones
+        // that are still read have to be within their size
+        #[test]
+        fn cells_stay_within_the_header(
+            text in "x = -?[0-9]{1,10}, y = -?[0-9]{1,10}(, rule = [BS0-9/]{0,8})?\n([0-9]{0,10}[bo.A$!# \n]){0,40}",
+            cut in any::<usize>(),
+        ) {
+            // The symbols are all ASCII, so the text can be cut at any byte
+            if let Ok(rle) = parse(&text[..cut % (text.len() + 1)]) {
+                prop_assert!(rle.cells.len() <= MAX_ALIVE_CELLS);
+                prop_assert!(rle.cells.iter().all(|(x, y)| (0..rle.width).contains(x) && (0..rle.height).contains(y)));
+            }
+        }
+    }
+}
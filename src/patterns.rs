@@ -0,0 +1,76 @@
+// Classic patterns that can be stamped on the board
+
+// A named pattern made of alive cells
+// Coordinates of the cells are relative to the upper left corner of the pattern
+pub struct Pattern {
+    pub name: &'static str,
+    pub cells: &'static [(i32, i32)],
+}
+
+pub const GLIDER: Pattern = Pattern {
+    name: "Glider",
+    cells: &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)],
+};
+
+pub const BLINKER: Pattern = Pattern {
+    name: "Blinker",
+    cells: &[(0, 0), (1, 0), (2, 0)],
+};
+
+pub const PULSAR: Pattern = Pattern {
+    name: "Pulsar",
+    cells: &[
+        (2, 0), (3, 0), (4, 0), (8, 0), (9, 0), (10, 0), (0, 2), (5, 2),
+        (7, 2), (12, 2), (0, 3), (5, 3), (7, 3), (12, 3), (0, 4), (5, 4),
+        (7, 4), (12, 4), (2, 5), (3, 5), (4, 5), (8, 5), (9, 5), (10, 5),
+        (2, 7), (3, 7), (4, 7), (8, 7), (9, 7), (10, 7), (0, 8), (5, 8),
+        (7, 8), (12, 8), (0, 9), (5, 9), (7, 9), (12, 9), (0, 10), (5, 10),
+        (7, 10), (12, 10), (2, 12), (3, 12), (4, 12), (8, 12), (9, 12), (10, 12),
+    ],
+};
+
+// Lightweight spaceship
+pub const LWSS: Pattern = Pattern {
+    name: "LWSS",
+    cells: &[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)],
+};
+
+pub const GOSPER_GUN: Pattern = Pattern {
+    name: "Gosper gun",
+    cells: &[
+        (24, 0), (22, 1), (24, 1), (12, 2), (13, 2), (20, 2), (21, 2), (34, 2),
+        (35, 2), (11, 3), (15, 3), (20, 3), (21, 3), (34, 3), (35, 3), (0, 4),
+        (1, 4), (10, 4), (16, 4), (20, 4), (21, 4), (0, 5), (1, 5), (10, 5),
+        (14, 5), (16, 5), (17, 5), (22, 5), (24, 5), (10, 6), (16, 6), (24, 6),
+        (11, 7), (15, 7), (12, 8), (13, 8),
+    ],
+};
+
+// Patterns selected with number keys
+pub const PRESETS: [Pattern; 5] = [GLIDER, BLINKER, PULSAR, LWSS, GOSPER_GUN];
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn presets_evolve_as_expected() {
+        // Pulsar is an oscillator with period 3
+        let mut board = Board::new(20, 20);
+        board.stamp(3, 3, PULSAR.cells);
+        for _ in 0..3 {
+            board.step();
+        }
+        assert_eq!(board.population(), PULSAR.cells.len());
+
+        // Gosper gun emits a new glider every 30 generations
+        let mut board = Board::new(60, 40);
+        board.stamp(1, 1, GOSPER_GUN.cells);
+        for _ in 0..30 {
+            board.step();
+        }
+        assert_eq!(board.population(), GOSPER_GUN.cells.len() + GLIDER.cells.len());
+    }
+}
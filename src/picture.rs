@@ -0,0 +1,111 @@
+// Cells seeded from pictures: dark pixels become alive cells
+use std::fmt;
+use std::path::Path;
+use image::GrayImage;
+
+// Luminance below which a cell is alive by default
+pub const DEFAULT_THRESHOLD: u8 = 128;
+
+// Limit of the width and the height of a picture (in pixels)
+pub const MAX_SIDE: u32 = 10_000;
+
+// Errors that can occur while reading a picture
+#[derive(Debug)]
+pub enum PictureError {
+    Image(image::ImageError),
+    // Width and height of a picture larger than `MAX_SIDE`
+    TooLarge(u32, u32),
+}
+
+impl fmt::Display for PictureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PictureError::Image(e) => write!(f, "{}", e),
+            PictureError::TooLarge(width, height) => write!(f, "The picture is {}x{} pixels, at most {}x{} are allowed",
+                width, height, MAX_SIDE, MAX_SIDE),
+        }
+    }
+}
+
+impl std::error::Error for PictureError {}
+
+impl From<image::ImageError> for PictureError {
+    fn from(e: image::ImageError) -> PictureError {
+        PictureError::Image(e)
+    }
+}
+
+// Function reads the picture and finds cells of a `cols` x `rows` board covered by its dark parts
+// The size is checked before the picture is decoded, so huge pictures are rejected quickly
+pub fn load<P: AsRef<Path>>(path: P, cols: i32, rows: i32, threshold: u8) -> Result<Vec<(i32, i32)>, PictureError> {
+    let (width, height) = image::image_dimensions(&path)?;
+    if width > MAX_SIDE || height > MAX_SIDE {
+        return Err(PictureError::TooLarge(width, height));
+    }
+    let picture = image::open(&path)?.into_luma8();
+    Ok(dark_cells(&picture, cols, rows, threshold))
+}
+
+// Function scales the picture to a `cols` x `rows` board keeping its proportions and centers it
+// Every cell takes the average luminance of the pixels it covers and is alive if it's below the threshold
+// Returns the alive cells row by row
+pub fn dark_cells(picture: &GrayImage, cols: i32, rows: i32, threshold: u8) -> Vec<(i32, i32)> {
+    let (width, height) = (picture.width() as i32, picture.height() as i32);
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    // The picture fills the board along one side and leaves equal margins along the other one
+    let scale = (cols as f64 / width as f64).min(rows as f64 / height as f64);
+    let fitted_cols = ((width as f64 * scale).round() as i32).clamp(1, cols);
+    let fitted_rows = ((height as f64 * scale).round() as i32).clamp(1, rows);
+    let (left, top) = ((cols - fitted_cols) / 2, (rows - fitted_rows) / 2);
+
+    // Pixels of the cell along one side, at least one when the picture is smaller than the board
+    let pixels = |cell: i32, cells: i32, size: i32| {
+        let start = cell * size / cells;
+        start..((cell + 1) * size / cells).max(start + 1)
+    };
+    let mut cells = Vec::new();
+    for row in 0..fitted_rows {
+        for col in 0..fitted_cols {
+            let (xs, ys) = (pixels(col, fitted_cols, width), pixels(row, fitted_rows, height));
+            let count = xs.len() * ys.len();
+            let sum: u64 = ys.flat_map(|y| xs.clone().map(move |x| (x, y)))
+                .map(|(x, y)| picture.get_pixel(x as u32, y as u32).0[0] as u64)
+                .sum();
+            if sum < threshold as u64 * count as u64 {
+                cells.push((left + col, top + row));
+            }
+        }
+    }
+    cells
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dark_pixels_become_alive_cells() {
+        // A 4x4 picture with a dark upper left quarter and a gray lower right one
+        let picture = GrayImage::from_fn(4, 4, |x, y| match (x < 2, y < 2) {
+            (true, true) => image::Luma([0]),
+            (false, false) => image::Luma([120]),
+            _ => image::Luma([255]),
+        });
+        assert_eq!(dark_cells(&picture, 2, 2, DEFAULT_THRESHOLD), vec![(0, 0), (1, 1)]);
+        assert_eq!(dark_cells(&picture, 2, 2, 100), vec![(0, 0)]);
+        // A small picture is scaled up
+        assert_eq!(dark_cells(&picture, 4, 4, DEFAULT_THRESHOLD).len(), 8);
+    }
+
+    #[test]
+    fn wide_picture_is_letterboxed() {
+        // A black 4x2 picture fills the width of a 4x4 board and the middle 2 rows
+        let picture = GrayImage::new(4, 2);
+        let cells = dark_cells(&picture, 4, 4, DEFAULT_THRESHOLD);
+        assert_eq!(cells.len(), 8);
+        assert!(cells.iter().all(|(_, row)| *row == 1 || *row == 2));
+    }
+}
@@ -0,0 +1,116 @@
+// Pattern files loaded the latest, kept between sessions in a file of one path per line
+use directories::ProjectDirs;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// Number of files kept
+pub const LIMIT: usize = 8;
+
+// Paths of the files, the latest first
+#[derive(Debug, Default, PartialEq)]
+pub struct RecentFiles {
+    paths: Vec<PathBuf>,
+}
+
+impl RecentFiles {
+    // Function reads the paths from the lines of the text
+    // Empty lines and paths over the limit are skipped
+    pub fn parse(text: &str) -> RecentFiles {
+        let paths = text.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .take(LIMIT)
+            .map(PathBuf::from)
+            .collect();
+        RecentFiles{paths}
+    }
+
+    // Function writes the paths as lines of text
+    pub fn to_text(&self) -> String {
+        self.paths.iter().map(|path| format!("{}\n", path.display())).collect()
+    }
+
+    // Paths of the files, the latest first
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    // Function puts the path first and forgets the oldest path over the limit
+    // A path that is already on the list is moved to the top
+    pub fn push(&mut self, path: &Path) {
+        self.remove(path);
+        self.paths.insert(0, path.to_path_buf());
+        self.paths.truncate(LIMIT);
+    }
+
+    // Function forgets the path
+    pub fn remove(&mut self, path: &Path) {
+        self.paths.retain(|recent| recent != path);
+    }
+}
+
+// Function reads the list from the file
+// There are no recent files if the file doesn't exist yet
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<RecentFiles> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(RecentFiles::parse(&text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(RecentFiles::default()),
+        Err(e) => Err(e),
+    }
+}
+
+// Function writes the list to the file
+// Missing directories of the path are created
+pub fn save<P: AsRef<Path>>(recent: &RecentFiles, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, recent.to_text())
+}
+
+// Path of the list in the data directory of the user, next to the session file
+// (like `~/.local/share/gameoflife/recent.txt` on Linux)
+pub fn default_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "gameoflife").map(|dirs| dirs.data_dir().join("recent.txt"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn latest_files_come_first() {
+        let mut recent = RecentFiles::default();
+        for i in 0..10 {
+            recent.push(Path::new(&format!("pattern_{}.rle", i)));
+        }
+        assert_eq!(recent.paths().len(), LIMIT);
+        assert_eq!(recent.paths()[0], PathBuf::from("pattern_9.rle"));
+        assert_eq!(recent.paths()[LIMIT - 1], PathBuf::from("pattern_2.rle"));
+
+        // Loading a file again moves it to the top instead of repeating it
+        recent.push(Path::new("pattern_5.rle"));
+        assert_eq!(recent.paths().len(), LIMIT);
+        assert_eq!(recent.paths()[0], PathBuf::from("pattern_5.rle"));
+        recent.remove(Path::new("pattern_5.rle"));
+        assert_eq!(recent.paths().len(), LIMIT - 1);
+    }
+
+    #[test]
+    fn list_is_kept_in_file() {
+        let path = env::temp_dir().join(format!("game_of_life_recent_{}", std::process::id())).join("recent.txt");
+        assert_eq!(load(&path).unwrap(), RecentFiles::default());
+
+        let mut recent = RecentFiles::default();
+        recent.push(Path::new("/patterns/gun.rle"));
+        recent.push(Path::new("/patterns/glider.cells"));
+        save(&recent, &path).unwrap();
+        assert_eq!(load(&path).unwrap(), recent);
+        assert_eq!(RecentFiles::parse("a.rle\r\n\r\nb.rle\n").paths().len(), 2);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
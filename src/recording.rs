@@ -0,0 +1,116 @@
+// Recording of generations of the board to an animated GIF
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+use crate::automaton;
+use crate::board::Board;
+use crate::render;
+use crate::theme::Theme;
+
+// Maximal number of generations in a recording
+pub const MAX_FRAMES: usize = 2000;
+
+// Length of a side of a cell in recordings (in pixels)
+// It's smaller than in screenshots to keep long recordings small
+pub const CELL_PIXELS: u32 = 4;
+
+// Generations of the board recorded one after another
+pub struct Recording {
+    // Size of the board
+    width: i32,
+    height: i32,
+    // States of all cells of every generation
+    frames: Vec<Vec<u8>>,
+}
+
+impl Recording {
+    // Constructor for a recording that starts with the current cells of the board
+    pub fn new(board: &Board) -> Recording {
+        Recording{width: board.width(), height: board.height(), frames: vec![board.cells().to_vec()]}
+    }
+
+    // Function adds the current cells of the board to the recording
+    // Returns `false` if the recording has `MAX_FRAMES` frames already
+    pub fn push(&mut self, board: &Board) -> bool {
+        if self.is_full() {
+            return false;
+        }
+        self.frames.push(board.cells().to_vec());
+        true
+    }
+
+    // Number of recorded generations
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    // Does the recording have no frames
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // Does the recording have `MAX_FRAMES` frames
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= MAX_FRAMES
+    }
+
+    // Function writes all frames to an animated GIF file that repeats forever
+    // `delay` is the time every frame is shown for, in hundredths of a second
+    // Missing directories of the path are created
+    pub fn save_gif<P: AsRef<Path>>(&self, path: P, theme: &Theme, grid: bool, delay: u16) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let width = self.width as u32 * CELL_PIXELS;
+        let height = self.height as u32 * CELL_PIXELS;
+        let (width, height) = match (u16::try_from(width), u16::try_from(height)) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(io::Error::other("The field is too large for a GIF")),
+        };
+        // GIF palettes have no opacity
+        let palette: Vec<u8> = render::palette(theme).iter().flat_map(|color| color[..3].to_vec()).collect();
+
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = gif::Encoder::new(file, width, height, &palette).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+        for cells in self.frames.iter() {
+            let alive = |x: i32, y: i32| automaton::is_alive(cells[(y * self.width + x) as usize]);
+            let buffer = render::indices(self.width, self.height, alive, CELL_PIXELS, grid);
+            let frame = gif::Frame{width, height, delay, buffer: Cow::Owned(buffer), ..gif::Frame::default()};
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+    use std::env;
+
+    #[test]
+    fn records_every_generation_until_full() {
+        let mut board = Board::new(10, 10);
+        board.stamp(4, 4, patterns::BLINKER.cells);
+        let mut recording = Recording::new(&board);
+        for _ in 0..3 {
+            board.step();
+            assert!(recording.push(&board));
+        }
+        assert_eq!(recording.len(), 4);
+
+        let path = env::temp_dir().join(format!("game_of_life_{}.gif", std::process::id()));
+        recording.save_gif(&path, &Theme::default(), false, 10).unwrap();
+        let file = fs::read(&path).unwrap();
+        assert!(file.starts_with(b"GIF89a"));
+        fs::remove_file(path).unwrap();
+
+        while recording.push(&board) {}
+        assert_eq!(recording.len(), MAX_FRAMES);
+        assert!(recording.is_full());
+    }
+}
@@ -0,0 +1,109 @@
+// Pictures of the board drawn from its cells without a window
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::Path;
+use crate::board::Board;
+use crate::theme::{Theme, ThemeColor};
+
+// Length of a side of a cell in exported pictures (in pixels)
+pub const CELL_PIXELS: u32 = 10;
+
+// A picture with 4 bytes (red, green, blue and opacity) in every pixel stored row by row
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    // Function returns the color of the pixel
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * self.width + x) * 4) as usize;
+        [self.pixels[index], self.pixels[index + 1], self.pixels[index + 2], self.pixels[index + 3]]
+    }
+}
+
+// Indices of colors in palettes of pictures
+pub const BACKGROUND: u8 = 0;
+pub const ALIVE: u8 = 1;
+pub const GRID: u8 = 2;
+
+// Function returns colors of the theme in the order of their indices
+pub fn palette(theme: &Theme) -> [[u8; 4]; 3] {
+    [bytes(theme.background), bytes(theme.cell_alive), bytes(theme.grid)]
+}
+
+// Function draws cells of a board of the given size with indices of colors, one byte in a pixel
+// Every cell is a square of `cell_pixels` pixels, with a grid line on its left and top edges if `grid` is set
+pub fn indices<F: Fn(i32, i32) -> bool>(cols: i32, rows: i32, alive: F, cell_pixels: u32, grid: bool) -> Vec<u8> {
+    let width = cols as u32 * cell_pixels;
+    let height = rows as u32 * cell_pixels;
+    let mut pixels = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let on_line = grid && cell_pixels > 2 && (x % cell_pixels == 0 || y % cell_pixels == 0);
+            pixels.push(match (on_line, alive((x / cell_pixels) as i32, (y / cell_pixels) as i32)) {
+                (true, _) => GRID,
+                (false, true) => ALIVE,
+                (false, false) => BACKGROUND,
+            });
+        }
+    }
+    pixels
+}
+
+// Function draws the board with the colors of the theme
+pub fn render(board: &Board, theme: &Theme, cell_pixels: u32, grid: bool) -> Frame {
+    let palette = palette(theme);
+    let pixels = indices(board.width(), board.height(), |x, y| board.get(x, y), cell_pixels, grid)
+        .into_iter()
+        .flat_map(|index| palette[index as usize])
+        .collect();
+    Frame{width: board.width() as u32 * cell_pixels, height: board.height() as u32 * cell_pixels, pixels}
+}
+
+// Function writes the frame to a PNG file
+// Missing directories of the path are created
+pub fn save_png<P: AsRef<Path>>(frame: &Frame, path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut encoder = png::Encoder::new(BufWriter::new(File::create(path)?), frame.width, frame.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&frame.pixels))
+        .map_err(io::Error::other)
+}
+
+// Function converts a color of a theme to bytes of a pixel
+fn bytes(color: ThemeColor) -> [u8; 4] {
+    let byte = |component: f32| (component.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [byte(color.r), byte(color.g), byte(color.b), byte(color.a)]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_cells_as_squares() {
+        let mut board = Board::new(3, 2);
+        board.set(1, 1, true);
+        let theme = Theme::light();
+        let frame = render(&board, &theme, 4, false);
+        assert_eq!((frame.width, frame.height), (12, 8));
+        assert_eq!(frame.pixels.len(), 12 * 8 * 4);
+        assert_eq!(frame.pixel(4, 4), bytes(theme.cell_alive));
+        assert_eq!(frame.pixel(7, 7), bytes(theme.cell_alive));
+        assert_eq!(frame.pixel(3, 4), bytes(theme.background));
+
+        // Grid lines are drawn over the edges of cells
+        let frame = render(&board, &theme, 4, true);
+        assert_eq!(frame.pixel(4, 4), bytes(theme.grid));
+        assert_eq!(frame.pixel(5, 5), bytes(theme.cell_alive));
+    }
+}
@@ -0,0 +1,188 @@
+// Rules of Life-like games written as rulestrings like "B3/S23"
+// and rules of the Generations family like "B2/S345/C4"
+// See https://conwaylife.com/wiki/Rulestring and https://conwaylife.com/wiki/Generations
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// Numbers of alive neighbours that make a cell come to life or survive
+// Rules are saved as rulestrings
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Rule {
+    // Bit N is set if a dead cell with N alive neighbours comes to life
+    birth: u32,
+    // Bit N is set if an alive cell with N alive neighbours survives
+    survival: u32,
+    // Number of states of cells: 2 for Life-like rules
+    // With more states alive cells that don't survive are dying for `states - 2` generations
+    states: u8,
+}
+
+// Error of reading a rulestring
+#[derive(Debug, PartialEq)]
+pub struct RuleError(pub String);
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Bad rule: {}", self.0)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+impl Rule {
+    // Classic rules of the game
+    pub const CONWAY: Rule = Rule{birth: 1 << 3, survival: 1 << 2 | 1 << 3, states: 2};
+    // B36/S23 with replicators
+    pub const HIGHLIFE: Rule = Rule{birth: 1 << 3 | 1 << 6, survival: 1 << 2 | 1 << 3, states: 2};
+    // B2/S where every cell dies right after it is born
+    pub const SEEDS: Rule = Rule{birth: 1 << 2, survival: 0, states: 2};
+    // B3678/S34678 where dead and alive cells behave the same way
+    pub const DAY_AND_NIGHT: Rule = Rule{
+        birth: 1 << 3 | 1 << 6 | 1 << 7 | 1 << 8,
+        survival: 1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8,
+        states: 2,
+    };
+
+    // Constructor for a rule from lists of numbers of neighbours
+    pub fn new(birth: &[u32], survival: &[u32]) -> Rule {
+        let to_bits = |counts: &[u32]| counts.iter().fold(0, |bits, count| bits | 1 << count);
+        Rule{birth: to_bits(birth), survival: to_bits(survival), states: 2}
+    }
+
+    // Function returns the rule with cells that have the given number of states
+    pub fn with_states(self, states: u8) -> Rule {
+        Rule{states, ..self}
+    }
+
+    // Number of states of cells, including dead and alive ones
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    // Does a dead cell with this number of alive neighbours come to life
+    pub fn born(&self, neighbours: u32) -> bool {
+        neighbours < 32 && self.birth & 1 << neighbours != 0
+    }
+
+    // Does an alive cell with this number of alive neighbours survive
+    pub fn survives(&self, neighbours: u32) -> bool {
+        neighbours < 32 && self.survival & 1 << neighbours != 0
+    }
+}
+
+// Rules that can be switched during the game
+pub const PRESETS: [Rule; 4] = [Rule::CONWAY, Rule::HIGHLIFE, Rule::SEEDS, Rule::DAY_AND_NIGHT];
+
+impl Default for Rule {
+    fn default() -> Rule {
+        Rule::CONWAY
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    // Reads both "B36/S23" and the older "23/36" (survival first) notations
+    // Generations rules have the number of states in the third part, like "B2/S345/C4" or "345/2/4"
+    fn from_str(text: &str) -> Result<Rule, RuleError> {
+        let invalid = || RuleError(String::from(text));
+
+        let parts: Vec<&str> = text.trim().split('/').collect();
+        let (first, second, states) = match parts[..] {
+            [first, second] => (first, second, 2),
+            [first, second, states] => {
+                let states = states.strip_prefix(['C', 'c']).unwrap_or(states);
+                (first, second, states.parse::<u8>().ok().filter(|states| *states >= 2).ok_or_else(invalid)?)
+            },
+            _ => return Err(invalid()),
+        };
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), _) => (&first[1..], second.strip_prefix(['S', 's']).ok_or_else(invalid)?),
+            (Some('S' | 's'), _) => (second.strip_prefix(['B', 'b']).ok_or_else(invalid)?, &first[1..]),
+            _ => (second, first),
+        };
+
+        let counts = |digits: &str| -> Result<Vec<u32>, RuleError> {
+            digits.chars()
+                .map(|c| c.to_digit(10).filter(|count| *count <= 8).ok_or_else(invalid))
+                .collect()
+        };
+        Ok(Rule::new(&counts(birth)?, &counts(survival)?).with_states(states))
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = |bits: u32| -> String {
+            (0..32).filter(|count| bits & 1 << count != 0).map(|count| count.to_string()).collect()
+        };
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))?;
+        match self.states {
+            2 => Ok(()),
+            states => write!(f, "/C{}", states),
+        }
+    }
+}
+
+impl TryFrom<String> for Rule {
+    type Error = RuleError;
+
+    fn try_from(text: String) -> Result<Rule, RuleError> {
+        text.parse()
+    }
+}
+
+impl From<Rule> for String {
+    fn from(rule: Rule) -> String {
+        rule.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_notations() {
+        let highlife = Rule::new(&[3, 6], &[2, 3]);
+        assert_eq!("B36/S23".parse::<Rule>(), Ok(highlife));
+        assert_eq!("b36/s23".parse::<Rule>(), Ok(highlife));
+        assert_eq!("S23/B36".parse::<Rule>(), Ok(highlife));
+        assert_eq!("23/36".parse::<Rule>(), Ok(highlife));
+        assert_eq!("23/3".parse::<Rule>(), Ok(Rule::CONWAY));
+        // Seeds has no numbers of neighbours to survive
+        assert_eq!("B2/S".parse::<Rule>(), Ok(Rule::new(&[2], &[])));
+    }
+
+    #[test]
+    fn rejects_bad_rulestrings() {
+        for text in ["", "B3", "B3/23", "B9/S23", "B3/Sx", "B2/S345/C1", "B2/S345/", "345/2/4/4"] {
+            assert!(text.parse::<Rule>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn writes_rulestring() {
+        assert_eq!(Rule::CONWAY.to_string(), "B3/S23");
+        assert_eq!(Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]).to_string(), "B3678/S34678");
+    }
+
+    #[test]
+    fn parses_generations_rules() {
+        let star_wars = Rule::new(&[2], &[3, 4, 5]).with_states(4);
+        assert_eq!("345/2/4".parse::<Rule>(), Ok(star_wars));
+        assert_eq!("B2/S345/C4".parse::<Rule>(), Ok(star_wars));
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+        // Two states are a Life-like rule
+        assert_eq!("B3/S23/C2".parse::<Rule>(), Ok(Rule::CONWAY));
+    }
+
+    #[test]
+    fn presets_match_their_rulestrings() {
+        let rulestrings: Vec<String> = PRESETS.iter().map(|rule| rule.to_string()).collect();
+        assert_eq!(rulestrings, ["B3/S23", "B36/S23", "B2/S", "B3678/S34678"]);
+    }
+}
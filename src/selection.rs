@@ -0,0 +1,89 @@
+// Rectangular selection of cells to copy, cut and paste
+use crate::board::Board;
+use crate::undo::{self, Edit};
+
+// A rectangle of cells between two corners picked with a mouse
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Selection {
+    // Cell the selection was started at
+    pub start: (i32, i32),
+    // Cell the selection was dragged to
+    pub end: (i32, i32),
+}
+
+impl Selection {
+    // Constructor for a selection of a single cell
+    pub fn new(start: (i32, i32)) -> Selection {
+        Selection{start, end: start}
+    }
+
+    // Upper left corner and size of the selection
+    // The selection can be dragged in any direction, so the corners are sorted
+    pub fn bounds(&self) -> (i32, i32, i32, i32) {
+        let x = self.start.0.min(self.end.0);
+        let y = self.start.1.min(self.end.1);
+        let width = (self.start.0 - self.end.0).abs() + 1;
+        let height = (self.start.1 - self.end.1).abs() + 1;
+        (x, y, width, height)
+    }
+
+    // Function checks if the cell is inside of the selection
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        let (left, top, width, height) = self.bounds();
+        (left..left + width).contains(&x) && (top..top + height).contains(&y)
+    }
+}
+
+// Function returns alive cells of the selection
+// Coordinates are relative to the upper left corner of the selection
+pub fn copy(board: &Board, selection: &Selection) -> Vec<(i32, i32)> {
+    let (left, top, width, height) = selection.bounds();
+    let mut cells = Vec::new();
+    for y in top..top + height {
+        for x in left..left + width {
+            if board.get(x, y) {
+                cells.push((x - left, y - top));
+            }
+        }
+    }
+    cells
+}
+
+// Function kills all cells of the selection
+// Returns the edit, so it can be undone
+pub fn clear(board: &mut Board, selection: &Selection) -> Edit {
+    let (left, top, _, _) = selection.bounds();
+    copy(board, selection).into_iter()
+        .filter_map(|(x, y)| undo::paint(board, left + x, top + y, false))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_is_dragged_in_any_direction() {
+        let mut selection = Selection::new((5, 5));
+        assert_eq!(selection.bounds(), (5, 5, 1, 1));
+        selection.end = (2, 7);
+        assert_eq!(selection.bounds(), (2, 5, 4, 3));
+        assert!(selection.contains(2, 7));
+        assert!(selection.contains(5, 5));
+        assert!(!selection.contains(6, 5));
+    }
+
+    #[test]
+    fn copies_and_clears_cells_of_selection() {
+        let mut board = Board::new(10, 10);
+        board.stamp(2, 2, &[(0, 0), (1, 1), (4, 4)]);
+        let selection = Selection{start: (3, 3), end: (1, 1)};
+        assert_eq!(copy(&board, &selection), vec![(1, 1), (2, 2)]);
+
+        let edit = clear(&mut board, &selection);
+        assert_eq!(edit.len(), 2);
+        // Cells outside of the selection are left alive
+        assert_eq!(board.alive_cells(), vec![(6, 6)]);
+    }
+}
@@ -0,0 +1,233 @@
+// State of the game saved between sessions
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::automaton::{Automaton, ALIVE, IMMORTAL, WALL};
+use crate::board::Board;
+use crate::cli::{MAX_CELLS, MAX_SPEED, MIN_CELLS, MIN_SPEED};
+use crate::neighbourhood::Neighbourhood;
+use crate::rule::Rule;
+
+// Version of the format of session files
+// Files of other versions are not read
+pub const VERSION: u32 = 1;
+
+// Everything needed to continue the game where it was left
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    // Size of the board
+    pub width: i32,
+    pub height: i32,
+    // Coordinates of alive cells
+    pub cells: Vec<(i32, i32)>,
+    // States of alive cells that are not simply `ALIVE` (like electrons of Wireworld)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub states: Vec<(i32, i32, u8)>,
+    // Coordinates of walls
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub walls: Vec<(i32, i32)>,
+    // Coordinates of immortal cells (they are in `cells` as well)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub immortals: Vec<(i32, i32)>,
+    pub rule: Rule,
+    // Sessions saved before other automata were added are Life
+    #[serde(default)]
+    pub automaton: Automaton,
+    // Surviving cells of automata with colors are captured by the other color
+    #[serde(default)]
+    pub capture: bool,
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
+    pub wrap: bool,
+    // Speed of the game (generations per second)
+    pub speed: f64,
+    // Number of times the rules were applied
+    pub generation: u32,
+}
+
+// Errors that can occur while reading or writing a session file
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    // The file was written by another version of the game
+    Version(u32),
+    // Values of the file are out of their limits
+    Invalid(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionError::Io(e) => write!(f, "{}", e),
+            SessionError::Json(e) => write!(f, "{}", e),
+            SessionError::Version(version) => write!(f, "Session version {} is not {}", version, VERSION),
+            SessionError::Invalid(reason) => write!(f, "Bad session: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+impl Session {
+    // Constructor for a session of the board
+    pub fn new(board: &Board, speed: f64, generation: u32) -> Session {
+        Session{
+            version: VERSION,
+            width: board.width(),
+            height: board.height(),
+            cells: board.alive_cells(),
+            states: board.alive_cells().into_iter()
+                .map(|(x, y)| (x, y, board.state(x, y)))
+                .filter(|(_, _, state)| *state != ALIVE && *state != IMMORTAL)
+                .collect(),
+            walls: board.cells_in(WALL),
+            immortals: board.cells_in(IMMORTAL),
+            rule: board.rule(),
+            automaton: board.automaton(),
+            capture: board.capture(),
+            neighbourhood: board.neighbourhood(),
+            wrap: board.wrap(),
+            speed,
+            generation,
+        }
+    }
+
+    // Function creates a board with the saved cells, walls, immortal cells, rule, automaton, captures, neighbourhood and wrapping
+    pub fn board(&self) -> Board {
+        let mut board = Board::new(self.width, self.height);
+        board.set_rule(self.rule);
+        board.set_automaton(self.automaton);
+        board.set_capture(self.capture);
+        board.set_neighbourhood(self.neighbourhood);
+        board.set_wrap(self.wrap);
+        for (x, y) in self.cells.iter() {
+            board.set(*x, *y, true);
+        }
+        for (x, y, state) in self.states.iter() {
+            if *state < self.automaton.states(self.rule) {
+                board.set_state(*x, *y, *state);
+            }
+        }
+        for (x, y) in self.walls.iter() {
+            board.set_state(*x, *y, WALL);
+        }
+        for (x, y) in self.immortals.iter() {
+            board.set_state(*x, *y, IMMORTAL);
+        }
+        board
+    }
+
+    // Function writes the session as JSON
+    pub fn to_json(&self) -> String {
+        // A session has no maps with non-string keys, so it can always be written
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+// Function reads a session from JSON and checks that it can be continued
+pub fn parse(text: &str) -> Result<Session, SessionError> {
+    let session: Session = serde_json::from_str(text).map_err(SessionError::Json)?;
+    if session.version != VERSION {
+        return Err(SessionError::Version(session.version));
+    }
+    let cells = MIN_CELLS..=MAX_CELLS;
+    if !cells.contains(&session.width) || !cells.contains(&session.height) {
+        return Err(SessionError::Invalid(format!("size {}x{}", session.width, session.height)));
+    }
+    if !(MIN_SPEED..=MAX_SPEED).contains(&session.speed) {
+        return Err(SessionError::Invalid(format!("speed {}", session.speed)));
+    }
+    Ok(session)
+}
+
+// Function reads a session from the file
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Session, SessionError> {
+    let text = fs::read_to_string(path).map_err(SessionError::Io)?;
+    parse(&text)
+}
+
+// Function writes the session to the file
+// Missing directories of the path are created
+pub fn save<P: AsRef<Path>>(session: &Session, path: P) -> Result<(), SessionError> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(SessionError::Io)?;
+    }
+    fs::write(path, session.to_json()).map_err(SessionError::Io)
+}
+
+// Path of the session file in the data directory of the user
+// (like `~/.local/share/gameoflife/session.json` on Linux)
+pub fn default_path() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "gameoflife").map(|dirs| dirs.data_dir().join("session.json"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn session_survives_round_trip() {
+        let mut board = Board::new(40, 30);
+        board.stamp(5, 5, patterns::GLIDER.cells);
+        board.set_rule(Rule::HIGHLIFE);
+        board.set_wrap(true);
+        board.set_neighbourhood(Neighbourhood::VonNeumann);
+        let session = Session::new(&board, 15.0, 42);
+
+        let restored = parse(&session.to_json()).unwrap();
+        assert_eq!(restored, session);
+        let restored_board = restored.board();
+        assert_eq!(restored_board.alive_cells(), board.alive_cells());
+        assert_eq!(restored_board.rule(), Rule::HIGHLIFE);
+        assert!(restored_board.wrap());
+        assert_eq!(restored_board.neighbourhood(), Neighbourhood::VonNeumann);
+        assert!(session.to_json().contains("\"rule\": \"B36/S23\""));
+    }
+
+    #[test]
+    fn states_of_other_automata_are_kept() {
+        let mut board = Board::new(10, 10);
+        board.set_automaton(Automaton::Wireworld);
+        board.set_state(2, 2, crate::automaton::CONDUCTOR);
+        board.set_state(3, 2, crate::automaton::HEAD);
+        let session = Session::new(&board, 5.0, 0);
+        assert_eq!(session.states, vec![(3, 2, crate::automaton::HEAD)]);
+
+        let restored = parse(&session.to_json()).unwrap().board();
+        assert_eq!(restored.automaton(), Automaton::Wireworld);
+        assert_eq!(restored.cells(), board.cells());
+    }
+
+    #[test]
+    fn walls_and_immortal_cells_are_kept() {
+        let mut board = Board::new(10, 10);
+        board.set(1, 1, true);
+        board.set_state(5, 5, WALL);
+        board.set_state(7, 2, IMMORTAL);
+        let session = Session::new(&board, 5.0, 0);
+        assert_eq!((session.walls.clone(), session.immortals.clone()), (vec![(5, 5)], vec![(7, 2)]));
+        assert!(session.states.is_empty());
+        assert_eq!(parse(&session.to_json()).unwrap().board().cells(), board.cells());
+    }
+
+    #[test]
+    fn rejects_corrupt_and_foreign_sessions() {
+        let session = Session::new(&Board::new(10, 10), 5.0, 0);
+        assert!(matches!(parse("{\"version\": 1, \"width\": "), Err(SessionError::Json(_))));
+
+        let newer = session.to_json().replace("\"version\": 1", "\"version\": 2");
+        assert!(matches!(parse(&newer), Err(SessionError::Version(2))));
+        let huge = session.to_json().replace("\"width\": 10", "\"width\": 100000");
+        assert!(matches!(parse(&huge), Err(SessionError::Invalid(_))));
+        let bad_rule = session.to_json().replace("B3/S23", "B9/S23");
+        assert!(matches!(parse(&bad_rule), Err(SessionError::Json(_))));
+    }
+}
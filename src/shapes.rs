@@ -0,0 +1,160 @@
+// Shapes made of cells used to paint the board
+use crate::pattern_io::bounding_box;
+
+// Function returns all cells on a straight line between two cells
+// Both ends of the line are included
+pub fn line(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    // Bresenham's line algorithm
+    let (mut x, mut y) = from;
+    let dx = (to.0 - from.0).abs();
+    let dy = -(to.1 - from.1).abs();
+    let step_x = if from.0 < to.0 { 1 } else { -1 };
+    let step_y = if from.1 < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut cells = Vec::new();
+    loop {
+        cells.push((x, y));
+        if (x, y) == to {
+            break;
+        }
+        let double_error = 2 * error;
+        if double_error >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if double_error <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+    cells
+}
+
+// Function returns all cells of a square of the given size centered on the cell
+// Squares of even sizes have one more cell to the right of and below the center
+pub fn square(center: (i32, i32), size: i32) -> Vec<(i32, i32)> {
+    let start = (center.0 - (size - 1) / 2, center.1 - (size - 1) / 2);
+    rectangle(start, (start.0 + size - 1, start.1 + size - 1), true)
+}
+
+// Function returns all cells of a rectangle with the given opposite corners
+// Only cells on the edges are returned unless the rectangle is `filled`
+// Cells are sorted row by row
+pub fn rectangle(from: (i32, i32), to: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (left, right) = (from.0.min(to.0), from.0.max(to.0));
+    let (top, bottom) = (from.1.min(to.1), from.1.max(to.1));
+    let mut cells = Vec::new();
+    for y in top..=bottom {
+        for x in left..=right {
+            if filled || x == left || x == right || y == top || y == bottom {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+// Function moves the cells so that the leftmost one is in column 0 and the topmost one in row 0
+// Cells are sorted row by row
+pub fn normalized(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let (left, top, _, _) = bounding_box(cells);
+    let mut cells: Vec<(i32, i32)> = cells.iter().map(|(x, y)| (x - left, y - top)).collect();
+    cells.sort_by_key(|(x, y)| (*y, *x));
+    cells
+}
+
+// Function turns the cells by 90 degrees clockwise
+// The upper left corner of the rotated cells stays at (0, 0)
+pub fn rotated(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let (_, _, _, height) = bounding_box(cells);
+    normalized(&normalized(cells).iter().map(|(x, y)| (height - 1 - y, *x)).collect::<Vec<_>>())
+}
+
+// Function mirrors the cells from left to right
+pub fn flipped_horizontally(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let (_, _, width, _) = bounding_box(cells);
+    normalized(&normalized(cells).iter().map(|(x, y)| (width - 1 - x, *y)).collect::<Vec<_>>())
+}
+
+// Function mirrors the cells from top to bottom
+pub fn flipped_vertically(cells: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let (_, _, _, height) = bounding_box(cells);
+    normalized(&normalized(cells).iter().map(|(x, y)| (*x, height - 1 - y)).collect::<Vec<_>>())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn lines_have_no_gaps_or_duplicates() {
+        assert_eq!(line((2, 3), (5, 3)), vec![(2, 3), (3, 3), (4, 3), (5, 3)]);
+        assert_eq!(line((1, 4), (1, 1)), vec![(1, 4), (1, 3), (1, 2), (1, 1)]);
+        assert_eq!(line((0, 0), (3, 3)), vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+        assert_eq!(line((3, 0), (0, 3)), vec![(3, 0), (2, 1), (1, 2), (0, 3)]);
+        assert_eq!(line((2, 2), (2, 2)), vec![(2, 2)]);
+
+        // Every next cell of a steep line touches the previous one
+        let cells = line((0, 0), (3, 10));
+        assert_eq!(cells.len(), 11);
+        for pair in cells.windows(2) {
+            let (dx, dy) = (pair[1].0 - pair[0].0, pair[1].1 - pair[0].1);
+            assert!(dx.abs() <= 1 && dy == 1);
+        }
+    }
+
+    #[test]
+    fn rectangles_from_any_corners() {
+        let outline = vec![(1, 1), (2, 1), (3, 1), (1, 2), (3, 2), (1, 3), (2, 3), (3, 3)];
+        assert_eq!(rectangle((1, 1), (3, 3), false), outline);
+        assert_eq!(rectangle((3, 1), (1, 3), false), outline);
+        assert_eq!(rectangle((3, 3), (1, 1), true).len(), 9);
+        // Thin rectangles have no duplicate cells
+        assert_eq!(rectangle((0, 2), (3, 2), false), vec![(0, 2), (1, 2), (2, 2), (3, 2)]);
+        assert_eq!(rectangle((5, 5), (5, 5), false), vec![(5, 5)]);
+    }
+
+    #[test]
+    fn squares_around_the_center() {
+        assert_eq!(square((4, 4), 1), vec![(4, 4)]);
+        assert_eq!(square((4, 4), 2), vec![(4, 4), (5, 4), (4, 5), (5, 5)]);
+        assert_eq!(square((4, 4), 3), rectangle((3, 3), (5, 5), true));
+        assert_eq!(square((4, 4), 5), rectangle((2, 2), (6, 6), true));
+    }
+
+    #[test]
+    fn rotates_glider_clockwise() {
+        let glider = normalized(patterns::GLIDER.cells);
+        // The glider flying down and right turns to fly down and left
+        assert_eq!(rotated(&glider), vec![(0, 0), (0, 1), (2, 1), (0, 2), (1, 2)]);
+        // A horizontal blinker becomes vertical
+        assert_eq!(rotated(patterns::BLINKER.cells), vec![(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn transforms_compose_to_identity() {
+        for pattern in patterns::PRESETS.iter() {
+            let cells = normalized(pattern.cells);
+            let mut turned = cells.clone();
+            for _ in 0..4 {
+                turned = rotated(&turned);
+            }
+            assert_eq!(turned, cells, "{}", pattern.name);
+            assert_eq!(flipped_horizontally(&flipped_horizontally(&cells)), cells, "{}", pattern.name);
+            assert_eq!(flipped_vertically(&flipped_vertically(&cells)), cells, "{}", pattern.name);
+            // Both flips are the same as turning by 180 degrees
+            assert_eq!(flipped_vertically(&flipped_horizontally(&cells)), rotated(&rotated(&cells)), "{}", pattern.name);
+        }
+    }
+
+    #[test]
+    fn transforms_move_cells_to_the_corner() {
+        // Empty space above and to the left of copied cells is dropped
+        assert_eq!(normalized(&[(3, 2), (4, 2)]), vec![(0, 0), (1, 0)]);
+        assert_eq!(flipped_horizontally(&[(3, 2), (3, 3), (4, 3)]), vec![(1, 0), (0, 1), (1, 1)]);
+        assert!(rotated(&[]).is_empty());
+    }
+}
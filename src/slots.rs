@@ -0,0 +1,102 @@
+// Quick save slots kept as RLE files
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::board::Board;
+use crate::pattern_io::{self, rle, LoadError};
+
+// Number of save slots
+pub const SLOT_COUNT: usize = 5;
+
+// Path of the file of the slot (numbered from 1) in the directory
+pub fn path(dir: &Path, slot: usize) -> PathBuf {
+    dir.join(format!("slot_{}.rle", slot))
+}
+
+// Function checks which slots of the directory have a file
+pub fn occupied(dir: &Path) -> [bool; SLOT_COUNT] {
+    let mut occupied = [false; SLOT_COUNT];
+    for (i, slot) in occupied.iter_mut().enumerate() {
+        *slot = path(dir, i + 1).exists();
+    }
+    occupied
+}
+
+// Function writes all alive cells of the board to the slot
+// The position of the cells is kept in a `#R` line, so they are loaded to the same place
+pub fn save(board: &Board, dir: &Path, slot: usize) -> std::io::Result<()> {
+    let (x, y, _, _) = pattern_io::bounding_box(&board.alive_cells());
+    let text = format!("#R {} {}\n{}", x, y, pattern_io::board_rle(board));
+    fs::create_dir_all(dir)?;
+    fs::write(path(dir, slot), text)
+}
+
+// Function replaces all cells of the board with the cells of the slot
+// Returns `false` if the slot is empty
+// Slots without a position are placed in the center of the board
+pub fn load(board: &mut Board, dir: &Path, slot: usize) -> Result<bool, LoadError> {
+    let path = path(dir, slot);
+    if !path.exists() {
+        return Ok(false);
+    }
+    let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+    let pattern = rle::parse(&text).map_err(LoadError::Rle)?;
+    board.clear();
+    match position(&text) {
+        Some((x, y)) => board.stamp_states(x, y, &pattern.cells, &pattern.states),
+        None => pattern_io::stamp_centered(board, &pattern),
+    }
+    Ok(true)
+}
+
+// Function finds the position of the upper left corner of the pattern in a `#R x y` line
+fn position(text: &str) -> Option<(i32, i32)> {
+    let line = text.lines().find_map(|line| line.trim().strip_prefix("#R"))?;
+    let mut numbers = line.split_whitespace().map(|number| number.parse::<i32>());
+    match (numbers.next(), numbers.next()) {
+        (Some(Ok(x)), Some(Ok(y))) => Some((x, y)),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automaton::{Automaton, BLUE};
+    use crate::patterns;
+    use std::env;
+
+    #[test]
+    fn slots_keep_cells_in_place() {
+        let dir = env::temp_dir().join(format!("game_of_life_slots_{}", std::process::id()));
+        let mut board = Board::new(20, 20);
+        board.stamp(12, 3, patterns::GLIDER.cells);
+        save(&board, &dir, 2).unwrap();
+        assert_eq!(occupied(&dir), [false, true, false, false, false]);
+
+        let mut loaded = Board::new(20, 20);
+        loaded.stamp(0, 0, patterns::BLINKER.cells);
+        assert!(load(&mut loaded, &dir, 2).unwrap());
+        assert_eq!(loaded.alive_cells(), board.alive_cells());
+        // Empty slots leave the board as it is
+        assert!(!load(&mut loaded, &dir, 3).unwrap());
+        assert_eq!(loaded.population(), 5);
+
+        // Colors of Immigration cells are kept
+        board.set_automaton(Automaton::Immigration);
+        board.set_state(12, 3, BLUE);
+        save(&board, &dir, 4).unwrap();
+        loaded.set_automaton(Automaton::Immigration);
+        assert!(load(&mut loaded, &dir, 4).unwrap());
+        assert_eq!(loaded.alive_states(), board.alive_states());
+        assert_eq!(loaded.population_of(BLUE), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reads_position_lines() {
+        assert_eq!(position("#N Glider\n#R 4 -2\nx = 3, y = 3\n"), Some((4, -2)));
+        assert_eq!(position("x = 3, y = 3\n"), None);
+        assert_eq!(position("#R four\n"), None);
+    }
+}
@@ -0,0 +1,174 @@
+// Search of random soups that live the longest before they settle down
+use std::cmp::Reverse;
+use std::fmt;
+use std::io::{self, Write};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::board::Board;
+use crate::cli::Options;
+use crate::stability::PeriodFinder;
+
+// First row of a summary file
+pub const HEADER: &str = "seed,generations,population,ending,period";
+
+// Number and density of soups to try
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SoupSearch {
+    pub soups: u32,
+    // Chance of every cell to be alive at the start
+    pub density: f64,
+}
+
+// How a soup ended up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ending {
+    // All cells died
+    Empty,
+    // Cells stopped changing
+    Still,
+    // Cells repeat themselves with the period
+    Oscillating(usize),
+    // Cells were still changing after the last generation
+    Unsettled,
+}
+
+impl Ending {
+    // Period the soup repeats itself with (if it does)
+    pub fn period(self) -> Option<usize> {
+        match self {
+            Ending::Empty | Ending::Still => Some(1),
+            Ending::Oscillating(period) => Some(period),
+            Ending::Unsettled => None,
+        }
+    }
+}
+
+impl fmt::Display for Ending {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ending::Empty => write!(f, "empty"),
+            Ending::Still => write!(f, "still"),
+            Ending::Oscillating(_) => write!(f, "oscillating"),
+            Ending::Unsettled => write!(f, "unsettled"),
+        }
+    }
+}
+
+// Result of a single soup
+#[derive(Debug, PartialEq)]
+pub struct Soup {
+    // Seed the cells of the soup are filled with
+    pub seed: u64,
+    // Generation the final state of the soup started at (the cap if it didn't settle)
+    pub generations: u32,
+    // Number of alive cells after the last generation
+    pub population: usize,
+    pub ending: Ending,
+}
+
+// A line of the log like "Seed 42: settled at gen 310 with 25 alive (oscillating, period 2)"
+impl fmt::Display for Soup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.ending {
+            Ending::Unsettled => write!(f, "Seed {}: unsettled after gen {} with {} alive", self.seed, self.generations, self.population),
+            Ending::Oscillating(period) => write!(f, "Seed {}: settled at gen {} with {} alive (oscillating, period {})",
+                self.seed, self.generations, self.population, period),
+            ending => write!(f, "Seed {}: settled at gen {} with {} alive ({})", self.seed, self.generations, self.population, ending),
+        }
+    }
+}
+
+// Function fills the board with a soup of the seed and runs it until it repeats itself
+// or `cap` generations are made
+// A soup settles at the first generation of its final cycle
+pub fn run_soup(board: &mut Board, seed: u64, density: f64, cap: u32) -> Soup {
+    let mut rng = StdRng::seed_from_u64(seed);
+    board.randomize(density, &mut rng);
+    let mut periods = PeriodFinder::new();
+    for generation in 1..=cap {
+        let before = board.cells().to_vec();
+        board.step();
+        if let Some(period) = periods.check(&before, board.cells()) {
+            let population = board.population();
+            let ending = match (population, period) {
+                (0, _) => Ending::Empty,
+                (_, 1) => Ending::Still,
+                (_, period) => Ending::Oscillating(period),
+            };
+            return Soup{seed, generations: generation - period as u32, population, ending};
+        }
+    }
+    Soup{seed, generations: cap, population: board.population(), ending: Ending::Unsettled}
+}
+
+// Function runs soups of seeds going up from `first_seed` on boards set by the options
+// `found` is called with every soup as soon as it's done
+// Returns the soups sorted by longevity, the longest-lived first
+pub fn search<F: FnMut(&Soup)>(options: &Options, search: SoupSearch, first_seed: u64, mut found: F) -> Vec<Soup> {
+    let mut board = Board::new(options.cols, options.rows);
+    board.set_wrap(options.wrap);
+    board.set_rule(options.rule);
+    board.set_automaton(options.automaton);
+    board.set_neighbourhood(options.neighbourhood);
+    board.set_engine(options.engine);
+    let mut soups: Vec<Soup> = (0..search.soups).map(|index| {
+        let soup = run_soup(&mut board, first_seed.wrapping_add(index as u64), search.density, options.stable_cap);
+        found(&soup);
+        soup
+    }).collect();
+    // Soups of the same longevity stay in the order of their seeds
+    soups.sort_by_key(|soup| Reverse(soup.generations));
+    soups
+}
+
+// Function writes the soups as CSV, one row per soup
+pub fn write_summary<W: Write>(mut out: W, soups: &[Soup]) -> io::Result<()> {
+    writeln!(out, "{}", HEADER)?;
+    for soup in soups {
+        let period = soup.ending.period().map_or(String::new(), |period| period.to_string());
+        writeln!(out, "{},{},{},{},{}", soup.seed, soup.generations, soup.population, soup.ending, period)?;
+    }
+    out.flush()
+}
+
+// Function returns the arguments that open the soup of the seed in the game
+pub fn game_args(options: &Options, density: f64, seed: u64) -> String {
+    let mut args = format!("--cols {} --rows {}", options.cols, options.rows);
+    if options.wrap {
+        args.push_str(" --wrap");
+    }
+    args.push_str(&format!(" --rule {} --automaton {} --neighbours {}", options.rule, options.automaton, options.neighbourhood));
+    args.push_str(&format!(" --density {} --seed {}", density, seed));
+    args
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soups_of_the_same_seed_end_the_same() {
+        let options = Options::default();
+        let search = SoupSearch{soups: 5, density: 0.35};
+        let mut logged = Vec::new();
+        let soups = super::search(&options, search, 7, |soup| logged.push(soup.seed));
+        assert_eq!(logged, vec![7, 8, 9, 10, 11]);
+        assert!(soups.windows(2).all(|pair| pair[0].generations >= pair[1].generations));
+
+        let mut board = Board::new(options.cols, options.rows);
+        assert_eq!(run_soup(&mut board, soups[0].seed, 0.35, options.stable_cap), soups[0]);
+    }
+
+    #[test]
+    fn full_board_dies_out() {
+        // Only the corners survive the first generation, and they die alone in the second one
+        let mut board = Board::new(20, 20);
+        let soup = run_soup(&mut board, 1, 1.0, 100);
+        assert_eq!(soup, Soup{seed: 1, generations: 2, population: 0, ending: Ending::Empty});
+
+        let mut csv = Vec::new();
+        write_summary(&mut csv, &[soup]).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "seed,generations,population,ending,period\n1,2,0,empty,1\n");
+    }
+}
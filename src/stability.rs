@@ -0,0 +1,216 @@
+// Detection of boards that don't change anymore or repeat themselves
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::board::Board;
+use crate::history;
+
+// Maximal period of oscillators that can be found
+pub const PERIOD_WINDOW: usize = 64;
+
+// How the board ended up after a generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    // All cells are dead
+    Extinct,
+    // The generation didn't change any cell
+    Stabilized,
+    // The board is the same as two generations ago
+    PeriodTwo,
+}
+
+// Compares every generation with the previous ones
+pub struct Detector {
+    // Also look for oscillators with period 2 (like a blinker)
+    pub period_two: bool,
+    // Cells before and after the previous generation (empty before the first one)
+    // The buffers are reused by every generation, so checks allocate nothing
+    earlier: Vec<u8>,
+    previous: Vec<u8>,
+}
+
+impl Detector {
+    // Constructor for a detector that has seen no generations yet
+    pub fn new(period_two: bool) -> Detector {
+        Detector{period_two, earlier: Vec::new(), previous: Vec::new()}
+    }
+
+    // Function checks the board after a generation
+    // `before` are the cells of the board right before the generation
+    // Cells edited between generations don't give a false period 2
+    pub fn check(&mut self, before: &[u8], after: &Board) -> Option<Outcome> {
+        let outcome = if after.population() == 0 {
+            Some(Outcome::Extinct)
+        } else if after.cells() == before {
+            Some(Outcome::Stabilized)
+        } else {
+            match self.period_two && !self.previous.is_empty() && self.previous == before && self.earlier == after.cells() {
+                true => Some(Outcome::PeriodTwo),
+                false => None,
+            }
+        };
+        history::copy_into(&mut self.earlier, before);
+        history::copy_into(&mut self.previous, after.cells());
+        outcome
+    }
+}
+
+
+// Finds periods of oscillators by hashes of the latest generations
+pub struct PeriodFinder {
+    // Hashes of cells of the latest generations, the newest is the last one
+    hashes: VecDeque<u64>,
+}
+
+impl PeriodFinder {
+    // Constructor for a finder that has seen no generations yet
+    pub fn new() -> PeriodFinder {
+        PeriodFinder{hashes: VecDeque::with_capacity(PERIOD_WINDOW + 1)}
+    }
+
+    // Function checks the cells after a generation
+    // `before` are the cells right before the generation
+    // Returns the number of generations since the cells were the same the last time
+    // If the cells were edited between generations the older hashes are forgotten
+    pub fn check(&mut self, before: &[u8], after: &[u8]) -> Option<usize> {
+        let before = hash(before);
+        if self.hashes.back() != Some(&before) {
+            self.clear();
+            self.hashes.push_back(before);
+        }
+        let after = hash(after);
+        let period = self.hashes.iter().rev().position(|seen| *seen == after).map(|index| index + 1);
+        if self.hashes.len() > PERIOD_WINDOW {
+            self.hashes.pop_front();
+        }
+        self.hashes.push_back(after);
+        period
+    }
+
+    // Function forgets all generations
+    pub fn clear(&mut self) {
+        self.hashes.clear();
+    }
+}
+
+impl Default for PeriodFinder {
+    fn default() -> PeriodFinder {
+        PeriodFinder::new()
+    }
+}
+
+// Function hashes states of cells
+// 64 bits of SipHash make a false match of two generations practically impossible
+fn hash(cells: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    cells.hash(&mut hasher);
+    hasher.finish()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    // Function applies rules once and checks the result
+    fn step(board: &mut Board, detector: &mut Detector) -> Option<Outcome> {
+        let before = board.cells().to_vec();
+        board.step();
+        detector.check(&before, board)
+    }
+
+    #[test]
+    fn block_stabilizes_at_first_generation() {
+        let mut board = Board::new(6, 6);
+        board.stamp(2, 2, &[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        let mut detector = Detector::new(false);
+        assert_eq!(step(&mut board, &mut detector), Some(Outcome::Stabilized));
+    }
+
+    #[test]
+    fn single_cell_dies_out() {
+        let mut board = Board::new(6, 6);
+        board.set(3, 3, true);
+        let mut detector = Detector::new(false);
+        assert_eq!(step(&mut board, &mut detector), Some(Outcome::Extinct));
+    }
+
+    #[test]
+    fn blinker_has_period_two_only_if_enabled() {
+        let blinker = [(0, 0), (1, 0), (2, 0)];
+        for period_two in [false, true] {
+            let mut board = Board::new(5, 5);
+            board.stamp(1, 2, &blinker);
+            let mut detector = Detector::new(period_two);
+            assert_eq!(step(&mut board, &mut detector), None);
+            let expected = if period_two { Some(Outcome::PeriodTwo) } else { None };
+            assert_eq!(step(&mut board, &mut detector), expected);
+        }
+    }
+
+    #[test]
+    fn edits_between_generations_are_not_a_period() {
+        let mut board = Board::new(5, 5);
+        board.stamp(1, 2, &[(0, 0), (1, 0), (2, 0)]);
+        let mut detector = Detector::new(true);
+        step(&mut board, &mut detector);
+        board.set(0, 0, true);
+        assert_eq!(step(&mut board, &mut detector), None);
+    }
+
+    // Function runs the board until a period is found
+    fn find_period(board: &mut Board) -> Option<usize> {
+        let mut finder = PeriodFinder::new();
+        for _ in 0..PERIOD_WINDOW {
+            let before = board.cells().to_vec();
+            board.step();
+            if let Some(period) = finder.check(&before, board.cells()) {
+                return Some(period);
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn finds_periods_of_oscillators() {
+        let mut board = Board::new(17, 17);
+        board.stamp(2, 2, patterns::PULSAR.cells);
+        assert_eq!(find_period(&mut board), Some(3));
+
+        // Pentadecathlon as 10 cells in a row that evolve into it
+        let mut board = Board::new(11, 18);
+        let row: Vec<(i32, i32)> = (0..10).map(|y| (0, y)).collect();
+        board.stamp(5, 4, &row);
+        assert_eq!(find_period(&mut board), Some(15));
+
+        let mut board = Board::new(6, 6);
+        board.stamp(2, 2, &[(0, 0), (1, 0), (0, 1), (1, 1)]);
+        assert_eq!(find_period(&mut board), Some(1));
+    }
+
+    #[test]
+    fn glider_has_no_period() {
+        let mut board = Board::new(40, 40);
+        board.stamp(1, 1, patterns::GLIDER.cells);
+        assert_eq!(find_period(&mut board), None);
+    }
+
+    #[test]
+    fn edits_forget_older_generations() {
+        let blinker = [(0, 0), (1, 0), (2, 0)];
+        let mut board = Board::new(5, 5);
+        board.stamp(1, 2, &blinker);
+        let mut finder = PeriodFinder::new();
+        let before = board.cells().to_vec();
+        board.step();
+        finder.check(&before, board.cells());
+
+        // Going back to the first phase by hand isn't a generation
+        let mut board = Board::new(5, 5);
+        board.stamp(1, 2, &blinker);
+        let before = board.cells().to_vec();
+        board.step();
+        assert_eq!(finder.check(&before, board.cells()), None);
+    }
+}
@@ -0,0 +1,69 @@
+// Population statistics written as CSV, one row per generation
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use crate::board::Board;
+
+// First row of a new file
+pub const HEADER: &str = "generation,population,births,deaths";
+
+// Writer of rows of statistics
+pub struct StatsLog<W: Write> {
+    out: W,
+}
+
+impl<W: Write> StatsLog<W> {
+    // Constructor for a log writing to `out`
+    // The header is written first if `header` is set
+    pub fn new(mut out: W, header: bool) -> io::Result<StatsLog<W>> {
+        if header {
+            writeln!(out, "{}", HEADER)?;
+        }
+        Ok(StatsLog{out})
+    }
+
+    // Function writes a row of the board after the generation
+    pub fn record(&mut self, generation: u32, board: &Board) -> io::Result<()> {
+        writeln!(self.out, "{},{},{},{}", generation, board.population(), board.births(), board.deaths())
+    }
+
+    // Function writes the buffered rows
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    // Function returns what was written to
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl StatsLog<BufWriter<File>> {
+    // Constructor for a log appending rows to the file
+    // The header is only written to a new or empty file
+    pub fn append<P: AsRef<Path>>(path: P) -> io::Result<StatsLog<BufWriter<File>>> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let header = file.metadata()?.len() == 0;
+        StatsLog::new(BufWriter::new(file), header)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns;
+
+    #[test]
+    fn writes_row_for_every_generation_of_blinker() {
+        let mut board = Board::new(10, 10);
+        board.stamp(4, 4, patterns::BLINKER.cells);
+        let mut log = StatsLog::new(Vec::new(), true).unwrap();
+        for generation in 1..=4 {
+            board.step();
+            log.record(generation, &board).unwrap();
+        }
+        let csv = String::from_utf8(log.into_inner()).unwrap();
+        assert_eq!(csv, "generation,population,births,deaths\n1,3,2,2\n2,3,2,2\n3,3,2,2\n4,3,2,2\n");
+    }
+}
@@ -0,0 +1,76 @@
+// Symmetry of painting mirrored across the center axes of the board
+use std::fmt;
+
+// Axes cells are mirrored across while painting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    Off,
+    // Cells are mirrored from the left half to the right one and back
+    Horizontal,
+    // Cells are mirrored from the upper half to the lower one and back
+    Vertical,
+    // Cells are mirrored across both axes
+    FourFold,
+}
+
+impl Symmetry {
+    // Function returns the mode that follows this one when they are cycled
+    pub fn next(self) -> Symmetry {
+        match self {
+            Symmetry::Off => Symmetry::Horizontal,
+            Symmetry::Horizontal => Symmetry::Vertical,
+            Symmetry::Vertical => Symmetry::FourFold,
+            Symmetry::FourFold => Symmetry::Off,
+        }
+    }
+
+    // Function returns the cell and its mirrored copies on a board of the given size
+    // Cells on an axis are their own copies, so every cell is returned once
+    pub fn mirrored(self, x: i32, y: i32, width: i32, height: i32) -> Vec<(i32, i32)> {
+        let (mirror_x, mirror_y) = (width - 1 - x, height - 1 - y);
+        let mut cells = match self {
+            Symmetry::Off => vec![(x, y)],
+            Symmetry::Horizontal => vec![(x, y), (mirror_x, y)],
+            Symmetry::Vertical => vec![(x, y), (x, mirror_y)],
+            Symmetry::FourFold => vec![(x, y), (mirror_x, y), (x, mirror_y), (mirror_x, mirror_y)],
+        };
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Symmetry::Off => "off",
+            Symmetry::Horizontal => "horizontal",
+            Symmetry::Vertical => "vertical",
+            Symmetry::FourFold => "4-fold",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirrors_across_center_of_non_square_board() {
+        assert_eq!(Symmetry::Off.mirrored(1, 2, 10, 6), vec![(1, 2)]);
+        assert_eq!(Symmetry::Horizontal.mirrored(1, 2, 10, 6), vec![(1, 2), (8, 2)]);
+        assert_eq!(Symmetry::Vertical.mirrored(1, 2, 10, 6), vec![(1, 2), (1, 3)]);
+        assert_eq!(Symmetry::FourFold.mirrored(1, 2, 10, 6), vec![(1, 2), (1, 3), (8, 2), (8, 3)]);
+    }
+
+    #[test]
+    fn cells_on_axes_are_not_repeated() {
+        // The middle column of a board with an odd width is its own mirror
+        assert_eq!(Symmetry::Horizontal.mirrored(4, 1, 9, 6), vec![(4, 1)]);
+        assert_eq!(Symmetry::FourFold.mirrored(4, 1, 9, 6), vec![(4, 1), (4, 4)]);
+        assert_eq!(Symmetry::FourFold.mirrored(2, 2, 5, 5), vec![(2, 2)]);
+        assert_eq!(Symmetry::Off.next().next().next().next(), Symmetry::Off);
+    }
+}
@@ -0,0 +1,178 @@
+// Color themes of the game
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// A color with components from 0 to 1
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ThemeColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl ThemeColor {
+    // Constructor for an opaque color
+    pub const fn rgb(r: f32, g: f32, b: f32) -> ThemeColor {
+        ThemeColor{r, g, b, a: 1.0}
+    }
+
+    // Constructor for a translucent color
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> ThemeColor {
+        ThemeColor{r, g, b, a}
+    }
+}
+
+// Colors are written like "#33aa00" or "#33aa0080" (with opacity)
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<ThemeColor, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(&hex);
+        if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+            return Err(format!("Bad color {}, expected #rrggbb or #rrggbbaa", hex));
+        }
+        let component = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16)
+            .map(|value| value as f32 / 255.0)
+            .map_err(|_| format!("Bad color {}, expected #rrggbb or #rrggbbaa", hex));
+        let a = match digits.len() {
+            8 => component(6)?,
+            _ => 1.0,
+        };
+        Ok(ThemeColor{r: component(0)?, g: component(2)?, b: component(4)?, a})
+    }
+}
+
+// Named colors everything on the screen is drawn with
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Theme {
+    // Name shown when the theme is selected
+    #[serde(default = "custom_name")]
+    pub name: String,
+    pub background: ThemeColor,
+    pub grid: ThemeColor,
+    pub cell_alive: ThemeColor,
+    // Color drawn over the cell under the cursor
+    pub cell_highlight: ThemeColor,
+    pub text: ThemeColor,
+}
+
+fn custom_name() -> String {
+    String::from("custom")
+}
+
+impl Theme {
+    // Gray field with a red grid and green cells
+    pub fn classic() -> Theme {
+        Theme{
+            name: String::from("classic"),
+            background: ThemeColor::rgb(0.2, 0.2, 0.2),
+            grid: ThemeColor::rgb(1.0, 0.0, 0.0),
+            cell_alive: ThemeColor::rgb(0.0, 1.0, 0.0),
+            cell_highlight: ThemeColor::rgba(1.0, 1.0, 1.0, 0.3),
+            text: ThemeColor::rgb(0.643, 0.258, 0.862),
+        }
+    }
+
+    // Almost black field with a faint grid and white cells
+    pub fn dark() -> Theme {
+        Theme{
+            name: String::from("dark"),
+            background: ThemeColor::rgb(0.05, 0.05, 0.08),
+            grid: ThemeColor::rgb(0.2, 0.2, 0.25),
+            cell_alive: ThemeColor::rgb(0.9, 0.9, 0.9),
+            cell_highlight: ThemeColor::rgba(1.0, 1.0, 1.0, 0.25),
+            text: ThemeColor::rgb(0.8, 0.8, 0.8),
+        }
+    }
+
+    // White field with a light grid and black cells
+    pub fn light() -> Theme {
+        Theme{
+            name: String::from("light"),
+            background: ThemeColor::rgb(0.95, 0.95, 0.95),
+            grid: ThemeColor::rgb(0.75, 0.75, 0.75),
+            cell_alive: ThemeColor::rgb(0.1, 0.1, 0.1),
+            cell_highlight: ThemeColor::rgba(0.0, 0.0, 0.0, 0.2),
+            text: ThemeColor::rgb(0.1, 0.1, 0.1),
+        }
+    }
+
+    // Themes that can be selected in the game
+    pub fn presets() -> Vec<Theme> {
+        vec![Theme::classic(), Theme::dark(), Theme::light()]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::classic()
+    }
+}
+
+// Errors that can occur while loading a theme file
+#[derive(Debug)]
+pub enum ThemeError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ThemeError::Io(e) => write!(f, "{}", e),
+            ThemeError::Toml(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+// Function reads a theme from the text of a TOML file
+pub fn parse(text: &str) -> Result<Theme, ThemeError> {
+    toml::from_str(text).map_err(ThemeError::Toml)
+}
+
+// Function reads a theme from the TOML file
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme, ThemeError> {
+    let text = fs::read_to_string(path).map_err(ThemeError::Io)?;
+    parse(&text)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_theme_with_hex_colors() {
+        let text = "\
+name = \"sepia\"
+background = \"#704214\"
+grid = \"#000000\"
+cell_alive = \"#FFFFFF\"
+cell_highlight = \"#ffffff80\"
+text = \"#ff0000\"
+";
+        let theme = parse(text).unwrap();
+        assert_eq!(theme.name, "sepia");
+        assert_eq!(theme.grid, ThemeColor::rgb(0.0, 0.0, 0.0));
+        assert_eq!(theme.cell_alive, ThemeColor::rgb(1.0, 1.0, 1.0));
+        assert_eq!(theme.cell_highlight.a, 128.0 / 255.0);
+        assert_eq!(theme.text, ThemeColor::rgb(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn rejects_bad_themes() {
+        // Missing colors
+        assert!(parse("background = \"#000000\"").is_err());
+        // Colors that are not hex numbers
+        let text = "background = \"black\"\ngrid = \"#000\"\ncell_alive = \"#00ff00\"\ncell_highlight = \"#ffffff\"\ntext = \"#ffffff\"";
+        assert!(parse(text).is_err());
+        assert!(ThemeColor::try_from(String::from("#00gg00")).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+// History of manual edits of the board that can be undone and redone
+use std::collections::VecDeque;
+use crate::board::Board;
+
+// A change of the state of a single cell
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Change {
+    pub x: i32,
+    pub y: i32,
+    // State of the cell before and after the change
+    pub before: u8,
+    pub after: u8,
+}
+
+// A single edit (like a stroke or a stamp) is a list of changes
+pub type Edit = Vec<Change>;
+
+// Function makes the cell on the board alive or dead
+// Returns the change or `None` if the cell already has this status or is outside of the board
+pub fn paint(board: &mut Board, x: i32, y: i32, alive: bool) -> Option<Change> {
+    paint_state(board, x, y, u8::from(alive))
+}
+
+// Function changes the state of the cell on the board
+// Returns the change or `None` if the cell already has this state or is outside of the board
+pub fn paint_state(board: &mut Board, x: i32, y: i32, state: u8) -> Option<Change> {
+    let before = board.state(x, y);
+    if !board.contains(x, y) || before == state {
+        return None;
+    }
+    board.set_state(x, y, state);
+    Some(Change{x, y, before, after: state})
+}
+
+// Stacks of edits to undo and to redo
+pub struct UndoStack {
+    // The oldest edits are dropped first when there are too many of them
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    // Maximal number of edits that can be undone
+    capacity: usize,
+}
+
+impl UndoStack {
+    // Constructor for an empty history
+    pub fn new(capacity: usize) -> UndoStack {
+        UndoStack{undo: VecDeque::new(), redo: Vec::new(), capacity}
+    }
+
+    // Function remembers a new edit
+    // Edits that were undone can't be redone after that
+    pub fn push(&mut self, edit: Edit) {
+        if edit.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push_back(edit);
+        if self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    // Function reverts the last edit on the board
+    // Returns `false` if there is nothing to undo
+    pub fn undo(&mut self, board: &mut Board) -> bool {
+        match self.undo.pop_back() {
+            Some(edit) => {
+                for change in edit.iter().rev() {
+                    board.set_state(change.x, change.y, change.before);
+                }
+                self.redo.push(edit);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Function applies the last undone edit to the board again
+    // Returns `false` if there is nothing to redo
+    pub fn redo(&mut self, board: &mut Board) -> bool {
+        match self.redo.pop() {
+            Some(edit) => {
+                for change in edit.iter() {
+                    board.set_state(change.x, change.y, change.after);
+                }
+                self.undo.push_back(edit);
+                true
+            },
+            None => false,
+        }
+    }
+
+    // Function forgets all edits
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revive(x: i32, y: i32) -> Edit {
+        vec![Change{x, y, before: 0, after: 1}]
+    }
+
+    #[test]
+    fn undo_and_redo_edits() {
+        let mut board = Board::new(10, 10);
+        let mut stack = UndoStack::new(10);
+        board.set(1, 1, true);
+        stack.push(revive(1, 1));
+        board.set(2, 2, true);
+        stack.push(revive(2, 2));
+
+        assert!(stack.undo(&mut board));
+        assert_eq!(board.alive_cells(), vec![(1, 1)]);
+        assert!(stack.undo(&mut board));
+        assert!(!stack.undo(&mut board));
+        assert_eq!(board.population(), 0);
+
+        assert!(stack.redo(&mut board));
+        assert_eq!(board.alive_cells(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn oldest_edits_are_dropped() {
+        let mut board = Board::new(10, 10);
+        let mut stack = UndoStack::new(2);
+        for x in 0..3 {
+            board.set(x, 0, true);
+            stack.push(revive(x, 0));
+        }
+
+        assert!(stack.undo(&mut board));
+        assert!(stack.undo(&mut board));
+        assert!(!stack.undo(&mut board));
+        assert_eq!(board.alive_cells(), vec![(0, 0)]);
+    }
+}
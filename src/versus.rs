@@ -0,0 +1,52 @@
+// Versus mode of automata with two colors (Immigration): two players seed cells of their colors,
+// and a color wins when the cells of the other one die out
+use std::fmt;
+
+// End of a game of two colors
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Victory {
+    Red,
+    Blue,
+    // Both colors died out in the same generation
+    Draw,
+}
+
+impl fmt::Display for Victory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Victory::Red => write!(f, "Red wins"),
+            Victory::Blue => write!(f, "Blue wins"),
+            Victory::Draw => write!(f, "Both colors died out"),
+        }
+    }
+}
+
+// Function checks if the generation ended the game by the numbers of red and blue cells before and after it
+// Only both colors being on the field can end the game, so a field seeded with a single color runs as usual
+pub fn victory(before: (usize, usize), after: (usize, usize)) -> Option<Victory> {
+    match (before, after) {
+        ((0, _), _) | ((_, 0), _) => None,
+        (_, (0, 0)) => Some(Victory::Draw),
+        (_, (_, 0)) => Some(Victory::Red),
+        (_, (0, _)) => Some(Victory::Blue),
+        _ => None,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_wins_when_the_other_dies_out() {
+        assert_eq!(victory((3, 2), (4, 0)), Some(Victory::Red));
+        assert_eq!(victory((3, 2), (0, 1)), Some(Victory::Blue));
+        assert_eq!(victory((1, 1), (0, 0)), Some(Victory::Draw));
+        assert_eq!(victory((3, 2), (2, 2)), None);
+        // A single color dying out or going on alone isn't a victory
+        assert_eq!(victory((3, 0), (0, 0)), None);
+        assert_eq!(victory((0, 2), (0, 5)), None);
+        assert_eq!(Victory::Blue.to_string(), "Blue wins");
+    }
+}
@@ -0,0 +1,213 @@
+// Conversion between positions on the screen and cells of the board
+
+// Limits of the scale the field is drawn with
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 8.0;
+
+// Share of the width or the height of the area a fitted rectangle takes
+pub const FIT_SHARE: f32 = 0.8;
+
+// Scale and offset the field is drawn with
+// A point of the field is drawn at `point * scale + offset` on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub scale: f32,
+    pub offset: (f32, f32),
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform{scale: 1.0, offset: (0.0, 0.0)}
+    }
+}
+
+impl Transform {
+    // Constructor for a transform that draws the rectangle of the field (x, y, width, height)
+    // in the middle of an area of the given size, taking `FIT_SHARE` of it along the tighter side
+    // The scale stays between `MIN_ZOOM` and `MAX_ZOOM`, so small rectangles are drawn smaller
+    pub fn fitted(rect: (f32, f32, f32, f32), area: (f32, f32)) -> Transform {
+        let (x, y, width, height) = rect;
+        let scale = (area.0 * FIT_SHARE / width).min(area.1 * FIT_SHARE / height).clamp(MIN_ZOOM, MAX_ZOOM);
+        let center = (x + width / 2.0, y + height / 2.0);
+        Transform{scale, offset: (area.0 / 2.0 - center.0 * scale, area.1 / 2.0 - center.1 * scale)}
+    }
+
+    // Function finds the point of the field drawn at the point of the screen
+    pub fn to_field(&self, point: (f32, f32)) -> (f32, f32) {
+        ((point.0 - self.offset.0) / self.scale, (point.1 - self.offset.1) / self.scale)
+    }
+
+    // Function finds the point of the screen the point of the field is drawn at
+    pub fn to_screen(&self, point: (f32, f32)) -> (f32, f32) {
+        (point.0 * self.scale + self.offset.0, point.1 * self.scale + self.offset.1)
+    }
+
+    // Function moves the drawn field by the given distance on the screen
+    pub fn pan(&mut self, dx: f32, dy: f32) {
+        self.offset = (self.offset.0 + dx, self.offset.1 + dy);
+    }
+
+    // Function multiplies the scale by `factor` keeping the point of the field under `center` in place
+    // The scale stays between `MIN_ZOOM` and `MAX_ZOOM`
+    pub fn zoom(&mut self, factor: f32, center: (f32, f32)) {
+        let anchor = self.to_field(center);
+        self.scale = (self.scale * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.offset = (center.0 - anchor.0 * self.scale, center.1 - anchor.1 * self.scale);
+    }
+}
+
+// Function finds the cell under the point of the grid
+// The grid starts at (0, 0) and has `cols` x `rows` square cells
+// A point exactly on a grid line belongs to the cell to the left of (or above) the line
+// Returns the column and the row of the cell or `None` if the point is outside of the grid
+pub fn point_to_cell(point: (f32, f32), cell_size: f32, cols: i32, rows: i32) -> Option<(i32, i32)> {
+    let col = axis_to_cell(point.0, cell_size, cols)?;
+    let row = axis_to_cell(point.1, cell_size, rows)?;
+    Some((col, row))
+}
+
+// Function finds the cell of the grid nearest to the point
+// Points outside of the grid give cells on its edges
+pub fn clamped_point_to_cell(point: (f32, f32), cell_size: f32, cols: i32, rows: i32) -> (i32, i32) {
+    let x = point.0.clamp(0.0, cell_size * cols as f32);
+    let y = point.1.clamp(0.0, cell_size * rows as f32);
+    // Clamped points are always on the grid
+    point_to_cell((x, y), cell_size, cols, rows).unwrap_or((0, 0))
+}
+
+// Function finds the length of a side of square cells for `cols` x `rows` cells to fit the area
+pub fn cell_size(cols: i32, rows: i32, width: f32, height: f32) -> f32 {
+    (width / cols as f32).min(height / rows as f32)
+}
+
+// Function finds the size of the field in a window of the given size with a panel of `panel_width` to the right
+// The field is never smaller than a pixel, so cells still have a size in a tiny window
+pub fn field_size(window: (f32, f32), panel_width: f32) -> (f32, f32) {
+    ((window.0 - panel_width).max(1.0), window.1.max(1.0))
+}
+
+// Function finds the number of the cell along a single axis
+fn axis_to_cell(coord: f32, cell_size: f32, cells: i32) -> Option<i32> {
+    if coord < 0.0 || coord > cell_size * cells as f32 {
+        return None;
+    }
+    Some(((coord / cell_size).ceil() as i32 - 1).max(0))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_cells_inside_of_the_grid() {
+        assert_eq!(point_to_cell((16.0, 16.0), 32.0, 20, 10), Some((0, 0)));
+        assert_eq!(point_to_cell((50.0, 100.0), 32.0, 20, 10), Some((1, 3)));
+        assert_eq!(point_to_cell((639.0, 319.0), 32.0, 20, 10), Some((19, 9)));
+    }
+
+    #[test]
+    fn finds_cells_at_corners_and_edges() {
+        // Corners of the grid
+        assert_eq!(point_to_cell((0.0, 0.0), 32.0, 20, 10), Some((0, 0)));
+        assert_eq!(point_to_cell((640.0, 0.0), 32.0, 20, 10), Some((19, 0)));
+        assert_eq!(point_to_cell((0.0, 320.0), 32.0, 20, 10), Some((0, 9)));
+        assert_eq!(point_to_cell((640.0, 320.0), 32.0, 20, 10), Some((19, 9)));
+
+        // Grid lines between cells pick the left and the upper cell
+        assert_eq!(point_to_cell((32.0, 10.0), 32.0, 20, 10), Some((0, 0)));
+        assert_eq!(point_to_cell((10.0, 64.0), 32.0, 20, 10), Some((0, 1)));
+        assert_eq!(point_to_cell((32.0, 64.0), 32.0, 20, 10), Some((0, 1)));
+    }
+
+    #[test]
+    fn clamps_points_to_the_edges() {
+        assert_eq!(clamped_point_to_cell((50.0, 100.0), 32.0, 20, 10), (1, 3));
+        assert_eq!(clamped_point_to_cell((-100.0, 100.0), 32.0, 20, 10), (0, 3));
+        assert_eq!(clamped_point_to_cell((700.0, -5.0), 32.0, 20, 10), (19, 0));
+        assert_eq!(clamped_point_to_cell((1000.0, 1000.0), 32.0, 20, 10), (19, 9));
+    }
+
+    #[test]
+    fn fits_wide_and_tall_grids() {
+        // A wide grid takes the whole width and half of the height
+        assert_eq!(cell_size(40, 20, 640.0, 640.0), 16.0);
+        assert_eq!(cell_size(20, 40, 640.0, 640.0), 16.0);
+        assert_eq!(cell_size(20, 20, 640.0, 640.0), 32.0);
+        // Cells of the last column and row of a wide grid
+        assert_eq!(point_to_cell((639.0, 319.0), 16.0, 40, 20), Some((39, 19)));
+        assert_eq!(point_to_cell((639.0, 321.0), 16.0, 40, 20), None);
+    }
+
+    #[test]
+    fn refits_cells_to_resized_window() {
+        let field = field_size((840.0, 640.0), 200.0);
+        assert_eq!(field, (640.0, 640.0));
+        assert_eq!(cell_size(20, 10, field.0, field.1), 32.0);
+
+        // A smaller window shrinks the cells, but points keep pointing at the same cells
+        let field = field_size((520.0, 400.0), 200.0);
+        let size = cell_size(20, 10, field.0, field.1);
+        assert_eq!(size, 16.0);
+        assert_eq!(point_to_cell((50.0, 100.0), 32.0, 20, 10), point_to_cell((25.0, 50.0), size, 20, 10));
+        assert_eq!(point_to_cell((319.0, 159.0), size, 20, 10), Some((19, 9)));
+
+        // The field of a window narrower than the panel still has a size
+        assert_eq!(field_size((150.0, 0.0), 200.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn zooms_around_the_center() {
+        let mut transform = Transform::default();
+        transform.zoom(2.0, (100.0, 50.0));
+        assert_eq!(transform.scale, 2.0);
+        // The point under the center stays there
+        assert_eq!(transform.to_screen((100.0, 50.0)), (100.0, 50.0));
+        assert_eq!(transform.to_field((120.0, 50.0)), (110.0, 50.0));
+        assert_eq!(transform.to_field(transform.to_screen((7.0, 3.0))), (7.0, 3.0));
+    }
+
+    #[test]
+    fn pans_the_scaled_field() {
+        let mut transform = Transform::default();
+        transform.zoom(2.0, (0.0, 0.0));
+        transform.pan(-40.0, 10.0);
+        assert_eq!(transform.to_field((0.0, 10.0)), (20.0, 0.0));
+        assert_eq!(transform.to_screen((20.0, 0.0)), (0.0, 10.0));
+    }
+
+    #[test]
+    fn limits_zoom() {
+        let mut transform = Transform::default();
+        transform.zoom(100.0, (0.0, 0.0));
+        assert_eq!(transform.scale, MAX_ZOOM);
+        transform.zoom(0.001, (10.0, 10.0));
+        assert_eq!(transform.scale, MIN_ZOOM);
+        assert_eq!(transform.to_screen(transform.to_field((10.0, 10.0))), (10.0, 10.0));
+    }
+
+    #[test]
+    fn fits_rectangle_in_the_middle() {
+        // A wide rectangle takes 80% of the width
+        let transform = Transform::fitted((100.0, 50.0, 100.0, 20.0), (500.0, 400.0));
+        assert_eq!(transform.scale, 4.0);
+        assert_eq!(transform.to_screen((150.0, 60.0)), (250.0, 200.0));
+        assert_eq!(transform.to_screen((100.0, 50.0)), (50.0, 160.0));
+
+        // A single cell is zoomed in as much as allowed
+        let transform = Transform::fitted((32.0, 32.0, 32.0, 32.0), (640.0, 640.0));
+        assert_eq!(transform.scale, MAX_ZOOM);
+        assert_eq!(transform.to_screen((48.0, 48.0)), (320.0, 320.0));
+    }
+
+    #[test]
+    fn ignores_points_outside_of_the_grid() {
+        assert_eq!(point_to_cell((-1.0, 10.0), 32.0, 20, 10), None);
+        assert_eq!(point_to_cell((10.0, -1.0), 32.0, 20, 10), None);
+        // The menu to the right of the grid
+        assert_eq!(point_to_cell((641.0, 10.0), 32.0, 20, 10), None);
+        assert_eq!(point_to_cell((700.0, 10.0), 32.0, 20, 10), None);
+        // Below the grid that is not as high as the field
+        assert_eq!(point_to_cell((10.0, 321.0), 32.0, 20, 10), None);
+    }
+}
@@ -0,0 +1,83 @@
+// Allocation tests: generations of the game are made without allocating, once their buffers are there
+// The counting allocator replaces the allocator of this test binary only, so other tests run with the usual one
+use game_of_life::board::Board;
+use game_of_life::engine::Engine;
+use game_of_life::graph::PopulationGraph;
+use game_of_life::history::{self, History};
+use game_of_life::stability::{Detector, PeriodFinder};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// Allocator counting allocations of every thread, so tests can check that steps allocate nothing
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Creates a randomly filled board computed by the dense engine without threads
+fn random_board() -> Board {
+    let mut board = Board::new(64, 64);
+    board.randomize(0.3, &mut StdRng::seed_from_u64(5));
+    board.set_engine(Some(Engine::Dense));
+    board.set_parallel(Some(false));
+    board
+}
+
+#[test]
+fn dense_steps_allocate_nothing() {
+    let mut board = random_board();
+    // The buffer of the next generation is allocated by the first step
+    board.step();
+    let allocations = ALLOCATIONS.with(|count| count.get());
+    for _ in 0..20 {
+        board.step();
+    }
+    assert_eq!(ALLOCATIONS.with(|count| count.get()), allocations);
+    assert!(board.population() > 0);
+}
+
+#[test]
+fn game_steps_allocate_nothing() {
+    // A generation of the game also keeps the cells to step back to, the population for the graph
+    // and checks if the field has settled down or repeats itself
+    let mut board = random_board();
+    let mut generations = History::new(10);
+    let mut detector = Detector::new(true);
+    let mut periods = PeriodFinder::new();
+    let mut graph = PopulationGraph::new(100);
+    let mut before = Vec::new();
+    let mut step = || {
+        history::copy_into(&mut before, board.cells());
+        generations.push(&before);
+        board.step();
+        graph.push(board.population());
+        periods.check(&before, board.cells());
+        detector.check(&before, &board);
+    };
+    // Buffers are allocated until the history is full
+    for _ in 0..10 {
+        step();
+    }
+    let allocations = ALLOCATIONS.with(|count| count.get());
+    for _ in 0..20 {
+        step();
+    }
+    assert_eq!(ALLOCATIONS.with(|count| count.get()), allocations);
+}
@@ -0,0 +1,95 @@
+// Golden tests: known patterns are run for many generations on a large bounded board, and their cells
+// are compared with the ones they are known to end up with
+// A board of 640x640 cells keeps gliders of the R-pentomino away from its edges until it stabilizes
+use game_of_life::board::Board;
+use game_of_life::engine::Engine;
+use game_of_life::pattern_io::rle;
+
+// Size of the board and the cell the upper left corner of every pattern is put at
+const SIZE: i32 = 640;
+const ORIGIN: (i32, i32) = (320, 320);
+
+// Engines that are quick enough for a board this large (the dense one is checked against them in unit tests)
+const ENGINES: [Engine; 2] = [Engine::Sparse, Engine::Bitwise];
+
+const GLIDER: &str = "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!";
+const R_PENTOMINO: &str = "x = 3, y = 3, rule = B3/S23\nb2o$2ob$bo!";
+const DIEHARD: &str = "x = 8, y = 3, rule = B3/S23\n6bob$2o6b$bo3b3o!";
+
+// The R-pentomino stabilizes at generation 1103 with 116 cells (6 gliders flying away among them)
+const R_PENTOMINO_END: u32 = 1103;
+const R_PENTOMINO_CELLS: usize = 116;
+// Cells of the R-pentomino at generation 1103 with the upper left corner of their bounding box
+// relative to the upper left corner of the R-pentomino
+const R_PENTOMINO_CORNER: (i32, i32) = (-240, -258);
+const R_PENTOMINO_RESULT: &str = "\
+x = 501, y = 525, rule = B3/S23
+479b2o$478bobo$480bo28$bo$2o$obo117$180b2o$180bobo$180bo90$265b2o$264b
+o2bo$265bobo$266bo$235bo$234bobo3b2o$235b2o3b2o3$284b3o$271b3o2$293b2o
+$293b2o5$201b2o$200bobo$200b2o31bo$232bobo$232bobo$233bo6bo$219b2o18bo
+bo$219b2o18bobo$240bo2$211b2o$211b2o56b2o$269b2o2$241b3o51b2o$295b2o4$
+307bo$306bobo$283b2o21bobo$283b2o22bo3$295b3o$265bo$264bobo$264bobo$
+265bo2$249b2o$249b2o208$473bo$474b2o$473b2o7$499bo$500bo$498b3o12$454b
+o$455b2o$454b2o!";
+
+// Diehard vanishes at generation 130
+const DIEHARD_END: u32 = 130;
+
+// Creates a board with the pattern of the RLE at the origin
+fn board_with(text: &str, engine: Engine) -> Board {
+    let pattern = rle::parse(text).unwrap();
+    let mut board = Board::new(SIZE, SIZE);
+    board.set_engine(Some(engine));
+    board.stamp(ORIGIN.0, ORIGIN.1, &pattern.cells);
+    board
+}
+
+// Alive cells of the RLE with the upper left corner at the given cell relative to the origin, row by row
+fn cells_of(text: &str, corner: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut cells: Vec<(i32, i32)> = rle::parse(text).unwrap().cells.into_iter()
+        .map(|(x, y)| (ORIGIN.0 + corner.0 + x, ORIGIN.1 + corner.1 + y))
+        .collect();
+    cells.sort_by_key(|(x, y)| (*y, *x));
+    cells
+}
+
+// Function makes the given number of generations
+fn run(board: &mut Board, generations: u32) {
+    for _ in 0..generations {
+        board.step();
+    }
+}
+
+#[test]
+fn glider_flies_diagonally() {
+    for engine in ENGINES {
+        let mut board = board_with(GLIDER, engine);
+        // A glider moves by a cell down and to the right every 4 generations
+        run(&mut board, 200);
+        assert_eq!(board.alive_cells(), cells_of(GLIDER, (50, 50)));
+        assert_eq!(board.bounding_box(), Some(((ORIGIN.0 + 50, ORIGIN.1 + 50), (ORIGIN.0 + 52, ORIGIN.1 + 52))));
+    }
+}
+
+#[test]
+fn r_pentomino_stabilizes_at_1103() {
+    for engine in ENGINES {
+        let mut board = board_with(R_PENTOMINO, engine);
+        run(&mut board, R_PENTOMINO_END - 1);
+        assert_ne!(board.population(), R_PENTOMINO_CELLS);
+        run(&mut board, 1);
+        assert_eq!(board.population(), R_PENTOMINO_CELLS);
+        assert_eq!(board.alive_cells(), cells_of(R_PENTOMINO_RESULT, R_PENTOMINO_CORNER));
+    }
+}
+
+#[test]
+fn diehard_vanishes_at_130() {
+    for engine in ENGINES {
+        let mut board = board_with(DIEHARD, engine);
+        run(&mut board, DIEHARD_END - 1);
+        assert!(board.population() > 0);
+        run(&mut board, 1);
+        assert_eq!(board.population(), 0);
+    }
+}